@@ -0,0 +1,37 @@
+#[cfg(feature = "fs")]
+use keyv::{adapter::fs::FsStoreBuilder, Keyv};
+
+#[cfg(feature = "fs")]
+#[tokio::main]
+async fn main() {
+    let dir = std::env::temp_dir().join("keyv-example");
+    let store = FsStoreBuilder::new()
+        .path(&dir) // Created if it does not exist
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("number", 42).await.unwrap();
+    keyv.set_with_ttl("session", "expires soon", 60)
+        .await
+        .unwrap();
+
+    match keyv.get("number").await.unwrap() {
+        Some(number) => {
+            let number: i32 = serde_json::from_value(number).unwrap(); // Deserialize
+            println!("number: {}", number);
+        }
+        None => println!("number not found"),
+    }
+
+    println!("entries are stored under {}", dir.display());
+}
+
+#[cfg(not(feature = "fs"))]
+fn main() {
+    println!("This example requires the 'fs' feature to be enabled.");
+    println!("Please run the command as follows:");
+    println!("cargo run --example fs --features fs");
+}
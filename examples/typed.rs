@@ -0,0 +1,28 @@
+use keyv::Keyv;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct User {
+    name: String,
+    age: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let keyv = Keyv::default();
+    let user = User {
+        name: "Alice".to_string(),
+        age: 30,
+    };
+    keyv.set("user:1", &user).await.unwrap();
+
+    match keyv.get_as::<User>("user:1").await.unwrap() {
+        Some(user) => println!("user: {:?}", user),
+        None => println!("user not found"),
+    }
+
+    match keyv.get_as::<User>("user:2").await.unwrap() {
+        Some(user) => println!("user: {:?}", user),
+        None => println!("user:2 not found"),
+    }
+}
@@ -0,0 +1,86 @@
+use std::{fmt, sync::Arc};
+
+use crate::store::StoreError;
+
+/// What bulk reads do with a stored value that cannot be decoded.
+///
+/// Applies to `Keyv::get_many`, `Keyv::get_many_as`, `Keyv::iter` and
+/// `Keyv::scan_prefix`. Reads of a single key always fail on a value they cannot decode.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{DecodePolicy, Keyv, StoreError};
+/// # use std::sync::{Arc, Mutex};
+/// let corrupt = Arc::new(Mutex::new(Vec::new()));
+/// let report = corrupt.clone();
+/// let keyv = Keyv::default().with_decode_policy(DecodePolicy::SkipWith(Arc::new(
+///     move |e: &StoreError| {
+///         if let StoreError::DeserializationError { key: Some(key), .. } = e {
+///             report.lock().unwrap().push(key.clone());
+///         }
+///     },
+/// )));
+/// ```
+#[derive(Clone, Default)]
+pub enum DecodePolicy {
+    /// Fails the whole read with `StoreError::DeserializationError` on the first value
+    /// that cannot be decoded.
+    #[default]
+    FailFast,
+    /// Leaves out values that cannot be decoded, as if their keys were missing, and logs
+    /// each skipped key at `warn`.
+    Skip,
+    /// Skips like `Skip` and also passes the error of each skipped value, a
+    /// `StoreError::DeserializationError` naming its key, to the callback.
+    SkipWith(Arc<dyn Fn(&StoreError) + Send + Sync>),
+}
+
+impl DecodePolicy {
+    /// Returns `Ok(())` if `error` is skipped under this policy and the error otherwise.
+    pub(crate) fn skip(&self, error: StoreError) -> Result<(), StoreError> {
+        if !matches!(error, StoreError::DeserializationError { .. }) {
+            return Err(error);
+        }
+        match self {
+            DecodePolicy::FailFast => Err(error),
+            DecodePolicy::Skip => {
+                log::warn!(
+                    "Skipped a value that cannot be decoded: {}",
+                    describe(&error)
+                );
+                Ok(())
+            }
+            DecodePolicy::SkipWith(report) => {
+                log::warn!(
+                    "Skipped a value that cannot be decoded: {}",
+                    describe(&error)
+                );
+                report(&error);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn is_fail_fast(&self) -> bool {
+        matches!(self, DecodePolicy::FailFast)
+    }
+}
+
+/// Formats `error` together with its source, which holds the actual decoding failure.
+fn describe(error: &StoreError) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+impl fmt::Debug for DecodePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodePolicy::FailFast => f.write_str("FailFast"),
+            DecodePolicy::Skip => f.write_str("Skip"),
+            DecodePolicy::SkipWith(_) => f.write_str("SkipWith(..)"),
+        }
+    }
+}
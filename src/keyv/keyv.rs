@@ -1,11 +1,36 @@
-use std::sync::Arc;
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
 
-use serde::Serialize;
-use serde_json::{json, Value};
+use futures::{future, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
 
-use crate::{adapter::inmemory::InMemoryStore, store::Store};
+use crate::{
+    adapter::{
+        events::{EventStore, StoreEvent},
+        inmemory::InMemoryStore,
+        metrics::{Metrics, MetricsStore},
+        namespaced::NamespacedStore,
+        readonly::ReadOnlyStore,
+        retrying::RetryingStore,
+        timeout::TimeoutStore,
+        validated::ValidatedStore,
+        verified::VerifiedStore,
+    },
+    store::{
+        split_found, utils::to_value, BatchOp, EntryMeta, EntryStream, Store, StoreError, StoreExt,
+        Ttl,
+    },
+};
 
-use super::KeyvError;
+#[cfg(feature = "compression")]
+use crate::adapter::compressed::{CompressedStore, Compression};
+#[cfg(feature = "encryption")]
+use crate::adapter::encrypted::EncryptedStore;
+#[cfg(feature = "key-hashing")]
+use crate::adapter::hashed::{HashAlgo, HashedStore};
+
+use super::{parse_u64_key, u64_key, DecodePolicy, KeyvError};
 
 /// Async Key-Value Store Interface
 ///
@@ -16,6 +41,20 @@ use super::KeyvError;
 /// The `Keyv` struct is generic over any implementation of the `Store` trait,
 /// thus can be backed by various storage engines.
 ///
+/// With the `tracing` feature, every operation runs in a span named after it, with the
+/// `backend` store type and the key as fields. Values are never recorded.
+///
+/// Integers keep their full precision across the whole `i64` and `u64` range on every
+/// backend and serializer, since `serde_json` holds them as integers rather than `f64`.
+/// Integers beyond that range, such as a large `u128`, cannot be represented and are
+/// rejected with `StoreError::SerializationError`; store them as strings instead. Numbers
+/// written by other clients outside the 64-bit range are read back as `f64`.
+///
+/// `Keyv` is `Clone`, `Send` and `Sync`. The store is held behind an `Arc`, so a clone is
+/// cheap and shares the same backend, connection pool and event channel, which makes it
+/// suitable as shared application state or for moving into spawned tasks. Settings such as
+/// `with_default_ttl` applied to a clone afterwards do not affect the original.
+///
 /// # Examples
 ///
 /// ## Create a new instance with in-memory store
@@ -55,8 +94,31 @@ use super::KeyvError;
 ///
 /// # };
 /// ```
+///
+/// ## Share across tasks
+///
+/// ```
+/// # use keyv::Keyv;
+/// # async {
+/// let keyv = Keyv::default();
+/// let writer = keyv.clone();
+/// tokio::spawn(async move { writer.set("greeting", "hello").await.unwrap() })
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(keyv.get_as::<String>("greeting").await.unwrap().unwrap(), "hello");
+/// # };
+/// ```
+#[derive(Clone)]
 pub struct Keyv {
     store: Arc<dyn Store>,
+    default_ttl: Option<u64>,
+    decode_policy: DecodePolicy,
+    /// Layer reporting the changes made through this `Keyv`, shared with namespace views.
+    events: Arc<EventStore>,
+    /// Type name of the store passed to `try_new`, recorded on tracing spans.
+    #[cfg(feature = "tracing")]
+    backend: &'static str,
 }
 
 impl Keyv {
@@ -103,118 +165,348 @@ impl Keyv {
     /// ```
     pub async fn try_new<S: Store + 'static>(store: S) -> Result<Self, KeyvError> {
         store.initialize().await?;
+        let events = Arc::new(EventStore::new(Arc::new(store)));
         Ok(Self {
-            store: Arc::new(store),
+            store: events.clone(),
+            default_ttl: None,
+            decode_policy: DecodePolicy::default(),
+            events,
+            #[cfg(feature = "tracing")]
+            backend: backend_name::<S>(),
         })
     }
 
-    /// Sets a value for a given key without a TTL.
+    /// Sets a default TTL (Time-To-Live) applied to every write that does not specify one.
+    ///
+    /// Calls such as `set` and `set_many` will pass this TTL to the store, while
+    /// `set_with_ttl` still uses its own TTL. The duration is rounded up to whole seconds,
+    /// so a sub-second TTL keeps values for one second instead of none. Without a
+    /// default, or with `Duration::ZERO`, values written without an explicit TTL never
+    /// expire.
+    ///
+    /// The TTL is forwarded to the underlying `Store`, so expiry follows the semantics
+    /// of the configured adapter. In particular it takes precedence over an adapter-level
+    /// default such as `RedisStoreBuilder::default_ttl`.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key under which the value is stored.
-    /// * `value` - The value to store. Must implement `Serialize`.
+    /// * `ttl` - The default time-to-live for new values.
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// Returns `KeyvError` if the operation fails.
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let keyv = Keyv::default().with_default_ttl(Duration::from_secs(3600));
+    /// keyv.set("session", "data").await.unwrap(); // Expires in 1 hour
+    /// # };
+    /// ```
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = (!ttl.is_zero()).then(|| Ttl::from(ttl).as_secs_ceil());
+        self
+    }
+
+    /// Compresses large values before they reach the store.
+    ///
+    /// Wraps the current store in a `CompressedStore` with its default threshold. Values
+    /// written before compression was enabled still read correctly.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The algorithm used for new values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, adapter::compressed::Compression};
+    /// # async {
+    /// let keyv = Keyv::default().with_compression(Compression::Zstd);
+    /// keyv.set("document", "a large value").await.unwrap();
+    /// # };
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.store = Arc::new(CompressedStore::new(self.store, compression));
+        self
+    }
+
+    /// Encrypts values before they reach the store.
+    ///
+    /// Wraps the current store in an `EncryptedStore`. Values must then have been written
+    /// with the same key; anything else fails with `StoreError::DecryptionError`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The 256-bit ChaCha20-Poly1305 key.
     ///
     /// # Examples
     ///
     /// ```
     /// # use keyv::Keyv;
     /// # async {
-    /// let keyv = Keyv::default();
-    /// keyv.set("key", "hello world").await.unwrap();
+    /// let keyv = Keyv::default().with_encryption([7u8; 32]);
+    /// keyv.set("token", "secret").await.unwrap();
     /// # };
     /// ```
-    pub async fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), KeyvError> {
-        Ok(self.store.set(key, json!(value), None).await?)
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(mut self, key: [u8; 32]) -> Self {
+        self.store = Arc::new(EncryptedStore::new(self.store, key));
+        self
     }
 
-    /// Sets a value for a given key with an expiry TTL (Time-To-Live).
+    /// Stores every entry under a fixed-length hash of its key.
+    ///
+    /// Wraps the current store in a `HashedStore`, which keeps backend indexes small when
+    /// keys are long, such as URLs. `get`, `set`, `remove` and the other calls hash keys
+    /// transparently, and the original key is stored with each value, so `keys` still
+    /// lists the original keys and reads never return a value written under a different
+    /// key. Entries written before hashing was enabled are no longer found. Receivers from
+    /// `subscribe` on the returned `Keyv` see the original keys too, while those obtained
+    /// before this call see the hashes.
     ///
     /// # Arguments
     ///
-    /// * `key` - A string slice that holds the key.
-    /// * `value` - The value to be stored, which must implement `Serialize`.
-    /// * `ttl` - The time-to-live (in seconds) for the key-value pair.
+    /// * `algo` - The hash function applied to keys.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    /// ```
+    /// # use keyv::{Keyv, adapter::hashed::HashAlgo};
+    /// # async {
+    /// let keyv = Keyv::default().with_key_hashing(HashAlgo::Sha256);
+    /// keyv.set("https://example.com/a/very/long/path", "page").await.unwrap();
+    /// # };
+    /// ```
+    #[cfg(feature = "key-hashing")]
+    pub fn with_key_hashing(mut self, algo: HashAlgo) -> Self {
+        // The event layer below only sees hashes, so a new one takes over on top.
+        let hashed = Arc::new(HashedStore::new(self.store, algo));
+        self.events = Arc::new(EventStore::new(hashed));
+        self.store = self.events.clone();
+        self
+    }
+
+    /// Fails any store call that takes longer than `timeout`.
+    ///
+    /// Wraps the current store in a `TimeoutStore`, so a hung backend surfaces as
+    /// `StoreError::Timeout` instead of blocking the caller indefinitely. Calls have no
+    /// time limit unless this is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The longest a single store call may take.
     ///
     /// # Examples
     ///
     /// ```
     /// # use keyv::Keyv;
+    /// # use std::time::Duration;
     /// # async {
-    /// let keyv = Keyv::default();
-    /// keyv.set_with_ttl("temp_key", "temp_value", 3600).await.unwrap(); // Expires in 1 hour
+    /// let keyv = Keyv::default().with_timeout(Duration::from_secs(2));
+    /// keyv.set("number", 42).await.unwrap();
     /// # };
     /// ```
-    pub async fn set_with_ttl<T: Serialize>(
-        &self,
-        key: &str,
-        value: T,
-        ttl: u64,
-    ) -> Result<(), KeyvError> {
-        Ok(self.store.set(key, json!(value), Some(ttl)).await?)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.store = Arc::new(TimeoutStore::new(self.store, timeout));
+        self
     }
 
-    /// Retrieves a value based on a key.
+    /// Retries store calls that fail with a transient error, such as a deadlock or a
+    /// dropped connection.
+    ///
+    /// Wraps the current store in a `RetryingStore`. The delay before a retry starts at
+    /// `backoff`, doubles after each attempt and is randomized so that clients do not
+    /// retry in lockstep. Permanent errors, including `StoreError::Timeout`, are returned
+    /// at once.
     ///
     /// # Arguments
     ///
-    /// * `key` - A string slice that holds the key to retrieve the value for.
+    /// * `max_attempts` - How many times a call is attempted in all, including the first.
+    /// * `backoff` - The delay before the first retry.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// Returns an `Ok` result with `Option<Value>` on success, where `None` indicates the
-    /// key does not exist, or a `KeyvError` on failure.
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use std::time::Duration;
+    /// # async {
+    /// let keyv = Keyv::default().with_retry(3, Duration::from_millis(50));
+    /// keyv.set("number", 42).await.unwrap();
+    /// # };
+    /// ```
+    pub fn with_retry(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.store = Arc::new(
+            RetryingStore::new(self.store)
+                .with_max_attempts(max_attempts)
+                .with_backoff(backoff),
+        );
+        self
+    }
+
+    /// Reports the name, latency and outcome of every store call to `metrics`.
+    ///
+    /// Wraps the current store in a `MetricsStore`. Nothing is measured unless this is
+    /// set. See `Metrics` for the operation names that are reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - The receiver of the samples.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.store = Arc::new(MetricsStore::new(self.store, metrics));
+        self
+    }
+
+    /// Rejects keys longer than `bytes` with `StoreError::InvalidKey`.
+    ///
+    /// Wraps the current store in a `ValidatedStore`, so an oversized key fails at the
+    /// call site instead of deep inside the driver. Keys are not checked unless this is
+    /// set. The limit applies to the full key, including the prefix of a namespace view.
+    /// See `ValidatedStore` for the recommended limit of each backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The maximum length of a key, in bytes of UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, KeyvError, StoreError};
+    /// # async {
+    /// let keyv = Keyv::default().with_max_key_length(8);
+    /// assert!(matches!(
+    ///     keyv.set("a-rather-long-key", 1).await,
+    ///     Err(KeyvError::StoreError(StoreError::InvalidKey(_)))
+    /// ));
+    /// # };
+    /// ```
+    pub fn with_max_key_length(mut self, bytes: usize) -> Self {
+        self.store = Arc::new(ValidatedStore::new(self.store).max_key_length(bytes));
+        self
+    }
+
+    /// Rejects keys containing any of `chars` with `StoreError::InvalidKey`.
+    ///
+    /// Wraps the current store in a `ValidatedStore`. Keys are not checked unless this is
+    /// set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters keys may not contain.
     ///
     /// # Examples
     ///
     /// ```
     /// # use keyv::Keyv;
     /// # async {
-    /// let keyv = Keyv::default();
+    /// let keyv = Keyv::default().with_forbidden_key_chars([' ', '\n']);
+    /// assert!(keyv.set("no spaces", 1).await.is_err());
+    /// # };
+    /// ```
+    pub fn with_forbidden_key_chars<I: IntoIterator<Item = char>>(mut self, chars: I) -> Self {
+        self.store = Arc::new(ValidatedStore::new(self.store).forbidden_chars(chars));
+        self
+    }
+
+    /// Rejects values larger than `bytes` with `StoreError::ValueTooLarge`.
     ///
-    /// keyv.set("array", vec!["hola", "test"]).await.unwrap();
+    /// Wraps the current store in a `ValidatedStore`, which measures each value once it
+    /// is serialized and fails before anything is sent to the store. Values are not
+    /// limited unless this is set. Wrappers added afterwards sit in front of the check,
+    /// so call this before `with_compression` or `with_encryption` to limit the size that
+    /// is actually stored.
     ///
-    /// match keyv.get("array").await.unwrap() {
-    ///     Some(array) => {
-    ///         let array: Vec<String> = serde_json::from_value(array).unwrap();
-    ///         assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
-    ///     }
-    ///     None => assert!(false),
-    /// }
+    /// # Arguments
     ///
-    /// keyv.set("string", "life long").await.unwrap();
-    /// match keyv.get("string").await.unwrap() {
-    ///     Some(string) => {
-    ///         let string: String = serde_json::from_value(string).unwrap();
-    ///         assert_eq!(string, "life long");
-    ///     }
-    ///     None => assert!(false),
-    /// }
+    /// * `bytes` - The maximum size of a value, in bytes.
+    ///
+    /// # Examples
     ///
+    /// ```
+    /// # use keyv::{Keyv, KeyvError, StoreError};
+    /// # async {
+    /// let keyv = Keyv::default().with_max_value_bytes(16);
+    /// keyv.set("small", "ok").await.unwrap();
+    /// assert!(matches!(
+    ///     keyv.set("large", "far more than sixteen bytes").await,
+    ///     Err(KeyvError::StoreError(StoreError::ValueTooLarge { limit: 16, .. }))
+    /// ));
     /// # };
     /// ```
-    pub async fn get(&self, key: &str) -> Result<Option<Value>, KeyvError> {
-        Ok(self.store.get(key).await?)
+    pub fn with_max_value_bytes(mut self, bytes: usize) -> Self {
+        self.store = Arc::new(ValidatedStore::new(self.store).max_value_bytes(bytes));
+        self
     }
 
-    /// Removes a specified key from the store.
+    /// Reads every value back right after writing it and checks it is unchanged.
+    ///
+    /// A value that does not survive the round trip fails the write with
+    /// `StoreError::IntegrityError`. Each write then costs an extra read, so this is meant
+    /// for development and debugging; when `enabled` is `false` the store is left as it
+    /// is and writes pay nothing. Call it after `with_compression` and `with_encryption`
+    /// so that the check covers their encoding too. See `VerifiedStore`.
     ///
     /// # Arguments
     ///
-    /// * `key` - A string slice that represents the key to be removed.
+    /// * `enabled` - Whether to verify writes.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// Returns an `Ok` result if the key has been successfully removed, or a `KeyvError`
-    /// on failure.
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default().with_verify_writes(cfg!(debug_assertions));
+    /// keyv.set("id", u64::MAX).await.unwrap();
+    /// # };
+    /// ```
+    pub fn with_verify_writes(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.store = Arc::new(VerifiedStore::new(self.store));
+        }
+        self
+    }
+
+    /// Sets what bulk reads do with stored values that cannot be decoded.
+    ///
+    /// By default, `get_many`, `get_many_as`, `iter` and `scan_prefix` fail on the first
+    /// such value. With `DecodePolicy::Skip` they leave it out and carry on, so a few
+    /// corrupt or legacy entries do not make the rest unreadable; use
+    /// `DecodePolicy::SkipWith` to collect the skipped keys for repair. When a store
+    /// fails a whole `get_many` on one value, the keys are read again one at a time to
+    /// find it.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy for values that cannot be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{DecodePolicy, Keyv};
+    /// # async {
+    /// let keyv = Keyv::default().with_decode_policy(DecodePolicy::Skip);
+    /// keyv.set("key1", "value1").await.unwrap();
+    /// keyv.set("key2", "value2").await.unwrap();
+    ///
+    /// // Values that are not numbers are left out instead of failing the read.
+    /// let numbers = keyv.get_many_as::<i64, _>(&["key1", "key2"]).await.unwrap();
+    /// assert!(numbers.is_empty());
+    /// # };
+    /// ```
+    pub fn with_decode_policy(mut self, policy: DecodePolicy) -> Self {
+        self.decode_policy = policy;
+        self
+    }
+
+    /// Returns a view of this `Keyv` whose keys all live under `"{name}:"`.
+    ///
+    /// The view shares the store and default TTL of `self`. Every operation on it, including
+    /// `keys`, `len` and `clear`, only sees the keys of its namespace. Namespaces nest, so
+    /// `keyv.namespace("a").namespace("b")` stores its keys under `"a:b:"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The namespace, without the trailing `:`.
     ///
     /// # Examples
     ///
@@ -222,23 +514,126 @@ impl Keyv {
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    /// keyv.remove("my_key").await.unwrap(); // Removes "my_key" from the store
+    /// let sessions = keyv.namespace("session");
+    ///
+    /// sessions.set("42", "alice").await.unwrap();
+    /// assert_eq!(keyv.get("session:42").await.unwrap().unwrap(), "alice");
+    ///
+    /// sessions.clear().await.unwrap();
+    /// assert!(!keyv.has("session:42").await.unwrap());
     /// # };
     /// ```
-    pub async fn remove(&self, key: &str) -> Result<(), KeyvError> {
-        Ok(self.store.remove(key).await?)
+    pub fn namespace<S: Into<String>>(&self, name: S) -> Self {
+        Self {
+            store: Arc::new(NamespacedStore::new(self.store.clone(), name)),
+            default_ttl: self.default_ttl,
+            decode_policy: self.decode_policy.clone(),
+            events: self.events.clone(),
+            #[cfg(feature = "tracing")]
+            backend: self.backend,
+        }
     }
 
-    /// Removes multiple keys from the store in one operation.
+    /// Returns a view of this `Keyv` that can only read.
+    ///
+    /// The view shares the store of `self`, wrapped in a `ReadOnlyStore`. Reads such as
+    /// `get`, `keys` and `len` work as usual, while every write, removal or clear fails
+    /// with `StoreError::ReadOnly` without reaching the store. `self` can still write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, KeyvError, StoreError};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user", "alice").await.unwrap();
+    ///
+    /// let reader = keyv.read_only();
+    /// assert_eq!(reader.get("user").await.unwrap().unwrap(), "alice");
+    /// assert!(matches!(
+    ///     reader.remove("user").await,
+    ///     Err(KeyvError::StoreError(StoreError::ReadOnly))
+    /// ));
+    /// # };
+    /// ```
+    pub fn read_only(&self) -> Self {
+        Self {
+            store: Arc::new(ReadOnlyStore::new(self.store.clone())),
+            default_ttl: self.default_ttl,
+            decode_policy: self.decode_policy.clone(),
+            events: self.events.clone(),
+            #[cfg(feature = "tracing")]
+            backend: self.backend,
+        }
+    }
+
+    /// Returns a receiver for the changes made through this `Keyv` from now on.
+    ///
+    /// Nothing is broadcast until the first call, so instances without subscribers pay
+    /// no cost. Each write, removal or clear that succeeds sends a `StoreEvent` carrying
+    /// the key as stored, so events from a namespace view include its prefix and reach
+    /// the subscribers of the parent as well. Changes made by other processes, or keys
+    /// expiring, are not reported; to keep several instances in sync, forward the events
+    /// over a channel they all listen to.
+    ///
+    /// A receiver that falls more than 1024 events behind gets `RecvError::Lagged` and
+    /// skips the oldest ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, adapter::events::StoreEvent};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let mut events = keyv.subscribe();
+    ///
+    /// keyv.set("user", "alice").await.unwrap();
+    /// keyv.remove("user").await.unwrap();
+    ///
+    /// assert_eq!(events.recv().await.unwrap(), StoreEvent::Set { key: "user".into() });
+    /// assert_eq!(events.recv().await.unwrap(), StoreEvent::Removed { key: "user".into() });
+    /// # };
+    /// ```
+    pub fn subscribe(&self) -> broadcast::Receiver<StoreEvent> {
+        self.events.subscribe()
+    }
+
+    /// Sends `event` to every subscriber, as if the change had been made through this
+    /// instance.
+    ///
+    /// Use it to forward changes made outside this process, for example by connecting a
+    /// Redis `KeyspaceListener` so that local caches are invalidated when another service
+    /// writes to the same keys. Namespace views share one channel with the instance they
+    /// come from, so the event should name the full key, as the events of views do.
     ///
     /// # Arguments
     ///
-    /// * `keys` - A slice of strings or string-like objects that represent the keys to be removed.
+    /// * `event` - The change to report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, adapter::events::StoreEvent};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let mut events = keyv.subscribe();
+    ///
+    /// keyv.publish(StoreEvent::Removed { key: "user".into() });
+    /// assert_eq!(events.recv().await.unwrap(), StoreEvent::Removed { key: "user".into() });
+    /// # };
+    /// ```
+    pub fn publish(&self, event: StoreEvent) {
+        self.events.publish(event);
+    }
+
+    /// Checks that the underlying store is reachable, without writing any data.
+    ///
+    /// Useful for readiness probes: SQL stores run `SELECT 1`, Redis answers `PING` and
+    /// MongoDB runs the `ping` command.
     ///
     /// # Returns
     ///
-    /// Returns an `Ok` result if the keys have been successfully removed, or a `KeyvError`
-    /// on failure.
+    /// Returns `Ok(())` if the store answered, or a `KeyvError` if it could not be reached.
     ///
     /// # Examples
     ///
@@ -246,20 +641,27 @@ impl Keyv {
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    /// keyv.remove_many(&["key1", "key2"]).await.unwrap(); // Removes "key1" and "key2"
+    /// keyv.ping().await.unwrap();
     /// # };
     /// ```
-    pub async fn remove_many<T: AsRef<str> + Sync>(&self, keys: &[T]) -> Result<(), KeyvError> {
-        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-        Ok(self.store.remove_many(&keys).await?)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ping", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn ping(&self) -> Result<(), KeyvError> {
+        Ok(self.store.health_check().await?)
     }
 
-    /// Clears the entire store, removing all key-value pairs.
+    /// Sets a value for a given key, using the default TTL if one is configured.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// Returns an `Ok` result if the store has been successfully cleared, or a `KeyvError`
-    /// on failure.
+    /// * `key` - The key under which the value is stored.
+    /// * `value` - The value to store. Must implement `Serialize`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyvError` if the operation fails.
     ///
     /// # Examples
     ///
@@ -267,18 +669,1756 @@ impl Keyv {
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    /// keyv.clear().await.unwrap(); // Clears the entire store
+    /// keyv.set("key", "hello world").await.unwrap();
     /// # };
     /// ```
-    pub async fn clear(&self) -> Result<(), KeyvError> {
-        Ok(self.store.clear().await?)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), KeyvError> {
+        Ok(self
+            .store
+            .set(key, to_value(key, value)?, self.default_ttl)
+            .await?)
     }
-}
 
-impl Default for Keyv {
-    fn default() -> Self {
-        Self {
-            store: Arc::new(InMemoryStore::new()),
-        }
-    }
+    /// Sets a value for a given key with an expiry TTL (Time-To-Live).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `ttl` - The time-to-live for the key-value pair. A plain integer is taken as
+    ///   seconds; use `Ttl::millis` for a TTL in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, Ttl};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_with_ttl("temp_key", "temp_value", 3600).await.unwrap(); // Expires in 1 hour
+    /// keyv.set_with_ttl("lock", "owner", Ttl::millis(500)).await.unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_with_ttl", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn set_with_ttl<T: Serialize, D: Into<Ttl>>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: D,
+    ) -> Result<(), KeyvError> {
+        Ok(self
+            .store
+            .set_with_ttl(key, to_value(key, value)?, ttl.into())
+            .await?)
+    }
+
+    /// Sets multiple key-value pairs in one operation, using the default TTL if one is
+    /// configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `(key, value)` pairs. Values must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_many(&[("key1", "value1"), ("key2", "value2")]).await.unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_many", skip_all, fields(backend = self.backend, count = entries.len()), err)
+    )]
+    pub async fn set_many<K: AsRef<str> + Sync, T: Serialize + Sync>(
+        &self,
+        entries: &[(K, T)],
+    ) -> Result<(), KeyvError> {
+        let entries = entries
+            .iter()
+            .map(|(k, v)| Ok((k.as_ref(), to_value(k.as_ref(), v)?, self.default_ttl)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        Ok(self.store.set_many(&entries).await?)
+    }
+
+    /// Writes multiple key-value pairs as one group that expires together.
+    ///
+    /// The group is applied atomically where the store's `apply_batch` is: SQL stores use
+    /// a transaction and redis `MULTI`/`EXEC`. See `Store::set_group` for the exact
+    /// guarantees of each backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `(key, value)` pairs. Values must implement `Serialize`.
+    /// * `ttl` - The time-to-live in seconds shared by every entry, or `None` for the
+    ///   default TTL.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result once the whole group has been written, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_group(&[("session:1:user", "alice"), ("session:1:role", "admin")], Some(3600))
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_group", skip_all, fields(backend = self.backend, count = entries.len()), err)
+    )]
+    pub async fn set_group<K: AsRef<str> + Sync, T: Serialize + Sync>(
+        &self,
+        entries: &[(K, T)],
+        ttl: Option<u64>,
+    ) -> Result<(), KeyvError> {
+        let entries = entries
+            .iter()
+            .map(|(k, v)| Ok((k.as_ref(), to_value(k.as_ref(), v)?)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        Ok(self
+            .store
+            .set_group(&entries, ttl.or(self.default_ttl))
+            .await?)
+    }
+
+    /// Stores raw bytes under a key, without encoding them as JSON.
+    ///
+    /// Redis and SQLite keep the bytes as-is; other stores fall back to a JSON array. Read
+    /// them back with `get_bytes`. The default TTL applies if one is configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The bytes to store.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the bytes were stored, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_bytes("thumbnail", &[0x89, 0x50, 0x4e, 0x47]).await.unwrap();
+    /// assert_eq!(
+    ///     keyv.get_bytes("thumbnail").await.unwrap(),
+    ///     Some(vec![0x89, 0x50, 0x4e, 0x47])
+    /// );
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_bytes", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn set_bytes(&self, key: &str, value: &[u8]) -> Result<(), KeyvError> {
+        Ok(self.store.set_bytes(key, value, self.default_ttl).await?)
+    }
+
+    /// Retrieves bytes stored with `set_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<Vec<u8>>` on success, where `None` indicates
+    /// the key does not exist, or a `KeyvError` on failure.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_bytes", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, KeyvError> {
+        Ok(self.store.get_bytes(key).await?)
+    }
+
+    /// Retrieves a value based on a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<Value>` on success, where `None` indicates the
+    /// key does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    ///
+    /// keyv.set("array", vec!["hola", "test"]).await.unwrap();
+    ///
+    /// match keyv.get("array").await.unwrap() {
+    ///     Some(array) => {
+    ///         let array: Vec<String> = serde_json::from_value(array).unwrap();
+    ///         assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
+    ///     }
+    ///     None => assert!(false),
+    /// }
+    ///
+    /// keyv.set("string", "life long").await.unwrap();
+    /// match keyv.get("string").await.unwrap() {
+    ///     Some(string) => {
+    ///         let string: String = serde_json::from_value(string).unwrap();
+    ///         assert_eq!(string, "life long");
+    ///     }
+    ///     None => assert!(false),
+    /// }
+    ///
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get(&self, key: &str) -> Result<Option<Value>, KeyvError> {
+        Ok(self.store.get(key).await?)
+    }
+
+    /// Retrieves the value for a key that must exist.
+    ///
+    /// Useful for configuration values, where a missing key is an error rather than an
+    /// expected case.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the value on success, `StoreError::KeyNotFound` if
+    /// the key does not exist, or a `KeyvError` on any other failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, KeyvError, StoreError};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("port", 8080).await.unwrap();
+    ///
+    /// assert_eq!(keyv.get_required("port").await.unwrap(), 8080);
+    /// assert!(matches!(
+    ///     keyv.get_required("host").await,
+    ///     Err(KeyvError::StoreError(StoreError::KeyNotFound(_)))
+    /// ));
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_required", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get_required(&self, key: &str) -> Result<Value, KeyvError> {
+        Ok(self.store.get_required(key).await?)
+    }
+
+    /// Retrieves a value based on a key and deserializes it into `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<T>` on success, where `None` indicates the
+    /// key does not exist, or a `KeyvError` on failure. A stored value that cannot be
+    /// deserialized into `T` is reported as `StoreError::DeserializationError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    ///
+    /// keyv.set("array", vec!["hola", "test"]).await.unwrap();
+    ///
+    /// let array: Option<Vec<String>> = keyv.get_as("array").await.unwrap();
+    /// assert_eq!(array, Some(vec!["hola".to_string(), "test".to_string()]));
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_as", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, KeyvError> {
+        match self.store.get(key).await? {
+            Some(value) => Ok(Some(serde_json::from_value(value).map_err(|e| {
+                StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                }
+            })?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieves a value deserialized into `T`, or `default` if the key does not exist.
+    ///
+    /// Saves the `unwrap_or` on every read of a setting that has a fallback. Only a missing
+    /// key falls back: a stored value that cannot be deserialized into `T` is still
+    /// reported as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve.
+    /// * `default` - The value returned when the key does not exist.
+    ///
+    /// # Returns
+    ///
+    /// Returns the stored value or `default`, or a `KeyvError` on failure. A stored value
+    /// that cannot be deserialized into `T` is reported as
+    /// `StoreError::DeserializationError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// struct Limits {
+    ///     requests_per_minute: u32,
+    /// }
+    ///
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("config:timeout_secs", 5).await.unwrap();
+    ///
+    /// let timeout: u64 = keyv.get_or("config:timeout_secs", 30).await.unwrap();
+    /// assert_eq!(timeout, 5);
+    ///
+    /// let limits = keyv
+    ///     .get_or("config:limits", Limits { requests_per_minute: 60 })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(limits, Limits { requests_per_minute: 60 });
+    /// # };
+    /// ```
+    pub async fn get_or<T: DeserializeOwned>(&self, key: &str, default: T) -> Result<T, KeyvError> {
+        Ok(self.get_as(key).await?.unwrap_or(default))
+    }
+
+    /// Retrieves a value deserialized into `T`, or `T::default()` if the key does not
+    /// exist.
+    ///
+    /// Like `get_or`, a stored value that cannot be deserialized into `T` is reported as an
+    /// error rather than replaced by the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns the stored value or `T::default()`, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    ///
+    /// let allowed: Vec<String> = keyv.get_or_default("config:allowed_hosts").await.unwrap();
+    /// assert!(allowed.is_empty());
+    /// # };
+    /// ```
+    pub async fn get_or_default<T: DeserializeOwned + Default>(
+        &self,
+        key: &str,
+    ) -> Result<T, KeyvError> {
+        Ok(self.get_as(key).await?.unwrap_or_default())
+    }
+
+    /// Retrieves a value together with when it was created and last updated.
+    ///
+    /// The timestamps are only recorded by SQL stores built with `timestamps(true)`;
+    /// other stores return an `EntryMeta` whose fields are all `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to look up.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some((Value, EntryMeta)))` if the key exists, `Ok(None)` if it does not,
+    /// or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("config", "v1").await.unwrap();
+    ///
+    /// let (value, meta) = keyv.get_with_meta("config").await.unwrap().unwrap();
+    /// assert_eq!(value, "v1");
+    /// assert_eq!(meta.created_at, None);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_with_meta", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, KeyvError> {
+        Ok(self.store.get_with_meta(key).await?)
+    }
+
+    /// Retrieves the values for multiple keys in one operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with one `Option<Value>` per key, in the same order as `keys`,
+    /// where `None` indicates the key does not exist, or a `KeyvError` on failure. Values
+    /// that cannot be decoded are reported as `None` if `with_decode_policy` says to skip
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("key1", "value1").await.unwrap();
+    ///
+    /// let values = keyv.get_many(&["key1", "key2"]).await.unwrap();
+    /// assert_eq!(values, vec![Some(serde_json::json!("value1")), None]);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_many", skip_all, fields(backend = self.backend, count = keys.len()), err)
+    )]
+    pub async fn get_many<T: AsRef<str> + Sync>(
+        &self,
+        keys: &[T],
+    ) -> Result<Vec<Option<Value>>, KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        Ok(self.fetch_many(&keys).await?)
+    }
+
+    /// Retrieves the values for multiple keys in one operation and tells which of them
+    /// were missing, for example to fetch only those from the origin when warming a cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with a map from each key that exists to its value and the
+    /// list of missing keys, in the order they were requested and each listed once, or a
+    /// `KeyvError` on failure. A value skipped by `with_decode_policy` counts as missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:2", "bob").await.unwrap();
+    ///
+    /// let (found, missing) = keyv
+    ///     .get_many_partial(&["user:1", "user:2", "user:3"])
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(found["user:2"], "bob");
+    /// assert_eq!(missing, vec!["user:1".to_string(), "user:3".to_string()]);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_many_partial", skip_all, fields(backend = self.backend, count = keys.len()), err)
+    )]
+    pub async fn get_many_partial<K: AsRef<str> + Sync>(
+        &self,
+        keys: &[K],
+    ) -> Result<(HashMap<String, Value>, Vec<String>), KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        let values = self.fetch_many(&keys).await?;
+        Ok(split_found(&keys, values))
+    }
+
+    /// Retrieves the values for multiple keys in one operation and deserializes each of
+    /// them into `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with a map from each key that exists to its value, or a
+    /// `KeyvError` on failure. Missing keys are left out of the map. A stored value that
+    /// cannot be deserialized into `T` fails the whole call with
+    /// `StoreError::DeserializationError` naming its key, unless `with_decode_policy` says
+    /// to skip it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", User { name: "alice".to_string(), age: 30 }).await.unwrap();
+    /// keyv.set("user:2", User { name: "bob".to_string(), age: 25 }).await.unwrap();
+    ///
+    /// let users = keyv
+    ///     .get_many_as::<User, _>(&["user:1", "user:2", "user:3"])
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(users.len(), 2);
+    /// assert_eq!(users["user:2"].name, "bob");
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_many_as", skip_all, fields(backend = self.backend, count = keys.len()), err)
+    )]
+    pub async fn get_many_as<T: DeserializeOwned, K: AsRef<str> + Sync>(
+        &self,
+        keys: &[K],
+    ) -> Result<HashMap<String, T>, KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        let values = self.fetch_many(&keys).await?;
+
+        let mut entries = HashMap::with_capacity(values.len());
+        for (key, value) in keys.into_iter().zip(values) {
+            let Some(value) = value else {
+                continue;
+            };
+            match serde_json::from_value(value) {
+                Ok(value) => {
+                    entries.insert(key.to_string(), value);
+                }
+                Err(e) => self.decode_policy.skip(StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })?,
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Reads `keys` with `Store::get_many`, applying the decode policy.
+    async fn fetch_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        match self.store.get_many(keys).await {
+            Err(StoreError::DeserializationError { .. }) if !self.decode_policy.is_fail_fast() => {
+                // The store cannot say which values did decode, so read them one at a time.
+                let mut values = Vec::with_capacity(keys.len());
+                for key in keys {
+                    values.push(match self.store.get(key).await {
+                        Ok(value) => value,
+                        Err(e) => {
+                            self.decode_policy.skip(e)?;
+                            None
+                        }
+                    });
+                }
+                Ok(values)
+            }
+            result => result,
+        }
+    }
+
+    /// Returns how long a key has left before it expires.
+    ///
+    /// `None` covers two cases: the key does not exist, or it exists without a TTL. Call
+    /// `has` when the difference matters.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to inspect.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the remaining seconds, rounded up, or `None` as described
+    /// above, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    /// keyv.set("forever", "data").await.unwrap();
+    ///
+    /// assert_eq!(keyv.ttl("session").await.unwrap(), Some(60));
+    /// assert_eq!(keyv.ttl("forever").await.unwrap(), None); // No expiry
+    /// assert_eq!(keyv.ttl("missing").await.unwrap(), None); // No key
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ttl", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn ttl(&self, key: &str) -> Result<Option<u64>, KeyvError> {
+        Ok(self.store.get_ttl(key).await?)
+    }
+
+    /// Checks whether a key exists in the store without fetching its value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to look up.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the key exists, `Ok(false)` if it does not, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("key", "hello world").await.unwrap();
+    /// assert!(keyv.has("key").await.unwrap());
+    /// assert!(!keyv.has("missing").await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "has", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn has(&self, key: &str) -> Result<bool, KeyvError> {
+        Ok(self.store.contains_key(key).await?)
+    }
+
+    /// Sets `new` for `key` only if the current value equals `expected`.
+    ///
+    /// Passing `None` as `expected` means the key must not exist. This is the building
+    /// block for optimistic locking.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `expected` - The value the key must currently hold, or `None` if it must be absent.
+    /// * `new` - The value to store when the comparison succeeds.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the value was swapped, `Ok(false)` if the current value did
+    /// not match, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert!(keyv.compare_and_swap("version", None::<u32>, 1).await.unwrap());
+    /// assert!(!keyv.compare_and_swap("version", Some(5), 6).await.unwrap());
+    /// assert!(keyv.compare_and_swap("version", Some(1), 2).await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "compare_and_swap", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn compare_and_swap<T: Serialize>(
+        &self,
+        key: &str,
+        expected: Option<T>,
+        new: T,
+    ) -> Result<bool, KeyvError> {
+        Ok(self
+            .store
+            .compare_and_swap(
+                key,
+                expected.map(|v| to_value(key, v)).transpose()?,
+                to_value(key, new)?,
+                self.default_ttl,
+            )
+            .await?)
+    }
+
+    /// Sets a value for a given key and returns the value it replaces.
+    ///
+    /// The write uses the default TTL if one is configured. Stores with a native
+    /// operation, such as Redis `SET ... GET`, read and write in one atomic step, so no
+    /// concurrent write can slip in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to store, which must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(Value))` with the previous value, `Ok(None)` if the key did not
+    /// exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert_eq!(keyv.swap("config", "v1").await.unwrap(), None);
+    /// assert_eq!(keyv.swap("config", "v2").await.unwrap().unwrap(), "v1");
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "swap", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn swap<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+    ) -> Result<Option<Value>, KeyvError> {
+        Ok(self
+            .store
+            .swap(key, to_value(key, value)?, self.default_ttl)
+            .await?)
+    }
+
+    /// Sets a value only if the key does not exist yet.
+    ///
+    /// This is the usual primitive for distributed locks: the caller that manages to set
+    /// the key holds the lock until it removes the key or the TTL runs out.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to store, which must implement `Serialize`.
+    /// * `ttl` - The time-to-live in seconds, or `None` to use the default TTL if one is
+    ///   configured.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the value was written, `Ok(false)` if the key already exists,
+    /// or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert!(keyv.set_if_absent("lock", "worker-1", Some(30)).await.unwrap());
+    /// assert!(!keyv.set_if_absent("lock", "worker-2", Some(30)).await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_if_absent", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn set_if_absent<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: Option<u64>,
+    ) -> Result<bool, KeyvError> {
+        Ok(self
+            .store
+            .set_nx(key, to_value(key, value)?, ttl.or(self.default_ttl))
+            .await?)
+    }
+
+    /// Returns the value for `key`, computing and storing it with `f` if it is missing.
+    ///
+    /// `f` only runs on a miss. Concurrent callers that miss together may each run `f`;
+    /// the first one to write wins and the others return its value, provided the store
+    /// implements `set_nx` atomically. See `StoreExt::get_or_insert_with`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `ttl` - The time-to-live in seconds of a newly stored value, or `None` to use the
+    ///   default TTL if one is configured.
+    /// * `f` - Produces the value to store, which must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Value)` with the existing or newly stored value, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let user = keyv
+    ///     .get_or_insert_with("user:1", Some(60), || async { "Alice" })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(user, "Alice");
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_or_insert_with", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn get_or_insert_with<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        f: F,
+    ) -> Result<Value, KeyvError>
+    where
+        T: Serialize,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = T> + Send,
+    {
+        // Follows `StoreExt::get_or_insert_with`, serializing the computed value before it
+        // is written so that a value JSON cannot hold fails instead of panicking.
+        if let Some(value) = self.store.get(key).await? {
+            return Ok(value);
+        }
+
+        let value = to_value(key, f().await)?;
+        if self
+            .store
+            .set_nx(key, value.clone(), ttl.or(self.default_ttl))
+            .await?
+        {
+            return Ok(value);
+        }
+        Ok(self.store.get(key).await?.unwrap_or(value))
+    }
+
+    /// Atomically adds `delta` to the integer counter stored under `key`.
+    ///
+    /// A missing key is treated as `0`, so the first call creates the counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key of the counter.
+    /// * `delta` - The amount to add, which may be negative.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the new value of the counter, or a `KeyvError` on
+    /// failure, including when the stored value is not an integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert_eq!(keyv.increment("hits", 1).await.unwrap(), 1);
+    /// assert_eq!(keyv.increment("hits", 5).await.unwrap(), 6);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "increment", skip_all, fields(backend = self.backend, key = %key, delta), err)
+    )]
+    pub async fn increment(&self, key: &str, delta: i64) -> Result<i64, KeyvError> {
+        Ok(self.store.increment(key, delta).await?)
+    }
+
+    /// Updates the expiry of an existing key without rewriting its value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to update.
+    /// * `ttl` - The new time-to-live in seconds, or `None` to make the key permanent.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the key exists and was updated, `Ok(false)` if it does not
+    /// exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    ///
+    /// assert!(keyv.expire("session", Some(3600)).await.unwrap()); // Extends to 1 hour
+    /// assert!(keyv.expire("session", None).await.unwrap()); // Never expires
+    /// assert!(!keyv.expire("missing", Some(60)).await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "expire", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, KeyvError> {
+        Ok(self.store.expire(key, ttl).await?)
+    }
+
+    /// Pushes back the expiry of an existing key, for sessions that slide forward on use.
+    ///
+    /// The seconds are added to the time the key has left, rather than counted from now
+    /// as with `expire`. A key without expiry stays permanent. The new expiry is capped at
+    /// the largest one the backend can hold instead of overflowing, so extending by
+    /// `u64::MAX` leaves the key as good as permanent. The SQL stores, Redis and the
+    /// in-memory store update the expiry atomically; other stores read it and write it
+    /// back.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to extend.
+    /// * `by_secs` - The seconds to add to the key's expiry.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the key exists, `Ok(false)` if it does not exist or has
+    /// expired, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    ///
+    /// assert!(keyv.extend_ttl("session", 60).await.unwrap()); // About 2 minutes left
+    /// assert!(!keyv.extend_ttl("missing", 60).await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "extend_ttl", skip_all, fields(backend = self.backend, key = %key, by_secs), err)
+    )]
+    pub async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, KeyvError> {
+        Ok(self.store.extend_ttl(key, by_secs).await?)
+    }
+
+    /// Sets one field of the JSON object stored at `key`, creating the object if needed.
+    ///
+    /// This treats the value as a record of named fields, like a Redis hash. A new key is
+    /// written with the default TTL, if one is configured; an existing key keeps its
+    /// expiry. Redis, the SQL stores and the in-memory store update the object atomically,
+    /// so concurrent writes to different fields of one key are all kept. Other stores read
+    /// the object and write it back, and may lose a concurrent update of the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key of the object.
+    /// * `field` - The name of the field to set.
+    /// * `value` - The field's value, which must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success, or a `KeyvError` on failure. A stored value that is not
+    /// a JSON object is reported as `StoreError::NotAnObject`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_field("user:1", "name", "Alice").await.unwrap();
+    /// keyv.set_field("user:1", "age", 30).await.unwrap();
+    ///
+    /// assert_eq!(keyv.get_field("user:1", "name").await.unwrap().unwrap(), "Alice");
+    /// assert_eq!(
+    ///     keyv.get("user:1").await.unwrap().unwrap(),
+    ///     serde_json::json!({"name": "Alice", "age": 30})
+    /// );
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_field", skip_all, fields(backend = self.backend, key = %key, field = %field), err)
+    )]
+    pub async fn set_field<T: Serialize>(
+        &self,
+        key: &str,
+        field: &str,
+        value: T,
+    ) -> Result<(), KeyvError> {
+        Ok(self
+            .store
+            .set_field(key, field, to_value(key, value)?, self.default_ttl)
+            .await?)
+    }
+
+    /// Writes `value` at a JSON path inside the document stored at `key`.
+    ///
+    /// The path starts with `$` and walks the document with `.field` and `[index]`
+    /// segments, such as `$.address.city` or `$.tags[0]`. The parent must already exist:
+    /// a missing last field is added, while an index must point at an existing element.
+    /// The key keeps its expiry. Postgres and SQLite update the document server-side in one
+    /// statement; other stores read and rewrite it with `compare_and_swap`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key of the document.
+    /// * `path` - Where to write inside the document.
+    /// * `value` - The value to write, which must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the document was updated, `Ok(false)` if the key or the
+    /// path's parent does not exist, or a `KeyvError` on failure, including
+    /// `StoreError::InvalidJsonPath` for a malformed path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", serde_json::json!({"address": {"city": "Paris"}, "tags": ["a"]}))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert!(keyv.set_json_path("user:1", "$.address.city", "Lyon").await.unwrap());
+    /// assert!(keyv.set_json_path("user:1", "$.tags[0]", "b").await.unwrap());
+    /// assert!(!keyv.set_json_path("user:1", "$.phone.home", "555").await.unwrap());
+    /// assert_eq!(
+    ///     keyv.get("user:1").await.unwrap().unwrap(),
+    ///     serde_json::json!({"address": {"city": "Lyon"}, "tags": ["b"]})
+    /// );
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "set_json_path", skip_all, fields(backend = self.backend, key = %key, path = %path), err)
+    )]
+    pub async fn set_json_path<T: Serialize>(
+        &self,
+        key: &str,
+        path: &str,
+        value: T,
+    ) -> Result<bool, KeyvError> {
+        Ok(self
+            .store
+            .set_json_path(key, path, to_value(key, value)?)
+            .await?)
+    }
+
+    /// Reads one field of the JSON object stored at `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key of the object.
+    /// * `field` - The name of the field to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(Value))` with the field's value, `Ok(None)` if the key or the field
+    /// does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_field("user:1", "name", "Alice").await.unwrap();
+    ///
+    /// assert!(keyv.get_field("user:1", "email").await.unwrap().is_none());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "get_field", skip_all, fields(backend = self.backend, key = %key, field = %field), err)
+    )]
+    pub async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, KeyvError> {
+        Ok(self.store.get_field(key, field).await?)
+    }
+
+    /// Removes one field of the JSON object stored at `key`.
+    ///
+    /// The key is kept, with its expiry, even once its last field is removed. Atomicity is
+    /// the same as for `set_field`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key of the object.
+    /// * `field` - The name of the field to remove.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the field existed and was removed, `Ok(false)` if the key or
+    /// the field does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_field("user:1", "name", "Alice").await.unwrap();
+    ///
+    /// assert!(keyv.remove_field("user:1", "name").await.unwrap());
+    /// assert!(!keyv.remove_field("user:1", "name").await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "remove_field", skip_all, fields(backend = self.backend, key = %key, field = %field), err)
+    )]
+    pub async fn remove_field(&self, key: &str, field: &str) -> Result<bool, KeyvError> {
+        Ok(self.store.remove_field(key, field).await?)
+    }
+
+    /// Removes a specified key from the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that represents the key to be removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the key has been successfully removed, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.remove("my_key").await.unwrap(); // Removes "my_key" from the store
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "remove", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn remove(&self, key: &str) -> Result<(), KeyvError> {
+        Ok(self.store.remove(key).await?)
+    }
+
+    /// Removes a key from the store and returns the value it held.
+    ///
+    /// On backends with an atomic fetch-and-delete, only one of several concurrent
+    /// callers receives the value, which makes this suitable for job-queue patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that represents the key to be taken.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the removed value, `None` if the key did not exist,
+    /// or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("job", "payload").await.unwrap();
+    ///
+    /// assert_eq!(keyv.take("job").await.unwrap(), Some(serde_json::json!("payload")));
+    /// assert_eq!(keyv.take("job").await.unwrap(), None);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "take", skip_all, fields(backend = self.backend, key = %key), err)
+    )]
+    pub async fn take(&self, key: &str) -> Result<Option<Value>, KeyvError> {
+        Ok(self.store.get_and_remove(key).await?)
+    }
+
+    /// Removes multiple keys from the store in one operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to be removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the number of keys that existed and were removed, or a
+    /// `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("key1", "value1").await.unwrap();
+    ///
+    /// let removed = keyv.remove_many(&["key1", "key2"]).await.unwrap(); // Removes "key1" and "key2"
+    /// assert_eq!(removed, 1);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "remove_many", skip_all, fields(backend = self.backend, count = keys.len()), err)
+    )]
+    pub async fn remove_many<T: AsRef<str> + Sync>(&self, keys: &[T]) -> Result<u64, KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        Ok(self.store.remove_many(&keys).await?)
+    }
+
+    /// Applies several sets and removes as a single unit, using the default TTL for sets
+    /// that do not carry their own.
+    ///
+    /// SQL stores run the batch in one transaction and redis in `MULTI`/`EXEC`, so a
+    /// failure leaves none of the operations applied. See `Store::apply_batch` for the
+    /// guarantees of other stores.
+    ///
+    /// # Arguments
+    ///
+    /// * `ops` - The operations to apply, in order.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result once the whole batch is applied, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{BatchOp, Keyv};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("order:1:status", "pending").await.unwrap();
+    ///
+    /// keyv.apply_batch(&[
+    ///     BatchOp::set("order:1:status", "shipped").unwrap(),
+    ///     BatchOp::set_with_ttl("order:1:tracking", "1Z999", 86400).unwrap(),
+    ///     BatchOp::remove("cart:1"),
+    /// ])
+    /// .await
+    /// .unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "apply_batch", skip_all, fields(backend = self.backend, count = ops.len()), err)
+    )]
+    pub async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), KeyvError> {
+        let ops: Vec<BatchOp> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => BatchOp::Set {
+                    key: key.clone(),
+                    value: value.clone(),
+                    ttl: ttl.or(self.default_ttl),
+                },
+                BatchOp::Remove { .. } => op.clone(),
+            })
+            .collect();
+        Ok(self.store.apply_batch(&ops).await?)
+    }
+
+    /// Moves the value stored at `from` to `to`, keeping its remaining TTL.
+    ///
+    /// A value already stored at `to` is overwritten. SQL stores and redis move the value
+    /// atomically.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key to move.
+    /// * `to` - The key to move it to.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if `from` existed and was moved, `Ok(false)` if it does not
+    /// exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("draft:1", "hello").await.unwrap();
+    ///
+    /// assert!(keyv.rename("draft:1", "published:1").await.unwrap());
+    /// assert!(!keyv.has("draft:1").await.unwrap());
+    /// assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "rename", skip_all, fields(backend = self.backend, from = %from, to = %to), err)
+    )]
+    pub async fn rename(&self, from: &str, to: &str) -> Result<bool, KeyvError> {
+        Ok(self.store.rename(from, to).await?)
+    }
+
+    /// Lists the keys in the store, optionally restricted to those starting with a prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - When set, only keys starting with this prefix are returned.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the matching keys in no particular order, or a
+    /// `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("session:1", "alice").await.unwrap();
+    /// keyv.set("cache:1", "page").await.unwrap();
+    ///
+    /// let sessions = keyv.keys(Some("session:")).await.unwrap();
+    /// assert_eq!(sessions, vec!["session:1".to_string()]);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "keys", skip_all, fields(backend = self.backend, prefix = ?prefix), err)
+    )]
+    pub async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, KeyvError> {
+        Ok(self.store.keys(prefix).await?)
+    }
+
+    /// Retrieves the entries whose keys fall in `[start, end)`, sorted by key.
+    ///
+    /// Keys are compared as strings, so zero-pad numeric parts such as timestamps to keep
+    /// them in order, or use `range_u64` for numeric IDs. SQL stores answer with a single
+    /// range query; other stores, Redis included, list the keys under the bounds' common
+    /// prefix and fetch the matches, which is slower on large keyspaces. Expired entries
+    /// are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The inclusive lower bound.
+    /// * `end` - The exclusive upper bound.
+    ///
+    /// # Returns
+    ///
+    /// Returns the matching `(key, value)` pairs in key order, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("event:0100", "a").await.unwrap();
+    /// keyv.set("event:0200", "b").await.unwrap();
+    /// keyv.set("event:0300", "c").await.unwrap();
+    ///
+    /// let entries = keyv.range("event:0100", "event:0300").await.unwrap();
+    /// let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, vec!["event:0100", "event:0200"]);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "range", skip_all, fields(backend = self.backend, start = %start, end = %end), err)
+    )]
+    pub async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, KeyvError> {
+        Ok(self.store.range(start, end).await?)
+    }
+
+    /// Sets a value under a numeric ID, using the default TTL if one is configured.
+    ///
+    /// The ID is stored under `u64_key(id)`, a fixed-width key that sorts in numeric
+    /// order, so `range_u64` returns IDs in order. Numeric and string keys share the same
+    /// key space; see `u64_key` on keeping them apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The numeric ID under which the value is stored.
+    /// * `value` - The value to store. Must implement `Serialize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_u64(42, "alice").await.unwrap();
+    /// assert_eq!(keyv.get_u64(42).await.unwrap(), Some("alice".into()));
+    /// # };
+    /// ```
+    pub async fn set_u64<T: Serialize>(&self, id: u64, value: T) -> Result<(), KeyvError> {
+        self.set(&u64_key(id), value).await
+    }
+
+    /// Retrieves the value stored under a numeric ID by `set_u64`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The numeric ID to look up.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(Value))` if the ID exists, `Ok(None)` if it does not, or a
+    /// `KeyvError` on failure.
+    pub async fn get_u64(&self, id: u64) -> Result<Option<Value>, KeyvError> {
+        self.get(&u64_key(id)).await
+    }
+
+    /// Removes the value stored under a numeric ID by `set_u64`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The numeric ID to remove.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on success, or a `KeyvError` on failure.
+    pub async fn remove_u64(&self, id: u64) -> Result<(), KeyvError> {
+        self.remove(&u64_key(id)).await
+    }
+
+    /// Retrieves the entries whose numeric IDs fall in `[start, end)`, sorted by ID.
+    ///
+    /// Works like `range` on the keys written by `set_u64`; keys that are not numeric IDs
+    /// are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The inclusive lower bound.
+    /// * `end` - The exclusive upper bound.
+    ///
+    /// # Returns
+    ///
+    /// Returns the matching `(id, value)` pairs in numeric order, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// for id in [9, 10, 11] {
+    ///     keyv.set_u64(id, id * 100).await.unwrap();
+    /// }
+    ///
+    /// let entries = keyv.range_u64(9, 11).await.unwrap();
+    /// let ids: Vec<u64> = entries.iter().map(|(id, _)| *id).collect();
+    /// assert_eq!(ids, vec![9, 10]);
+    /// # };
+    /// ```
+    pub async fn range_u64(&self, start: u64, end: u64) -> Result<Vec<(u64, Value)>, KeyvError> {
+        let entries = self.range(&u64_key(start), &u64_key(end)).await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|(key, value)| parse_u64_key(&key).map(|id| (id, value)))
+            .collect())
+    }
+
+    /// Streams every entry in the store as `(key, value)` pairs.
+    ///
+    /// Entries are fetched from the store in batches as the stream is polled, so large
+    /// stores can be walked without loading everything into memory. Expired entries are
+    /// skipped. Entries written or removed while iterating may or may not be seen.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each entry in no particular order, or a `KeyvError` if a
+    /// batch fails to load. Entries that cannot be decoded are left out if
+    /// `with_decode_policy` says to skip them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use futures::TryStreamExt;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("a", 1).await.unwrap();
+    /// keyv.set("b", 2).await.unwrap();
+    ///
+    /// let entries: Vec<(String, serde_json::Value)> = keyv.iter().try_collect().await.unwrap();
+    /// assert_eq!(entries.len(), 2);
+    /// # };
+    /// ```
+    pub fn iter(&self) -> impl Stream<Item = Result<(String, Value), KeyvError>> + Send + '_ {
+        self.skip_undecodable(self.store.iter())
+    }
+
+    /// Streams the entries whose key starts with `prefix` as `(key, value)` pairs.
+    ///
+    /// Like `iter`, entries are fetched in batches as the stream is polled and expired
+    /// entries are skipped. SQL backends page through the matching keys in key order, and
+    /// Redis walks them with `SCAN MATCH`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Only keys starting with this prefix are returned.
+    ///
+    /// # Returns
+    ///
+    /// Returns a stream yielding each matching entry, or a `KeyvError` if a batch fails to
+    /// load.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # use futures::TryStreamExt;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    /// keyv.set("order:1", 42).await.unwrap();
+    ///
+    /// let users: Vec<(String, serde_json::Value)> =
+    ///     keyv.scan_prefix("user:").try_collect().await.unwrap();
+    /// assert_eq!(users.len(), 1);
+    /// # };
+    /// ```
+    pub fn scan_prefix(
+        &self,
+        prefix: &str,
+    ) -> impl Stream<Item = Result<(String, Value), KeyvError>> + Send + '_ {
+        self.skip_undecodable(self.store.scan_prefix(prefix))
+    }
+
+    /// Drops the entries of `entries` that cannot be decoded, as the decode policy allows.
+    fn skip_undecodable<'a>(
+        &self,
+        entries: EntryStream<'a>,
+    ) -> impl Stream<Item = Result<(String, Value), KeyvError>> + Send + 'a {
+        let policy = self.decode_policy.clone();
+        entries.filter_map(move |entry| {
+            future::ready(match entry {
+                Ok(entry) => Some(Ok(entry)),
+                Err(e) => policy.skip(e).err().map(|e| Err(KeyvError::from(e))),
+            })
+        })
+    }
+
+    /// Returns the number of entries in the store.
+    ///
+    /// Entries whose TTL has passed are not counted, even if the backend has not removed
+    /// them yet.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the number of entries, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("key", "value").await.unwrap();
+    /// assert_eq!(keyv.len().await.unwrap(), 1);
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "len", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn len(&self) -> Result<usize, KeyvError> {
+        Ok(self.store.len().await?)
+    }
+
+    /// Returns `true` if the store holds no entries.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result indicating whether the store is empty, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert!(keyv.is_empty().await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "is_empty", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn is_empty(&self) -> Result<bool, KeyvError> {
+        Ok(self.store.is_empty().await?)
+    }
+
+    /// Clears the entire store, removing all key-value pairs.
+    ///
+    /// On a namespace view, only the keys of that namespace are removed, with a delete
+    /// scoped to its prefix. The prefix is matched byte for byte, so a view never removes
+    /// the keys of a namespace that differs only in case. On the root `Keyv` of a SQL
+    /// store, every row of the table is deleted.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the store has been successfully cleared, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.clear().await.unwrap(); // Clears the entire store
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "clear", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn clear(&self) -> Result<(), KeyvError> {
+        Ok(self.store.clear().await?)
+    }
+
+    /// Removes every key starting with `prefix`, leaving the rest of the store untouched.
+    ///
+    /// SQL and MongoDB stores delete the keys with a single query. The Redis store has to
+    /// `SCAN` its whole keyspace to find them, which is slow on large databases no matter
+    /// how few keys match.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the keys to remove.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the number of keys removed, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("session:1", "alice").await.unwrap();
+    /// keyv.set("session:2", "bob").await.unwrap();
+    /// keyv.set("cache:1", "page").await.unwrap();
+    ///
+    /// assert_eq!(keyv.clear_prefix("session:").await.unwrap(), 2);
+    /// assert!(keyv.has("cache:1").await.unwrap());
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "clear_prefix", skip_all, fields(backend = self.backend, prefix = %prefix), err)
+    )]
+    pub async fn clear_prefix(&self, prefix: &str) -> Result<u64, KeyvError> {
+        Ok(self.store.clear_prefix(prefix).await?)
+    }
+
+    /// Reclaims space left behind by deleted entries, where the store supports it.
+    ///
+    /// SQLite runs `VACUUM`, which blocks writers while it rebuilds the database file;
+    /// see `Store::optimize`. Other stores return immediately.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` once the store has been compacted, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.optimize().await.unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "optimize", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn optimize(&self) -> Result<(), KeyvError> {
+        Ok(self.store.optimize().await?)
+    }
+
+    /// Flushes pending writes and shuts the store down.
+    ///
+    /// Buffered writes are flushed, background cleanup and flush tasks are stopped and
+    /// SQL connection pools are closed. Rust has no async `Drop`, so await `close` before
+    /// the process exits; a store that is only dropped makes a best-effort attempt at
+    /// most. Namespace views share the store of their parent, which is closed for all of
+    /// them. Closing a store twice is safe.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` once the store has been closed, or a `KeyvError` if pending
+    /// writes could not be flushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user", "alice").await.unwrap();
+    /// keyv.close().await.unwrap();
+    /// # };
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "close", skip_all, fields(backend = self.backend), err)
+    )]
+    pub async fn close(self) -> Result<(), KeyvError> {
+        Ok(self.store.close().await?)
+    }
+}
+
+impl Default for Keyv {
+    fn default() -> Self {
+        let events = Arc::new(EventStore::new(Arc::new(InMemoryStore::new())));
+        Self {
+            store: events.clone(),
+            default_ttl: None,
+            decode_policy: DecodePolicy::default(),
+            events,
+            #[cfg(feature = "tracing")]
+            backend: backend_name::<InMemoryStore>(),
+        }
+    }
+}
+
+/// Returns the unqualified type name of `S`, such as `PostgresStore`.
+#[cfg(feature = "tracing")]
+fn backend_name<S>() -> &'static str {
+    let name = std::any::type_name::<S>();
+    name.rsplit("::").next().unwrap_or(name)
 }
@@ -1,5 +1,11 @@
+mod decode;
+pub use decode::*;
+
 mod errors;
 pub use errors::*;
 
 mod keyv;
 pub use keyv::*;
+
+mod u64_key;
+pub use u64_key::*;
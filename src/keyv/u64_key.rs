@@ -0,0 +1,30 @@
+/// Encodes a numeric ID as a key that sorts in numeric order.
+///
+/// The ID is written as its eight big-endian bytes in lowercase hex, always 16 characters,
+/// so comparing two keys as strings gives the same order as comparing the IDs. This is
+/// the key `Keyv::set_u64` and `Keyv::get_u64` use, and it keeps `Keyv::range` correct
+/// for numeric IDs, where decimal strings would put `"10"` before `"9"`.
+///
+/// Encoded IDs share the key space of the store with string keys; nothing marks them
+/// apart. A string key such as `"00000000000000ff"` is the same key as ID 255, so keep
+/// numeric and string keys in separate namespaces.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{u64_key, parse_u64_key};
+/// assert_eq!(u64_key(255), "00000000000000ff");
+/// assert!(u64_key(9) < u64_key(10));
+/// assert_eq!(parse_u64_key(&u64_key(42)), Some(42));
+/// ```
+pub fn u64_key(id: u64) -> String {
+    format!("{:016x}", id)
+}
+
+/// Decodes a key written by `u64_key`, returning `None` for any other key.
+pub fn parse_u64_key(key: &str) -> Option<u64> {
+    if key.len() != 16 || !key.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    u64::from_str_radix(key, 16).ok()
+}
@@ -1,6 +1,10 @@
 #![allow(dead_code)]
+#![allow(clippy::module_inception)]
 
-pub const DEFAUTL_NAMESPACE_NAME: &str = "keyv";
+pub const DEFAULT_NAMESPACE_NAME: &str = "keyv";
+
+#[deprecated(since = "0.2.2", note = "use `DEFAULT_NAMESPACE_NAME` instead")]
+pub const DEFAUTL_NAMESPACE_NAME: &str = DEFAULT_NAMESPACE_NAME;
 
 mod keyv;
 pub use keyv::*;
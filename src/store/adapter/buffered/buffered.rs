@@ -0,0 +1,378 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
+use serde_json::Value;
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError};
+
+const DEFAULT_MAX_ENTRIES: usize = 100;
+
+/// Writes waiting to be flushed, with the TTL each was set with.
+type Pending = HashMap<String, (Value, Option<u64>)>;
+
+/// A write-behind store that buffers `set` calls and writes them to an inner store in
+/// batches.
+///
+/// Writes are coalesced per key in memory and flushed with a single `set_many` once
+/// `max_entries` keys are pending, every `flush_interval` if one is configured, or when
+/// `flush` is called. Reads see buffered values before the inner store. Any other
+/// operation, such as `increment` or `keys`, flushes the buffer first so that it
+/// observes every earlier write.
+///
+/// **Durability:** buffered writes only live in memory. If the process crashes, or the
+/// inner store keeps failing, the writes since the last flush are lost. Dropping the
+/// store starts a final flush in the background on a best-effort basis; call `flush`
+/// before shutting down to be sure every write reached the inner store. The TTL of a
+/// buffered write starts counting when it is flushed.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{buffered::BufferedStore, inmemory::InMemoryStore}};
+/// # use std::{sync::Arc, time::Duration};
+/// # async {
+/// let store = BufferedStore::new(Arc::new(InMemoryStore::new()))
+///     .max_entries(500)
+///     .flush_interval(Duration::from_millis(200));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("metric:requests", 1).await.unwrap();
+/// # };
+/// ```
+pub struct BufferedStore {
+    inner: Arc<dyn Store>,
+    pending: Arc<Mutex<Pending>>,
+    max_entries: usize,
+    flush_task: Option<JoinHandle<()>>,
+}
+
+impl BufferedStore {
+    /// Creates a store that buffers writes to `inner`, flushing every 100 pending keys.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self {
+            inner,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            max_entries: DEFAULT_MAX_ENTRIES,
+            flush_task: None,
+        }
+    }
+
+    /// Sets how many distinct keys may be pending before a write flushes the buffer.
+    ///
+    /// The write that fills the buffer waits for the flush. Defaults to 100.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The number of pending keys that triggers a flush.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Starts a background task that flushes the buffer every `interval`.
+    ///
+    /// The task is stopped when the store is dropped. Must be called from within a
+    /// Tokio runtime. Failed flushes are logged and retried on the next tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two flushes.
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        let inner = self.inner.clone();
+        let pending = self.pending.clone();
+        self.flush_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; wait a full interval before flushing.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let mut pending = pending.lock().await;
+                if let Err(e) = flush_pending(inner.as_ref(), &mut pending).await {
+                    log::warn!("Failed to flush {} buffered writes: {}", pending.len(), e);
+                }
+            }
+        }));
+        self
+    }
+
+    /// Writes every buffered value to the inner store.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the buffer is empty.
+    /// - `Err(StoreError)` if the inner store rejected the batch. The writes stay
+    ///   buffered and are retried on the next flush.
+    pub async fn flush(&self) -> Result<(), StoreError> {
+        let mut pending = self.pending.lock().await;
+        flush_pending(self.inner.as_ref(), &mut pending).await
+    }
+
+    /// Adds `entries` to the buffer, flushing it if it is full.
+    async fn buffer<'a>(
+        &self,
+        entries: impl IntoIterator<Item = (&'a str, Value, Option<u64>)>,
+    ) -> Result<(), StoreError> {
+        let mut pending = self.pending.lock().await;
+        for (key, value, ttl) in entries {
+            pending.insert(key.to_string(), (value, ttl));
+        }
+        if pending.len() >= self.max_entries {
+            flush_pending(self.inner.as_ref(), &mut pending).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `pending` to `inner` in one `set_many` and empties it on success.
+///
+/// The caller holds the buffer lock throughout, so readers never miss a value that has
+/// left the buffer but not yet reached the inner store.
+async fn flush_pending(inner: &dyn Store, pending: &mut Pending) -> Result<(), StoreError> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let entries: Vec<(&str, Value, Option<u64>)> = pending
+        .iter()
+        .map(|(key, (value, ttl))| (key.as_str(), value.clone(), *ttl))
+        .collect();
+    inner.set_many(&entries).await?;
+    pending.clear();
+    Ok(())
+}
+
+impl Drop for BufferedStore {
+    fn drop(&mut self) {
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
+
+        let Ok(mut pending) = self.pending.try_lock() else {
+            return;
+        };
+        if pending.is_empty() {
+            return;
+        }
+        let mut pending = std::mem::take(&mut *pending);
+        let inner = self.inner.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = flush_pending(inner.as_ref(), &mut pending).await {
+                        log::warn!("Failed to flush {} buffered writes: {}", pending.len(), e);
+                    }
+                });
+            }
+            Err(_) => log::warn!(
+                "Dropped {} buffered writes outside of a Tokio runtime",
+                pending.len()
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl Store for BufferedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        if let Some((value, _)) = self.pending.lock().await.get(key) {
+            return Ok(Some(value.clone()));
+        }
+        self.inner.get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        if self.pending.lock().await.contains_key(key) {
+            return Ok(true);
+        }
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let buffered: Vec<Option<Value>> = {
+            let pending = self.pending.lock().await;
+            keys.iter()
+                .map(|key| pending.get(*key).map(|(value, _)| value.clone()))
+                .collect()
+        };
+        let stored = self.inner.get_many(keys).await?;
+        Ok(buffered
+            .into_iter()
+            .zip(stored)
+            .map(|(buffered, stored)| buffered.or(stored))
+            .collect())
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.flush().await?;
+        self.inner.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.flush().await?;
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.buffer([(key, value, ttl)]).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        // Bytes go straight to the inner store; drop any buffered value they replace.
+        let mut pending = self.pending.lock().await;
+        pending.remove(key);
+        self.inner.set_bytes(key, value, ttl).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.flush().await?;
+        self.inner.get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.buffer(
+            entries
+                .iter()
+                .map(|(key, value, ttl)| (*key, value.clone(), *ttl)),
+        )
+        .await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.flush().await?;
+        self.inner.compare_and_swap(key, expected, new, ttl).await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.flush().await?;
+        self.inner.set_nx(key, value, ttl).await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.flush().await?;
+        self.inner.swap(key, value, ttl).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.flush().await?;
+        self.inner.increment(key, delta).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.flush().await?;
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.flush().await?;
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        // Hold the lock so that a concurrent flush cannot write the key back afterwards.
+        let mut pending = self.pending.lock().await;
+        pending.remove(key);
+        self.inner.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.flush().await?;
+        self.inner.get_and_remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.flush().await?;
+        self.inner.remove_many(keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.flush().await?;
+        self.inner.apply_batch(ops).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.flush().await?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.flush().await?;
+        self.inner.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.flush().await?;
+        self.inner.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(
+            stream::once(self.flush())
+                .map_ok(|()| self.inner.iter())
+                .try_flatten(),
+        )
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let prefix = prefix.to_string();
+        Box::pin(
+            stream::once(self.flush())
+                .map_ok(move |()| self.inner.scan_prefix(&prefix))
+                .try_flatten(),
+        )
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        Box::pin(
+            stream::once(self.flush())
+                .map_ok(|()| self.inner.export())
+                .try_flatten(),
+        )
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.flush().await?;
+        self.inner.len().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let mut pending = self.pending.lock().await;
+        pending.clear();
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.flush().await?;
+        self.inner.clear_prefix(prefix).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        if let Some(task) = &self.flush_task {
+            task.abort();
+        }
+        self.flush().await?;
+        self.inner.close().await
+    }
+}
@@ -0,0 +1,2 @@
+mod buffered;
+pub use buffered::*;
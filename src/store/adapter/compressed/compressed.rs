@@ -0,0 +1,354 @@
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::{read::GzDecoder, write::GzEncoder};
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, Store, StoreError, Ttl};
+
+/// Marks a string value as a compressed payload. The algorithm name and a base64 body
+/// follow, e.g. `"\u{1b}keyv:zstd:KLUv/..."`.
+const MAGIC: &str = "\u{1b}keyv:";
+
+const DEFAULT_THRESHOLD: usize = 1024;
+
+const DEFAULT_MAX_DECODED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Compression algorithms supported by `CompressedStore`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn name(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gzip" => Some(Compression::Gzip),
+            "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>, StoreError> {
+        match self {
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(bytes)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|e| StoreError::CodecError { source: e.into() })
+            }
+            Compression::Zstd => zstd::encode_all(bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .map_err(|e| StoreError::CodecError { source: e.into() }),
+        }
+    }
+
+    /// Decompresses `bytes`, failing once the output grows past `limit` bytes.
+    fn decompress(&self, bytes: &[u8], limit: usize) -> Result<Vec<u8>, StoreError> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Compression::Gzip => Box::new(GzDecoder::new(bytes)),
+            Compression::Zstd => Box::new(
+                zstd::stream::read::Decoder::new(bytes)
+                    .map_err(|e| StoreError::CodecError { source: e.into() })?,
+            ),
+        };
+
+        // Reading one byte past the limit tells a value of exactly `limit` bytes apart
+        // from a larger one without decompressing the rest.
+        let mut decompressed = Vec::new();
+        decoder
+            .take(limit as u64 + 1)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| StoreError::CodecError { source: e.into() })?;
+        if decompressed.len() > limit {
+            return Err(StoreError::CodecError {
+                source: format!("Decompressed value exceeds {} bytes", limit).into(),
+            });
+        }
+        Ok(decompressed)
+    }
+}
+
+/// A store that compresses large values before handing them to an inner store.
+///
+/// Strings, arrays and objects whose JSON encoding reaches the threshold are compressed
+/// and stored as a string carrying a magic header, the algorithm name and a base64 body.
+/// Everything else, including all numbers so that `increment` keeps working, is stored
+/// unchanged. Reads recognise the header, so data written before compression was enabled,
+/// or with another algorithm, still reads correctly.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{compressed::{CompressedStore, Compression}, inmemory::InMemoryStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let store = CompressedStore::new(Arc::new(InMemoryStore::new()), Compression::Zstd)
+///     .threshold(256);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct CompressedStore {
+    inner: Arc<dyn Store>,
+    compression: Compression,
+    threshold: usize,
+    max_decoded_size: usize,
+}
+
+impl CompressedStore {
+    /// Creates a store that compresses values written to `inner` with `compression`.
+    pub fn new(inner: Arc<dyn Store>, compression: Compression) -> Self {
+        Self {
+            inner,
+            compression,
+            threshold: DEFAULT_THRESHOLD,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+        }
+    }
+
+    /// Sets the size, in bytes of JSON, from which values are compressed.
+    ///
+    /// Smaller values are stored as-is to avoid the compression overhead. Defaults to
+    /// 1024 bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The minimum encoded size of a compressed value.
+    pub fn threshold(mut self, bytes: usize) -> Self {
+        self.threshold = bytes;
+        self
+    }
+
+    /// Sets the largest size, in bytes, a compressed value may decompress to.
+    ///
+    /// A small payload can expand to gigabytes, so reads stop and fail with
+    /// `StoreError::CodecError` once the output grows past this size. Defaults to 64 MiB.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The maximum decompressed size of a value.
+    pub fn max_decoded_size(mut self, bytes: usize) -> Self {
+        self.max_decoded_size = bytes;
+        self
+    }
+
+    fn encode(&self, value: Value) -> Result<Value, StoreError> {
+        // A plain string that happens to start with the header must be compressed too,
+        // otherwise it would be mistaken for a compressed payload on read.
+        let must_compress = matches!(&value, Value::String(s) if s.starts_with(MAGIC));
+        if !matches!(value, Value::String(_) | Value::Array(_) | Value::Object(_)) {
+            return Ok(value);
+        }
+
+        let json = serde_json::to_vec(&value).map_err(|e| StoreError::SerializationError {
+            key: None,
+            source: e,
+        })?;
+        if json.len() < self.threshold && !must_compress {
+            return Ok(value);
+        }
+
+        let compressed = self.compression.compress(&json)?;
+        Ok(Value::String(format!(
+            "{}{}:{}",
+            MAGIC,
+            self.compression.name(),
+            STANDARD.encode(compressed)
+        )))
+    }
+
+    fn decode(&self, value: Value) -> Result<Value, StoreError> {
+        let Some(payload) = value.as_str().and_then(|s| s.strip_prefix(MAGIC)) else {
+            return Ok(value);
+        };
+
+        let (name, body) = payload
+            .split_once(':')
+            .ok_or_else(|| StoreError::CodecError {
+                source: "Compressed value has no algorithm name".into(),
+            })?;
+        let compression = Compression::from_name(name).ok_or_else(|| StoreError::CodecError {
+            source: format!("Unknown compression algorithm '{}'", name).into(),
+        })?;
+        let compressed = STANDARD
+            .decode(body)
+            .map_err(|e| StoreError::CodecError { source: e.into() })?;
+        let json = compression.decompress(&compressed, self.max_decoded_size)?;
+
+        serde_json::from_slice(&json).map_err(|e| StoreError::DeserializationError {
+            key: None,
+            source: e,
+        })
+    }
+}
+
+#[async_trait]
+impl Store for CompressedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .get(key)
+            .await?
+            .map(|value| self.decode(value))
+            .transpose()
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.inner
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .map(|value| value.map(|value| self.decode(value)).transpose())
+            .collect()
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner
+            .get_with_meta(key)
+            .await?
+            .map(|(value, meta)| Ok((self.decode(value)?, meta)))
+            .transpose()
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, self.encode(value)?, ttl).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner.set_with_ttl(key, self.encode(value)?, ttl).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let entries = entries
+            .iter()
+            .map(|(key, value, ttl)| Ok((*key, self.encode(value.clone())?, *ttl)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.inner.set_many(&entries).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        // Compression is deterministic, so the encoded `expected` matches what this store
+        // wrote for the same value with the same algorithm.
+        let expected = expected.map(|value| self.encode(value)).transpose()?;
+        self.inner
+            .compare_and_swap(key, expected, self.encode(new)?, ttl)
+            .await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .swap(key, self.encode(value)?, ttl)
+            .await?
+            .map(|value| self.decode(value))
+            .transpose()
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.inner.increment(key, delta).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .get_and_remove(key)
+            .await?
+            .map(|value| self.decode(value))
+            .transpose()
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let ops = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => Ok(BatchOp::Set {
+                    key: key.clone(),
+                    value: self.encode(value.clone())?,
+                    ttl: *ttl,
+                }),
+                BatchOp::Remove { .. } => Ok(op.clone()),
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.inner.apply_batch(&ops).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.inner.clear_prefix(prefix).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
@@ -0,0 +1,2 @@
+mod compressed;
+pub use compressed::*;
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, Store, StoreError, Ttl};
+
+/// Prefix of every encrypted value, followed by the base64 of the nonce and ciphertext.
+const MAGIC: &str = "\u{1b}keyv:chacha20poly1305:";
+
+const NONCE_LEN: usize = 12;
+
+/// A store that encrypts values with ChaCha20-Poly1305 before handing them to an inner
+/// store.
+///
+/// Each value is encrypted under a fresh random nonce, which is prepended to the
+/// ciphertext. The key is used as associated data, so a ciphertext copied to another key
+/// fails to decrypt. Keys themselves are stored in plaintext.
+///
+/// Every value read back must have been written by an `EncryptedStore` with the same
+/// key; anything else fails with `StoreError::DecryptionError`. Because ciphertexts are
+/// randomised, `compare_and_swap` and `increment` use the non-atomic trait defaults, and
+/// `set_nx` is atomic only when the inner store's `compare_and_swap` is. As each
+/// ciphertext is bound to its key, `rename` re-encrypts the value under the new key
+/// through the trait default as well.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{encrypted::EncryptedStore, inmemory::InMemoryStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let key = [7u8; 32]; // Load this from a secret manager.
+/// let store = EncryptedStore::new(Arc::new(InMemoryStore::new()), key);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("token", "secret").await.unwrap();
+/// # };
+/// ```
+pub struct EncryptedStore {
+    inner: Arc<dyn Store>,
+    cipher: ChaCha20Poly1305,
+}
+
+impl EncryptedStore {
+    /// Creates a store that encrypts values written to `inner` with the 256-bit `key`.
+    pub fn new(inner: Arc<dyn Store>, key: [u8; 32]) -> Self {
+        Self {
+            inner,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        }
+    }
+
+    fn encrypt(&self, key: &str, value: &Value) -> Result<Value, StoreError> {
+        let plaintext = serde_json::to_vec(value).map_err(|e| StoreError::SerializationError {
+            key: Some(key.to_string()),
+            source: e,
+        })?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| StoreError::CodecError {
+                source: "Failed to encrypt the value".into(),
+            })?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(Value::String(format!(
+            "{}{}",
+            MAGIC,
+            STANDARD.encode(payload)
+        )))
+    }
+
+    fn decrypt(&self, key: &str, value: Value) -> Result<Value, StoreError> {
+        let error = || StoreError::DecryptionError(key.to_string());
+
+        let payload = value
+            .as_str()
+            .and_then(|s| s.strip_prefix(MAGIC))
+            .ok_or_else(error)?;
+        let payload = STANDARD.decode(payload).map_err(|_| error())?;
+        if payload.len() < NONCE_LEN {
+            return Err(error());
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| error())?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| StoreError::DeserializationError {
+            key: Some(key.to_string()),
+            source: e,
+        })
+    }
+}
+
+#[async_trait]
+impl Store for EncryptedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .get(key)
+            .await?
+            .map(|value| self.decrypt(key, value))
+            .transpose()
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.inner
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .zip(keys)
+            .map(|(value, key)| value.map(|value| self.decrypt(key, value)).transpose())
+            .collect()
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner
+            .get_with_meta(key)
+            .await?
+            .map(|(value, meta)| Ok((self.decrypt(key, value)?, meta)))
+            .transpose()
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, self.encrypt(key, &value)?, ttl).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner
+            .set_with_ttl(key, self.encrypt(key, &value)?, ttl)
+            .await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let entries = entries
+            .iter()
+            .map(|(key, value, ttl)| Ok((*key, self.encrypt(key, value)?, *ttl)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.inner.set_many(&entries).await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .swap(key, self.encrypt(key, &value)?, ttl)
+            .await?
+            .map(|value| self.decrypt(key, value))
+            .transpose()
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        // An absent key needs no ciphertext comparison, so this is exactly as atomic as the
+        // inner `compare_and_swap`. The in-memory, file, SQL and redis stores check and
+        // write in one step; a store on the trait default, such as `MongoStore`, does not.
+        self.inner
+            .compare_and_swap(key, None, self.encrypt(key, &value)?, ttl)
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner
+            .get_and_remove(key)
+            .await?
+            .map(|value| self.decrypt(key, value))
+            .transpose()
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let ops = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => Ok(BatchOp::Set {
+                    key: key.clone(),
+                    value: self.encrypt(key, value)?,
+                    ttl: *ttl,
+                }),
+                BatchOp::Remove { .. } => Ok(op.clone()),
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.inner.apply_batch(&ops).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.inner.clear_prefix(prefix).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
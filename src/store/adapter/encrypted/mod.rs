@@ -0,0 +1,2 @@
+mod encrypted;
+pub use encrypted::*;
@@ -0,0 +1,402 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::{
+    utils::remaining_ttl, BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl,
+};
+
+/// Number of events a subscriber may fall behind before it starts missing them.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A change made through an `EventStore`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreEvent {
+    /// `key` was written.
+    Set { key: String },
+    /// `key` was removed.
+    Removed { key: String },
+    /// Every key was removed.
+    Cleared,
+    /// Every key starting with `prefix` was removed.
+    ClearedPrefix { prefix: String },
+}
+
+/// A store that broadcasts a `StoreEvent` for every change made through it.
+///
+/// The channel is only created by the first call to `subscribe`; until then writes
+/// are passed through without building any events. Events are sent once the inner
+/// store reports success, and only cover changes made through this store, not those
+/// made by other processes sharing the backend or keys expiring on their own.
+///
+/// `remove`, `remove_many` and `BatchOp::Remove` report every key they were given,
+/// whether or not it existed. `set_field`, `remove_field` and `set_json_path` report a
+/// `Set` for the key they changed. An `import` reports each entry it wrote once it is
+/// done, and nothing if it fails part way. A subscriber that falls more than 1024 events behind
+/// receives `RecvError::Lagged` and skips the oldest ones.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Store, adapter::{events::{EventStore, StoreEvent}, inmemory::InMemoryStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let store = EventStore::new(Arc::new(InMemoryStore::new()));
+/// let mut events = store.subscribe();
+///
+/// store.set("user", "alice".into(), None).await.unwrap();
+/// assert_eq!(
+///     events.recv().await.unwrap(),
+///     StoreEvent::Set { key: "user".to_string() }
+/// );
+/// # };
+/// ```
+pub struct EventStore {
+    inner: Arc<dyn Store>,
+    sender: OnceLock<broadcast::Sender<StoreEvent>>,
+}
+
+impl EventStore {
+    /// Creates a store that reports the changes made to `inner`.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self {
+            inner,
+            sender: OnceLock::new(),
+        }
+    }
+
+    /// Returns a receiver for the changes made after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<StoreEvent> {
+        self.sender
+            .get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Sends `event` to the subscribers as if the change had been made through this store.
+    ///
+    /// Lets changes made elsewhere, such as those reported by a Redis `KeyspaceListener`,
+    /// reach the same subscribers.
+    pub fn publish(&self, event: StoreEvent) {
+        self.emit(|| [event]);
+    }
+
+    /// Sends the events built by `events`, if anyone is listening.
+    fn emit<I: IntoIterator<Item = StoreEvent>>(&self, events: impl FnOnce() -> I) {
+        let Some(sender) = self.sender.get() else {
+            return;
+        };
+        if sender.receiver_count() == 0 {
+            return;
+        }
+        for event in events() {
+            // Only fails once every receiver is gone, in which case nobody is missing it.
+            let _ = sender.send(event);
+        }
+    }
+
+    fn emit_set(&self, key: &str) {
+        self.emit(|| {
+            [StoreEvent::Set {
+                key: key.to_string(),
+            }]
+        });
+    }
+
+    /// Tells whether anyone is listening, so that events need to be built.
+    fn has_subscribers(&self) -> bool {
+        self.sender
+            .get()
+            .is_some_and(|sender| sender.receiver_count() > 0)
+    }
+
+    fn emit_removed(&self, key: &str) {
+        self.emit(|| {
+            [StoreEvent::Removed {
+                key: key.to_string(),
+            }]
+        });
+    }
+}
+
+#[async_trait]
+impl Store for EventStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.inner.get_many(keys).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, value, ttl).await?;
+        self.emit_set(key);
+        Ok(())
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner.set_with_ttl(key, value, ttl).await?;
+        self.emit_set(key);
+        Ok(())
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set_bytes(key, value, ttl).await?;
+        self.emit_set(key);
+        Ok(())
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.inner.get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.inner.set_many(entries).await?;
+        self.emit(|| {
+            entries.iter().map(|(key, _, _)| StoreEvent::Set {
+                key: key.to_string(),
+            })
+        });
+        Ok(())
+    }
+
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.inner.set_group(entries, ttl).await?;
+        self.emit(|| {
+            entries.iter().map(|(key, _)| StoreEvent::Set {
+                key: key.to_string(),
+            })
+        });
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let swapped = self.inner.compare_and_swap(key, expected, new, ttl).await?;
+        if swapped {
+            self.emit_set(key);
+        }
+        Ok(swapped)
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let stored = self.inner.set_nx(key, value, ttl).await?;
+        if stored {
+            self.emit_set(key);
+        }
+        Ok(stored)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let previous = self.inner.swap(key, value, ttl).await?;
+        self.emit_set(key);
+        Ok(previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let value = self.inner.increment(key, delta).await?;
+        self.emit_set(key);
+        Ok(value)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get_field(key, field).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.inner.set_field(key, field, value, ttl).await?;
+        self.emit_set(key);
+        Ok(())
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        let updated = self.inner.set_json_path(key, path, value).await?;
+        if updated {
+            self.emit_set(key);
+        }
+        Ok(updated)
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        let removed = self.inner.remove_field(key, field).await?;
+        if removed {
+            // The key keeps the rest of its object, so it was rewritten, not removed.
+            self.emit_set(key);
+        }
+        Ok(removed)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await?;
+        self.emit_removed(key);
+        Ok(())
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let value = self.inner.get_and_remove(key).await?;
+        if value.is_some() {
+            self.emit_removed(key);
+        }
+        Ok(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let removed = self.inner.remove_many(keys).await?;
+        self.emit(|| {
+            keys.iter().map(|key| StoreEvent::Removed {
+                key: key.to_string(),
+            })
+        });
+        Ok(removed)
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.inner.apply_batch(ops).await?;
+        self.emit(|| {
+            ops.iter().map(|op| match op {
+                BatchOp::Set { key, .. } => StoreEvent::Set { key: key.clone() },
+                BatchOp::Remove { key } => StoreEvent::Removed { key: key.clone() },
+            })
+        });
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let renamed = self.inner.rename(from, to).await?;
+        if renamed && from != to {
+            self.emit(|| {
+                [
+                    StoreEvent::Removed {
+                        key: from.to_string(),
+                    },
+                    StoreEvent::Set {
+                        key: to.to_string(),
+                    },
+                ]
+            });
+        }
+        Ok(renamed)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.inner.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn import(&self, entries: ExportStream<'_>) -> Result<u64, StoreError> {
+        if !self.has_subscribers() {
+            return self.inner.import(entries).await;
+        }
+
+        // Collects the keys as the inner store reads them, leaving out the entries it
+        // skips for having expired.
+        let keys = Mutex::new(Vec::new());
+        let entries = Box::pin(entries.inspect_ok(|(key, _, expires_at)| {
+            if expires_at.is_none_or(|expires_at| remaining_ttl(expires_at).is_some()) {
+                keys.lock().unwrap().push(key.clone());
+            }
+        }));
+        let imported = self.inner.import(entries).await?;
+        let keys = keys.into_inner().unwrap();
+        self.emit(|| keys.into_iter().map(|key| StoreEvent::Set { key }));
+        Ok(imported)
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.inner.is_empty().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await?;
+        self.emit(|| [StoreEvent::Cleared]);
+        Ok(())
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let removed = self.inner.clear_prefix(prefix).await?;
+        self.emit(|| {
+            [StoreEvent::ClearedPrefix {
+                prefix: prefix.to_string(),
+            }]
+        });
+        Ok(removed)
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
@@ -0,0 +1,2 @@
+mod events;
+pub use events::*;
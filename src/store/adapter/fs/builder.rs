@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use crate::StoreError;
+
+use super::FsStore;
+
+/// Builder for creating an `FsStore`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::adapter::fs::FsStoreBuilder;
+/// # #[tokio::main]
+/// # async fn main(){
+/// let store = FsStoreBuilder::new()
+///     .path("/var/cache/my-app")
+///     .build()
+///     .await.unwrap();
+///  }
+/// ```
+pub struct FsStoreBuilder {
+    path: Option<PathBuf>,
+}
+
+impl FsStoreBuilder {
+    pub fn new() -> Self {
+        Self { path: None }
+    }
+
+    /// Sets the directory holding the store's files.
+    ///
+    /// The directory is created, along with its parents, if it does not exist. It should
+    /// be dedicated to the store, since `clear` removes every entry file in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The base directory of the store.
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Builds the `FsStore`, creating its directory if needed.
+    pub async fn build(self) -> Result<FsStore, StoreError> {
+        let path = self
+            .path
+            .expect("FsStore requires a directory path to be set");
+        tokio::fs::create_dir_all(&path)
+            .await
+            .map_err(|e| StoreError::DatabaseError { source: e.into() })?;
+
+        Ok(FsStore::new(path))
+    }
+}
+
+impl Default for FsStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
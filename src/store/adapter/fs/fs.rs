@@ -0,0 +1,447 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+    utils::{expires_at, now_secs},
+    Store, StoreError,
+};
+
+/// Extension of the files holding entries.
+const ENTRY_EXTENSION: &str = "kv";
+
+/// Extension of the files an entry is written to before being renamed into place.
+const TEMP_EXTENSION: &str = "tmp";
+
+/// 128-bit FNV-1a, used to turn keys into fixed-length file names.
+///
+/// Unlike `DefaultHasher` it is stable across Rust releases, so existing files stay
+/// addressable after an upgrade.
+fn fnv1a_128(bytes: &[u8]) -> u128 {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    bytes.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ *byte as u128).wrapping_mul(PRIME)
+    })
+}
+
+fn io_error(e: std::io::Error) -> StoreError {
+    match e.kind() {
+        // The call was cut short rather than refused; the same call can succeed next time.
+        ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut => {
+            StoreError::Transient(e.to_string())
+        }
+        _ => StoreError::DatabaseError { source: e.into() },
+    }
+}
+
+fn malformed(path: &Path) -> StoreError {
+    StoreError::CodecError {
+        source: format!("Malformed entry file '{}'", path.display()).into(),
+    }
+}
+
+/// The contents of an entry file.
+struct Record {
+    key: String,
+    value: Value,
+    /// Unix time in seconds after which the entry is gone.
+    expires_at: Option<u64>,
+}
+
+impl Record {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, StoreError> {
+        serde_json::to_vec(&json!({
+            "key": self.key,
+            "value": self.value,
+            "expires_at": self.expires_at,
+        }))
+        .map_err(|e| StoreError::SerializationError {
+            key: Some(self.key.clone()),
+            source: e,
+        })
+    }
+
+    fn decode(path: &Path, bytes: &[u8]) -> Result<Self, StoreError> {
+        let Value::Object(mut fields) =
+            serde_json::from_slice(bytes).map_err(|e| StoreError::DeserializationError {
+                key: None,
+                source: e,
+            })?
+        else {
+            return Err(malformed(path));
+        };
+
+        let key = match fields.remove("key") {
+            Some(Value::String(key)) => key,
+            _ => return Err(malformed(path)),
+        };
+        let value = fields.remove("value").ok_or_else(|| malformed(path))?;
+        let expires_at = match fields.remove("expires_at") {
+            None | Some(Value::Null) => None,
+            Some(expires_at) => Some(expires_at.as_u64().ok_or_else(|| malformed(path))?),
+        };
+
+        Ok(Record {
+            key,
+            value,
+            expires_at,
+        })
+    }
+}
+
+/// A store that keeps each entry in its own file under a base directory.
+///
+/// A file is named after a 128-bit hash of its key, so arbitrary keys map to safe,
+/// fixed-length names and can never escape the directory. The file holds a small JSON
+/// document with the key, the value and the expiry time. Writes go to a temporary file
+/// that is then renamed over the entry, so readers never see a half-written value.
+///
+/// Expired entries are skipped by reads and deleted when `get` comes across them. Entries
+/// that are never read again stay on disk until `cleanup_expired` runs.
+///
+/// Read-modify-write operations such as `compare_and_swap` and `increment` are atomic
+/// within one `FsStore`, but not across processes or several stores sharing a directory.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::fs::FsStoreBuilder};
+/// # async {
+/// let store = FsStoreBuilder::new().path("./cache").build().await.unwrap();
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("greeting", "hello").await.unwrap();
+/// # };
+/// ```
+pub struct FsStore {
+    dir: PathBuf,
+    /// Serialises writes, so read-modify-write operations do not interleave.
+    write_lock: Mutex<()>,
+    temp_counter: AtomicU64,
+}
+
+impl FsStore {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            write_lock: Mutex::new(()),
+            temp_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the directory holding the store's files.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Deletes the files of every entry whose TTL has passed.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries removed.
+    pub async fn cleanup_expired(&self) -> Result<u64, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let now = now_secs();
+        let mut removed = 0;
+        for path in self.entry_paths().await? {
+            if let Some(record) = self.read_record(&path).await? {
+                if record.is_expired(now) && self.remove_file(&path).await? {
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn file_name(key: &str) -> String {
+        format!("{:032x}.{}", fnv1a_128(key.as_bytes()), ENTRY_EXTENSION)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(Self::file_name(key))
+    }
+
+    /// Lists the entry files in the directory, ignoring temporary and unrelated files.
+    async fn entry_paths(&self) -> Result<Vec<PathBuf>, StoreError> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await.map_err(io_error)?;
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION) {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Reads the record at `path`, or `None` if the file does not exist.
+    async fn read_record(&self, path: &Path) -> Result<Option<Record>, StoreError> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Record::decode(path, &bytes).map(Some),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    /// Reads the record of `key`, treating an expired entry as absent.
+    async fn read_live(&self, key: &str) -> Result<Option<Record>, StoreError> {
+        let record = self.read_record(&self.entry_path(key)).await?;
+        // A different key in the file can only be a hash collision, so `key` is absent.
+        Ok(record.filter(|record| record.key == key && !record.is_expired(now_secs())))
+    }
+
+    async fn write_record(&self, record: &Record) -> Result<(), StoreError> {
+        let path = self.entry_path(&record.key);
+        let temp_path = self.dir.join(format!(
+            ".{}.{}.{}.{}",
+            Self::file_name(&record.key),
+            std::process::id(),
+            self.temp_counter.fetch_add(1, Ordering::Relaxed),
+            TEMP_EXTENSION
+        ));
+
+        tokio::fs::write(&temp_path, record.encode()?)
+            .await
+            .map_err(io_error)?;
+        if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(io_error(e));
+        }
+        Ok(())
+    }
+
+    /// Deletes the file at `path` and returns whether it existed.
+    async fn remove_file(&self, path: &Path) -> Result<bool, StoreError> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    async fn set_locked(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.write_record(&Record {
+            key: key.to_string(),
+            value,
+            expires_at: expires_at(ttl),
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl Store for FsStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        tokio::fs::create_dir_all(&self.dir).await.map_err(io_error)
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        let metadata = tokio::fs::metadata(&self.dir).await.map_err(io_error)?;
+        if !metadata.is_dir() {
+            return Err(StoreError::ConnectionError(format!(
+                "'{}' is not a directory",
+                self.dir.display()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let path = self.entry_path(key);
+        let Some(record) = self.read_record(&path).await? else {
+            return Ok(None);
+        };
+        if record.key != key {
+            return Ok(None);
+        }
+        if !record.is_expired(now_secs()) {
+            return Ok(Some(record.value));
+        }
+
+        // Re-check under the lock, so a value written in the meantime is not deleted.
+        let _guard = self.write_lock.lock().await;
+        if let Some(record) = self.read_record(&path).await? {
+            if record.key == key && record.is_expired(now_secs()) {
+                self.remove_file(&path).await?;
+            }
+        }
+        Ok(None)
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        Ok(self.read_live(key).await?.is_some())
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let now = now_secs();
+        Ok(self
+            .read_live(key)
+            .await?
+            .and_then(|record| record.expires_at)
+            .map(|expires_at| expires_at.saturating_sub(now)))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.set_locked(key, value, ttl).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        for (key, value, ttl) in entries {
+            self.set_locked(key, value.clone(), *ttl).await?;
+        }
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let current = self.read_live(key).await?.map(|record| record.value);
+        if current != expected {
+            return Ok(false);
+        }
+        self.set_locked(key, new, ttl).await?;
+        Ok(true)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let previous = self.read_live(key).await?.map(|record| record.value);
+        self.set_locked(key, value, ttl).await?;
+        Ok(previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let (current, expires_at) = match self.read_live(key).await? {
+            Some(record) => (
+                record
+                    .value
+                    .as_i64()
+                    .ok_or_else(|| StoreError::NotAnInteger(key.to_string()))?,
+                record.expires_at,
+            ),
+            None => (0, None),
+        };
+        let new = current
+            .checked_add(delta)
+            .ok_or_else(|| StoreError::QueryError("Increment would overflow".to_string()))?;
+
+        // Like redis INCR, the counter keeps its current expiry.
+        self.write_record(&Record {
+            key: key.to_string(),
+            value: Value::from(new),
+            expires_at,
+        })
+        .await?;
+        Ok(new)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let Some(record) = self.read_live(key).await? else {
+            return Ok(false);
+        };
+        self.set_locked(key, record.value, ttl).await?;
+        Ok(true)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let path = self.entry_path(key);
+        if let Some(record) = self.read_record(&path).await? {
+            if record.key == key {
+                self.remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let path = self.entry_path(key);
+        let record = self.read_record(&path).await?;
+        let Some(record) = record.filter(|record| record.key == key) else {
+            return Ok(None);
+        };
+        self.remove_file(&path).await?;
+        Ok((!record.is_expired(now_secs())).then_some(record.value))
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let now = now_secs();
+        let mut removed = 0;
+        for key in keys {
+            let path = self.entry_path(key);
+            let Some(record) = self.read_record(&path).await? else {
+                continue;
+            };
+            if record.key != *key {
+                continue;
+            }
+            // Expired files are deleted too, but they were not present from the caller's view.
+            if self.remove_file(&path).await? && !record.is_expired(now) {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let now = now_secs();
+        let mut keys = Vec::new();
+        for path in self.entry_paths().await? {
+            // The file may have been removed since the directory was listed.
+            let Some(record) = self.read_record(&path).await? else {
+                continue;
+            };
+            if !record.is_expired(now) && prefix.is_none_or(|prefix| record.key.starts_with(prefix))
+            {
+                keys.push(record.key);
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let mut entries = tokio::fs::read_dir(&self.dir).await.map_err(io_error)?;
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == ENTRY_EXTENSION || ext == TEMP_EXTENSION)
+            {
+                self.remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod fs;
+pub use fs::*;
+
+mod builder;
+pub use builder::*;
@@ -0,0 +1,338 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// The field of a stored entry holding the original key.
+const KEY_FIELD: &str = "key";
+
+/// The field of a stored entry holding the value.
+const VALUE_FIELD: &str = "value";
+
+/// Hash functions supported by `HashedStore`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgo {
+    /// SHA-256, stored as 64 lowercase hex characters.
+    Sha256,
+}
+
+impl HashAlgo {
+    fn hash(&self, key: &str) -> String {
+        match self {
+            HashAlgo::Sha256 => Sha256::digest(key.as_bytes())
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        }
+    }
+}
+
+/// A store that keeps every entry under a fixed-length hash of its key.
+///
+/// Long keys, such as URLs, make backend indexes large and slow; hashing bounds every key
+/// the inner store sees to the same short length. Each value is stored as an object
+/// holding the original key and the value, `{"key": ..., "value": ...}`, so the original
+/// keys can still be listed and every read compares them. Should two keys ever hash to
+/// the same value, they share one slot: a write or remove through either key replaces
+/// the other's entry, but a read never returns a value written under a different key.
+///
+/// Since hashes carry no order, `keys`, `scan_prefix`, `range` and `clear_prefix` read
+/// every entry of the inner store to find the original keys. `increment` and `rename`
+/// read the entry and write it back.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{hashed::{HashAlgo, HashedStore}, inmemory::InMemoryStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let store = HashedStore::new(Arc::new(InMemoryStore::new()), HashAlgo::Sha256);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("https://example.com/a/very/long/path", "page").await.unwrap();
+/// # };
+/// ```
+pub struct HashedStore {
+    inner: Arc<dyn Store>,
+    algo: HashAlgo,
+}
+
+impl HashedStore {
+    /// Creates a store that writes to `inner` under keys hashed with `algo`.
+    pub fn new(inner: Arc<dyn Store>, algo: HashAlgo) -> Self {
+        Self { inner, algo }
+    }
+
+    fn hash(&self, key: &str) -> String {
+        self.algo.hash(key)
+    }
+
+    /// The value of a stored entry, or `None` if the entry belongs to another key.
+    fn open(&self, key: &str, stored: Option<Value>) -> Result<Option<Value>, StoreError> {
+        match stored {
+            Some(stored) => {
+                let (original, value) = unwrap_entry(stored)?;
+                Ok((original == key).then_some(value))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// The entry stored for `key`: the original key next to the value.
+fn wrap_entry(key: &str, value: Value) -> Value {
+    json!({ KEY_FIELD: key, VALUE_FIELD: value })
+}
+
+/// Splits a stored entry back into the original key and the value.
+fn unwrap_entry(stored: Value) -> Result<(String, Value), StoreError> {
+    let not_hashed = || StoreError::CodecError {
+        source: "Value was not written by a HashedStore".into(),
+    };
+    let Value::Object(mut object) = stored else {
+        return Err(not_hashed());
+    };
+    match (object.remove(KEY_FIELD), object.remove(VALUE_FIELD)) {
+        (Some(Value::String(key)), Some(value)) => Ok((key, value)),
+        _ => Err(not_hashed()),
+    }
+}
+
+#[async_trait]
+impl Store for HashedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.open(key, self.inner.get(&self.hash(key)).await?)
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let hashed: Vec<String> = keys.iter().map(|key| self.hash(key)).collect();
+        let hashed: Vec<&str> = hashed.iter().map(String::as_str).collect();
+        self.inner
+            .get_many(&hashed)
+            .await?
+            .into_iter()
+            .zip(keys)
+            .map(|(stored, key)| self.open(key, stored))
+            .collect()
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        match self.inner.get_with_meta(&self.hash(key)).await? {
+            Some((stored, meta)) => Ok(self.open(key, Some(stored))?.map(|value| (value, meta))),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(&self.hash(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner
+            .set(&self.hash(key), wrap_entry(key, value), ttl)
+            .await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner
+            .set_with_ttl(&self.hash(key), wrap_entry(key, value), ttl)
+            .await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let hashed: Vec<String> = entries.iter().map(|(key, _, _)| self.hash(key)).collect();
+        let entries: Vec<(&str, Value, Option<u64>)> = entries
+            .iter()
+            .zip(&hashed)
+            .map(|((key, value, ttl), hashed)| {
+                (hashed.as_str(), wrap_entry(key, value.clone()), *ttl)
+            })
+            .collect();
+        self.inner.set_many(&entries).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let expected = expected.map(|value| wrap_entry(key, value));
+        self.inner
+            .compare_and_swap(&self.hash(key), expected, wrap_entry(key, new), ttl)
+            .await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let previous = self
+            .inner
+            .swap(&self.hash(key), wrap_entry(key, value), ttl)
+            .await?;
+        self.open(key, previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let hashed = self.hash(key);
+        loop {
+            let stored = self.inner.get(&hashed).await?;
+            let current = self.open(key, stored.clone())?;
+            let ttl = match current {
+                Some(_) => self.inner.get_ttl(&hashed).await?,
+                None => None,
+            };
+            let current = match current {
+                Some(value) => value
+                    .as_i64()
+                    .ok_or_else(|| StoreError::NotAnInteger(key.to_string()))?,
+                None => 0,
+            };
+            let new = current
+                .checked_add(delta)
+                .ok_or_else(|| StoreError::QueryError("Increment would overflow".to_string()))?;
+            if self
+                .inner
+                .compare_and_swap(&hashed, stored, wrap_entry(key, Value::from(new)), ttl)
+                .await?
+            {
+                return Ok(new);
+            }
+        }
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(&self.hash(key), ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(&self.hash(key), by_secs).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(&self.hash(key)).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let previous = self.inner.get_and_remove(&self.hash(key)).await?;
+        self.open(key, previous)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let hashed: Vec<String> = keys.iter().map(|key| self.hash(key)).collect();
+        let hashed: Vec<&str> = hashed.iter().map(String::as_str).collect();
+        self.inner.remove_many(&hashed).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let ops: Vec<BatchOp> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => BatchOp::Set {
+                    key: self.hash(key),
+                    value: wrap_entry(key, value.clone()),
+                    ttl: *ttl,
+                },
+                BatchOp::Remove { key } => BatchOp::Remove {
+                    key: self.hash(key),
+                },
+            })
+            .collect();
+        self.inner.apply_batch(&ops).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        // The original key is part of the stored entry, so the entry is rewritten.
+        let hashed_from = self.hash(from);
+        let Some(value) = self.open(from, self.inner.get(&hashed_from).await?)? else {
+            return Ok(false);
+        };
+        if from == to {
+            return Ok(true);
+        }
+
+        let ttl = self.inner.get_ttl(&hashed_from).await?;
+        self.inner
+            .apply_batch(&[
+                BatchOp::Set {
+                    key: self.hash(to),
+                    value: wrap_entry(to, value),
+                    ttl,
+                },
+                BatchOp::Remove { key: hashed_from },
+            ])
+            .await?;
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.scan_prefix(prefix.unwrap_or(""))
+            .map_ok(|(key, _)| key)
+            .try_collect()
+            .await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.scan_prefix("")
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let prefix = prefix.to_string();
+        Box::pin(self.inner.iter().filter_map(move |entry| {
+            let entry = entry
+                .and_then(|(_, stored)| unwrap_entry(stored))
+                .map(|(key, value)| key.starts_with(&prefix).then_some((key, value)))
+                .transpose();
+            futures::future::ready(entry)
+        }))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        Box::pin(self.inner.export().and_then(|(_, stored, expires_at)| {
+            futures::future::ready(
+                unwrap_entry(stored).map(|(key, value)| (key, value, expires_at)),
+            )
+        }))
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let keys = self.keys(Some(prefix)).await?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.remove_many(&keys).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
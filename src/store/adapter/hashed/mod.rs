@@ -0,0 +1,2 @@
+mod hashed;
+pub use hashed::*;
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use super::InMemoryStore;
+
+/// Builder for creating an `InMemoryStore`.
+///
+/// This builder allows for configuring an `InMemoryStore` with a capacity bound and a
+/// periodic sweep of expired entries. `InMemoryStore::new` is equivalent to a builder
+/// with no options set.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::adapter::inmemory::InMemoryStoreBuilder;
+/// # use std::time::Duration;
+/// # #[tokio::main]
+/// # async fn main(){
+/// let store = InMemoryStoreBuilder::new()
+///     .max_entries(10_000)
+///     .cleanup_interval(Duration::from_secs(60))
+///     .build();
+///  }
+/// ```
+pub struct InMemoryStoreBuilder {
+    max_entries: Option<usize>,
+    shards: Option<usize>,
+    cleanup_interval: Option<Duration>,
+}
+
+impl InMemoryStoreBuilder {
+    pub fn new() -> Self {
+        Self {
+            max_entries: None,
+            shards: None,
+            cleanup_interval: None,
+        }
+    }
+
+    /// Bounds the number of entries held by the store.
+    ///
+    /// Once an insert takes the store over `max`, the least recently used entries are
+    /// evicted until it is back at capacity. Writes, `get` and `get_many` mark an entry as
+    /// used. If not set, the store grows without bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of entries.
+    pub fn max_entries(mut self, max: usize) -> Self {
+        self.max_entries = Some(max);
+        self
+    }
+
+    /// Splits the store into `count` shards, each behind a lock of its own.
+    ///
+    /// Tasks working on keys in different shards run in parallel, which matters when
+    /// many tasks share the store, for example as the near tier of a `TieredStore`.
+    /// Defaults to 16 shards, or to a single shard when `max_entries` is set.
+    ///
+    /// With `max_entries`, each shard evicts its own least recently used entries once it
+    /// holds more than `max_entries / count`, rounded up. Eviction is then only least
+    /// recently used within a shard, and the store may hold up to `count - 1` entries
+    /// over `max_entries`. A count of zero is treated as one.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of shards.
+    pub fn shards(mut self, count: usize) -> Self {
+        self.shards = Some(count);
+        self
+    }
+
+    /// Enables a background task that drops expired entries every `interval`.
+    ///
+    /// See `InMemoryStore::with_cleanup_interval`. Building with this option set must
+    /// happen within a Tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two sweeps.
+    pub fn cleanup_interval(mut self, interval: Duration) -> Self {
+        self.cleanup_interval = Some(interval);
+        self
+    }
+
+    /// Builds the `InMemoryStore` based on the provided configurations.
+    pub fn build(self) -> InMemoryStore {
+        let store = InMemoryStore::with_options(self.max_entries, self.shards);
+        match self.cleanup_interval {
+            Some(interval) => store.with_cleanup_interval(interval),
+            None => store,
+        }
+    }
+}
+
+impl Default for InMemoryStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
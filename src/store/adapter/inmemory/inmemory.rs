@@ -1,19 +1,321 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::{BuildHasher, RandomState},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
 use serde_json::Value;
-use tokio::sync::Mutex;
+use tokio::{
+    sync::{Mutex, MutexGuard},
+    task::JoinHandle,
+};
 
-use crate::{Store, StoreError};
+use crate::{
+    utils::{now_secs, ITER_BATCH_SIZE},
+    BatchOp, EntryStream, ExportEntry, ExportStream, Store, StoreError, Ttl,
+};
 
+/// Shards of a store built without `max_entries`, when the shard count is not set.
+pub(crate) const DEFAULT_SHARDS: usize = 16;
+
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// When an entry written now with `ttl` expires. A TTL too far out for `Instant` is as
+/// good as none, so it is stored as no expiry instead of overflowing.
+fn expires_at(ttl: Duration) -> Option<Instant> {
+    Instant::now().checked_add(ttl)
+}
+
+/// The entries of an `InMemoryStore` together with their recency order.
+///
+/// `recency` maps each entry's `last_used` tick to its key, so its first element is
+/// always the least recently used entry.
+struct Db {
+    entries: HashMap<String, Entry>,
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+    max_entries: Option<usize>,
+}
+
+impl Db {
+    fn new(max_entries: Option<usize>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            max_entries,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Returns the entry stored under `key` and marks it as recently used, dropping it
+    /// first if its TTL has passed.
+    fn get_live(&mut self, key: &str) -> Option<&mut Entry> {
+        if self.entries.get(key)?.is_expired(Instant::now()) {
+            self.remove(key);
+            return None;
+        }
+
+        let tick = self.tick();
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(tick, key.to_string());
+        entry.last_used = tick;
+        Some(entry)
+    }
+
+    /// Returns the entry stored under `key` without touching its recency.
+    fn peek_live(&self, key: &str) -> Option<&Entry> {
+        self.entries
+            .get(key)
+            .filter(|entry| !entry.is_expired(Instant::now()))
+    }
+
+    /// Inserts or replaces `key`, expiring after `ttl` seconds when one is given.
+    fn insert(&mut self, key: &str, value: Value, ttl: Option<u64>) {
+        self.insert_for(key, value, ttl.map(Duration::from_secs));
+    }
+
+    /// Inserts or replaces `key`, then evicts least recently used entries while the
+    /// store holds more than `max_entries`.
+    fn insert_for(&mut self, key: &str, value: Value, ttl: Option<Duration>) {
+        let tick = self.tick();
+        let entry = Entry {
+            value,
+            expires_at: ttl.and_then(expires_at),
+            last_used: tick,
+        };
+        if let Some(previous) = self.entries.insert(key.to_string(), entry) {
+            self.recency.remove(&previous.last_used);
+        }
+        self.recency.insert(tick, key.to_string());
+
+        if let Some(max_entries) = self.max_entries {
+            while self.entries.len() > max_entries {
+                let Some((_, oldest)) = self.recency.pop_first() else {
+                    break;
+                };
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    /// Removes every expired entry and returns how many were dropped.
+    fn remove_expired(&mut self) -> u64 {
+        let now = Instant::now();
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| !entry.is_expired(now));
+        let entries = &self.entries;
+        self.recency.retain(|_, key| entries.contains_key(key));
+        (before - self.entries.len()) as u64
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+/// An in-process store backed by a `HashMap`.
+///
+/// Values written with a TTL expire lazily: reads skip and drop entries whose TTL has
+/// passed. Entries that are never read again stay in memory until `cleanup_expired` runs,
+/// either manually or periodically through `with_cleanup_interval`.
+///
+/// When built with `InMemoryStoreBuilder::max_entries`, the store evicts the least
+/// recently used entries once an insert takes it over capacity. Writes, `get` and
+/// `get_many` count as a use; `contains_key`, `keys`, `iter` and `len` do not.
+///
+/// Keys are spread over shards, each behind a lock of its own, so that tasks working on
+/// different keys do not wait for one another. Operations on several keys, such as
+/// `get_many` or `apply_batch`, lock every shard they touch and stay atomic. See
+/// `InMemoryStoreBuilder::shards` for how sharding interacts with `max_entries`.
 pub struct InMemoryStore {
-    db: Mutex<HashMap<String, Value>>,
+    shards: Arc<Vec<Mutex<Db>>>,
+    hasher: RandomState,
+    cleanup_task: Option<JoinHandle<()>>,
+}
+
+/// The shards locked by `InMemoryStore::lock`, looked up by key.
+struct Locked<'a> {
+    store: &'a InMemoryStore,
+    guards: Vec<Option<MutexGuard<'a, Db>>>,
+}
+
+impl Locked<'_> {
+    /// The shard holding `key`, which must be one of the keys the shards were locked for.
+    fn db(&mut self, key: &str) -> &mut Db {
+        self.guards[self.store.index(key)]
+            .as_mut()
+            .expect("the shard of every key is locked")
+    }
 }
 
 impl InMemoryStore {
     pub fn new() -> Self {
+        Self::with_options(None, None)
+    }
+
+    /// Creates a store split into `shards`, or into the default number of shards: one
+    /// when `max_entries` is set, to keep the eviction order exact, and `DEFAULT_SHARDS`
+    /// otherwise. Each shard holds at most its share of `max_entries`, rounded up.
+    pub(crate) fn with_options(max_entries: Option<usize>, shards: Option<usize>) -> Self {
+        let shards = shards
+            .unwrap_or(if max_entries.is_some() {
+                1
+            } else {
+                DEFAULT_SHARDS
+            })
+            .max(1);
+        let max_entries = max_entries.map(|max| max.div_ceil(shards));
         InMemoryStore {
-            db: Mutex::new(HashMap::new()),
+            shards: Arc::new(
+                (0..shards)
+                    .map(|_| Mutex::new(Db::new(max_entries)))
+                    .collect(),
+            ),
+            hasher: RandomState::new(),
+            cleanup_task: None,
+        }
+    }
+
+    /// The index of the shard holding `key`.
+    fn index(&self, key: &str) -> usize {
+        if self.shards.len() == 1 {
+            return 0;
+        }
+        (self.hasher.hash_one(key) % self.shards.len() as u64) as usize
+    }
+
+    /// Locks the shard holding `key`.
+    async fn shard(&self, key: &str) -> MutexGuard<'_, Db> {
+        self.shards[self.index(key)].lock().await
+    }
+
+    /// Locks the shards holding `keys` together. Shards are always locked in index order,
+    /// so concurrent calls cannot deadlock.
+    async fn lock<'k>(&self, keys: impl IntoIterator<Item = &'k str>) -> Locked<'_> {
+        let indexes: BTreeSet<usize> = keys.into_iter().map(|key| self.index(key)).collect();
+        let mut guards: Vec<Option<MutexGuard<'_, Db>>> =
+            self.shards.iter().map(|_| None).collect();
+        for index in indexes {
+            guards[index] = Some(self.shards[index].lock().await);
+        }
+        Locked {
+            store: self,
+            guards,
+        }
+    }
+
+    /// Starts a background task that calls `cleanup_expired` every `interval`.
+    ///
+    /// The task is stopped when the store is dropped. Must be called from within a
+    /// Tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two sweeps.
+    pub fn with_cleanup_interval(mut self, interval: Duration) -> Self {
+        let shards = self.shards.clone();
+        self.cleanup_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; wait a full interval before sweeping.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let mut removed = 0;
+                for shard in shards.iter() {
+                    removed += shard.lock().await.remove_expired();
+                }
+                log::debug!("Removed {} expired entries", removed);
+            }
+        }));
+        self
+    }
+
+    /// Drops every entry whose TTL has passed.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries removed.
+    pub async fn cleanup_expired(&self) -> u64 {
+        let mut removed = 0;
+        for shard in self.shards.iter() {
+            removed += shard.lock().await.remove_expired();
+        }
+        removed
+    }
+
+    /// Streams the live entries whose key starts with `prefix` along with their absolute
+    /// expiry.
+    fn scan(&self, prefix: &str) -> ExportStream<'_> {
+        let prefix = prefix.to_string();
+        // Same batching as the default, but peeking so iteration does not reorder the LRU.
+        let batches = stream::try_unfold(None, move |keys: Option<std::vec::IntoIter<String>>| {
+            let prefix = prefix.clone();
+            async move {
+                let mut keys = match keys {
+                    Some(keys) => keys,
+                    None => self.keys(Some(&prefix)).await?.into_iter(),
+                };
+                let batch: Vec<String> = keys.by_ref().take(ITER_BATCH_SIZE).collect();
+                if batch.is_empty() {
+                    return Ok(None);
+                }
+
+                let mut locked = self.lock(batch.iter().map(String::as_str)).await;
+                let (now, now_secs) = (Instant::now(), now_secs());
+                let entries: Vec<Result<ExportEntry, StoreError>> = batch
+                    .into_iter()
+                    .filter_map(|key| {
+                        let entry = locked.db(&key).peek_live(&key)?;
+                        let expires_at = entry.expires_at.map(|expires_at| {
+                            let ttl = expires_at.duration_since(now).as_millis().div_ceil(1000);
+                            now_secs + ttl as u64
+                        });
+                        Some(Ok((key, entry.value.clone(), expires_at)))
+                    })
+                    .collect();
+                Ok::<_, StoreError>(Some((stream::iter(entries), Some(keys))))
+            }
+        });
+        Box::pin(batches.try_flatten())
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InMemoryStore {
+    fn drop(&mut self) {
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
         }
     }
 }
@@ -24,34 +326,289 @@ impl Store for InMemoryStore {
         Ok(())
     }
 
+    async fn health_check(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let db_lock = self.db.lock().await;
-        Ok(db_lock.get(key).cloned())
+        let mut db_lock = self.shard(key).await;
+        Ok(db_lock.get_live(key).map(|entry| entry.value.clone()))
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        let db_lock = self.shard(key).await;
+        Ok(db_lock.peek_live(key).is_some())
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let mut locked = self.lock(keys.iter().copied()).await;
+        Ok(keys
+            .iter()
+            .map(|key| {
+                locked
+                    .db(key)
+                    .get_live(key)
+                    .map(|entry| entry.value.clone())
+            })
+            .collect())
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let db_lock = self.shard(key).await;
+        let now = Instant::now();
+        Ok(db_lock
+            .peek_live(key)
+            .and_then(|entry| entry.expires_at)
+            .map(|expires_at| expires_at.duration_since(now).as_millis().div_ceil(1000) as u64))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        let mut db_lock = self.shard(key).await;
+        db_lock.insert(key, value, ttl);
+        Ok(())
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        let mut db_lock = self.shard(key).await;
+        db_lock.insert_for(key, value, Some(ttl.as_duration()));
+        Ok(())
     }
 
-    async fn set(&self, key: &str, value: Value, _ttl: Option<u64>) -> Result<(), StoreError> {
-        let mut db_lock = self.db.lock().await;
-        db_lock.insert(key.to_string(), value.clone());
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let mut locked = self.lock(entries.iter().map(|(key, _, _)| *key)).await;
+        for (key, value, ttl) in entries {
+            locked.db(key).insert(key, value.clone(), *ttl);
+        }
         Ok(())
     }
 
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        if db_lock.peek_live(key).map(|entry| &entry.value) != expected.as_ref() {
+            return Ok(false);
+        }
+        db_lock.insert(key, new, ttl);
+        Ok(true)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        let now = Instant::now();
+        let previous = db_lock
+            .remove(key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.value);
+        db_lock.insert(key, value, ttl);
+        Ok(previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        let current = match db_lock.get_live(key) {
+            Some(entry) => entry
+                .value
+                .as_i64()
+                .ok_or_else(|| StoreError::NotAnInteger(key.to_string()))?,
+            None => 0,
+        };
+        let new = current
+            .checked_add(delta)
+            .ok_or_else(|| StoreError::QueryError("Increment would overflow".to_string()))?;
+        match db_lock.get_live(key) {
+            // Like redis INCR, the counter keeps its current expiry.
+            Some(entry) => entry.value = Value::from(new),
+            None => db_lock.insert(key, Value::from(new), None),
+        }
+        Ok(new)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        match db_lock.get_live(key) {
+            Some(entry) => {
+                entry.expires_at = ttl.and_then(|ttl| expires_at(Duration::from_secs(ttl)));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        match db_lock.get_live(key) {
+            Some(entry) => {
+                // An expiry too far out for `Instant` is as good as none.
+                entry.expires_at = entry
+                    .expires_at
+                    .and_then(|at| at.checked_add(Duration::from_secs(by_secs)));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let mut db_lock = self.db.lock().await;
+        let mut db_lock = self.shard(key).await;
         db_lock.remove(key);
         Ok(())
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let mut db_lock = self.db.lock().await;
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut db_lock = self.shard(key).await;
+        let now = Instant::now();
+        Ok(db_lock
+            .remove(key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.value))
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let mut locked = self.lock(keys.iter().copied()).await;
+        let now = Instant::now();
+        let mut removed = 0;
         for key in keys {
-            db_lock.remove(&key.to_string());
+            if locked
+                .db(key)
+                .remove(key)
+                .is_some_and(|entry| !entry.is_expired(now))
+            {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        // Nothing below can fail, so holding the locks makes the batch all-or-nothing.
+        let mut locked = self
+            .lock(ops.iter().map(|op| match op {
+                BatchOp::Set { key, .. } | BatchOp::Remove { key } => key.as_str(),
+            }))
+            .await;
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => locked.db(key).insert(key, value.clone(), *ttl),
+                BatchOp::Remove { key } => {
+                    locked.db(key).remove(key);
+                }
+            }
         }
         Ok(())
     }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let mut locked = self.lock([from, to]).await;
+        let Some(entry) = locked
+            .db(from)
+            .remove(from)
+            .filter(|entry| !entry.is_expired(Instant::now()))
+        else {
+            return Ok(false);
+        };
+
+        let db = locked.db(to);
+        db.insert(to, entry.value, None);
+        if let Some(renamed) = db.entries.get_mut(to) {
+            renamed.expires_at = entry.expires_at;
+        }
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let mut keys = Vec::new();
+        for shard in self.shards.iter() {
+            let db_lock = shard.lock().await;
+            let now = Instant::now();
+            keys.extend(
+                db_lock
+                    .entries
+                    .iter()
+                    .filter(|(key, entry)| {
+                        !entry.is_expired(now)
+                            && prefix.is_none_or(|prefix| key.starts_with(prefix))
+                    })
+                    .map(|(key, _)| key.clone()),
+            );
+        }
+        Ok(keys)
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(self.export().map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        Box::pin(self.scan(prefix).map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.scan("")
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let mut len = 0;
+        for shard in self.shards.iter() {
+            let db_lock = shard.lock().await;
+            let now = Instant::now();
+            len += db_lock
+                .entries
+                .values()
+                .filter(|entry| !entry.is_expired(now))
+                .count();
+        }
+        Ok(len)
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let mut removed = 0;
+        for shard in self.shards.iter() {
+            let mut db_lock = shard.lock().await;
+            let now = Instant::now();
+            let keys: Vec<String> = db_lock
+                .entries
+                .keys()
+                .filter(|key| key.starts_with(prefix))
+                .cloned()
+                .collect();
+            for key in keys {
+                if db_lock
+                    .remove(&key)
+                    .is_some_and(|entry| !entry.is_expired(now))
+                {
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
-        let mut db_lock = self.db.lock().await;
-        db_lock.clear();
+        // Holds every shard at once, so no write lands halfway through.
+        let mut guards = Vec::with_capacity(self.shards.len());
+        for shard in self.shards.iter() {
+            guards.push(shard.lock().await);
+        }
+        for db_lock in guards.iter_mut() {
+            db_lock.clear();
+        }
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        if let Some(task) = &self.cleanup_task {
+            task.abort();
+        }
         Ok(())
     }
 }
@@ -1,2 +1,5 @@
 mod inmemory;
 pub use inmemory::*;
+
+mod builder;
+pub use builder::*;
@@ -0,0 +1,285 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// Receives the outcome of every call made through a `MetricsStore`.
+///
+/// Implement it to bridge keyv to a metrics backend such as `prometheus` or `metrics`.
+/// It is called synchronously after each operation, so it should only record the
+/// sample and return.
+///
+/// The operation names are those of the `Store` methods: `initialize`, `health_check`,
+/// `get`, `contains_key`, `get_many`, `get_with_meta`, `get_ttl`, `set`, `set_with_ttl`,
+/// `set_bytes`, `get_bytes`, `set_many`, `set_group`, `compare_and_swap`, `set_nx`,
+/// `swap`, `increment`, `expire`, `extend_ttl`, `get_field`, `set_field`,
+/// `set_json_path`, `remove_field`, `remove`, `get_and_remove`, `remove_many`,
+/// `apply_batch`, `rename`, `keys`, `range`, `import`, `len`, `is_empty`, `clear`,
+/// `clear_prefix`, `optimize` and `close`. Streams returned by `iter`, `scan_prefix` and
+/// `export` are not observed; `import` is timed until the whole stream is written.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, StoreError, adapter::metrics::Metrics};
+/// # use std::{sync::Arc, time::Duration};
+/// struct LogMetrics;
+///
+/// impl Metrics for LogMetrics {
+///     fn on_operation(&self, op: &str, duration: Duration, result: Result<(), &StoreError>) {
+///         println!("{} took {:?} (ok: {})", op, duration, result.is_ok());
+///     }
+/// }
+///
+/// # async {
+/// let keyv = Keyv::default().with_metrics(Arc::new(LogMetrics));
+/// keyv.set("number", 42).await.unwrap();
+/// # };
+/// ```
+pub trait Metrics: Send + Sync {
+    /// Records one call to the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `op` - The name of the `Store` method that was called.
+    /// * `duration` - How long the call took.
+    /// * `result` - `Ok(())` if the call succeeded, or the error it failed with.
+    fn on_operation(&self, op: &str, duration: Duration, result: Result<(), &StoreError>);
+}
+
+/// A store that reports the name, latency and outcome of every call to an inner store
+/// to a `Metrics` implementation.
+pub struct MetricsStore {
+    inner: Arc<dyn Store>,
+    metrics: Arc<dyn Metrics>,
+}
+
+impl MetricsStore {
+    /// Creates a store that reports calls to `inner` to `metrics`.
+    pub fn new(inner: Arc<dyn Store>, metrics: Arc<dyn Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    async fn observe<T>(
+        &self,
+        op: &str,
+        operation: impl Future<Output = Result<T, StoreError>>,
+    ) -> Result<T, StoreError> {
+        let start = Instant::now();
+        let result = operation.await;
+        self.metrics
+            .on_operation(op, start.elapsed(), result.as_ref().map(|_| ()));
+        result
+    }
+}
+
+#[async_trait]
+impl Store for MetricsStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.observe("initialize", self.inner.initialize()).await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.observe("health_check", self.inner.health_check())
+            .await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.observe("get", self.inner.get(key)).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.observe("contains_key", self.inner.contains_key(key))
+            .await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.observe("get_many", self.inner.get_many(keys)).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.observe("get_with_meta", self.inner.get_with_meta(key))
+            .await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.observe("get_ttl", self.inner.get_ttl(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.observe("set", self.inner.set(key, value, ttl)).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.observe("set_with_ttl", self.inner.set_with_ttl(key, value, ttl))
+            .await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.observe("set_bytes", self.inner.set_bytes(key, value, ttl))
+            .await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.observe("get_bytes", self.inner.get_bytes(key)).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.observe("set_many", self.inner.set_many(entries)).await
+    }
+
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.observe("set_group", self.inner.set_group(entries, ttl))
+            .await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.observe(
+            "compare_and_swap",
+            self.inner.compare_and_swap(key, expected, new, ttl),
+        )
+        .await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.observe("set_nx", self.inner.set_nx(key, value, ttl))
+            .await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.observe("swap", self.inner.swap(key, value, ttl)).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.observe("increment", self.inner.increment(key, delta))
+            .await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.observe("expire", self.inner.expire(key, ttl)).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.observe("extend_ttl", self.inner.extend_ttl(key, by_secs))
+            .await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.observe("get_field", self.inner.get_field(key, field))
+            .await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.observe("set_field", self.inner.set_field(key, field, value, ttl))
+            .await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.observe("set_json_path", self.inner.set_json_path(key, path, value))
+            .await
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.observe("remove_field", self.inner.remove_field(key, field))
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.observe("remove", self.inner.remove(key)).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.observe("get_and_remove", self.inner.get_and_remove(key))
+            .await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.observe("remove_many", self.inner.remove_many(keys))
+            .await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.observe("apply_batch", self.inner.apply_batch(ops))
+            .await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.observe("rename", self.inner.rename(from, to)).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.observe("keys", self.inner.keys(prefix)).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.observe("range", self.inner.range(start, end)).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn import(&self, entries: ExportStream<'_>) -> Result<u64, StoreError> {
+        self.observe("import", self.inner.import(entries)).await
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.observe("len", self.inner.len()).await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.observe("is_empty", self.inner.is_empty()).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.observe("clear", self.inner.clear()).await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.observe("clear_prefix", self.inner.clear_prefix(prefix))
+            .await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.observe("optimize", self.inner.optimize()).await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.observe("close", self.inner.close()).await
+    }
+}
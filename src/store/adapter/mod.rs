@@ -13,4 +13,27 @@ pub mod mongodb;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 
+#[cfg(feature = "fs")]
+pub mod fs;
+
+#[cfg(feature = "compression")]
+pub mod compressed;
+
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+
+#[cfg(feature = "key-hashing")]
+pub mod hashed;
+
+pub mod buffered;
+pub mod events;
 pub mod inmemory;
+pub mod metrics;
+pub mod namespaced;
+pub mod readonly;
+pub mod retrying;
+pub mod sharded;
+pub mod tiered;
+pub mod timeout;
+pub mod validated;
+pub mod verified;
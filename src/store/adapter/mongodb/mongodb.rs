@@ -1,12 +1,19 @@
 use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
 use mongodb::{
-    bson::{doc, Bson, Document},
-    Client, Collection,
+    bson::{doc, Bson, DateTime, Document},
+    error::{ErrorKind, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR},
+    options::{FindOneAndReplaceOptions, IndexOptions, ReturnDocument},
+    Client, Collection, IndexModel,
 };
 use serde_json::Value;
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
-use crate::{Store, StoreError};
+use crate::{utils::ITER_BATCH_SIZE, EntryStream, Store, StoreError};
 
 pub struct MongoStore {
     pub(crate) client: Arc<Client>,
@@ -22,41 +29,174 @@ impl MongoStore {
     }
 }
 
+/// Maps a failed operation to `StoreError::Transient` when retrying it may succeed and to
+/// `StoreError::QueryError` otherwise.
+///
+/// Errors the driver labels as transient or retryable, network errors and failures to
+/// select a server, such as during a replica set election, are transient.
+fn query_error(e: mongodb::error::Error) -> StoreError {
+    let transient = e.contains_label(TRANSIENT_TRANSACTION_ERROR)
+        || e.contains_label(RETRYABLE_WRITE_ERROR)
+        || matches!(
+            *e.kind,
+            ErrorKind::Io(_)
+                | ErrorKind::ConnectionPoolCleared { .. }
+                | ErrorKind::ServerSelection { .. }
+        );
+    if transient {
+        StoreError::Transient(e.to_string())
+    } else {
+        StoreError::QueryError(e.to_string())
+    }
+}
+
+/// Restricts `filter` to documents whose `expires_at` is unset or still in the future.
+///
+/// MongoDB's TTL monitor only runs about once a minute, so reads must not rely on it.
+fn live(mut filter: Document) -> Document {
+    filter.insert(
+        "$or",
+        vec![
+            doc! { "expires_at": Bson::Null },
+            doc! { "expires_at": { "$gt": DateTime::now() } },
+        ],
+    );
+    filter
+}
+
+/// The expiry date of an entry stored now with `ttl` seconds to live, saturating at the
+/// latest date BSON can represent.
+fn expiry(ttl: u64) -> DateTime {
+    SystemTime::now()
+        .checked_add(Duration::from_secs(ttl))
+        .map_or(DateTime::MAX, DateTime::from_system_time)
+}
+
+/// Escapes the characters that have a special meaning in a MongoDB `$regex`.
+fn escape_regex(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[async_trait]
 impl Store for MongoStore {
     async fn initialize(&self) -> Result<(), StoreError> {
         // MongoDB creates databases and collections automatically when you insert data,
-        // so explicit creation is not needed.
-        Ok(())
+        // so only the TTL index has to be set up. Creating an identical index again is a
+        // no-op, which keeps repeated calls safe.
+        let index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("expires_at_ttl".to_string())
+                    .expire_after(Duration::ZERO)
+                    .build(),
+            )
+            .build();
+
+        self.get_collection()
+            .create_index(index, None)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::QueryError(format!("Failed to create the TTL index: {}", e)))
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.client
+            .database(&self.database_name)
+            .run_command(doc! { "ping": 1 }, None)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))
     }
 
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
         let coll = self.get_collection();
-        let filter = doc! { "key": key };
-        let result = coll
-            .find_one(filter, None)
-            .await
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        let filter = live(doc! { "key": key });
+        let result = coll.find_one(filter, None).await.map_err(query_error)?;
 
         result
             .map_or(Ok(None), |doc| {
                 doc.get("value")
                     .and_then(Bson::as_str)
-                    .map(|s| serde_json::from_str::<Value>(s))
+                    .map(serde_json::from_str::<Value>)
                     .transpose()
             })
-            .map_err(|e| StoreError::SerializationError { source: e.into() })
+            .map_err(|e| StoreError::DeserializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })
     }
 
-    async fn set(&self, key: &str, value: Value, _: Option<u64>) -> Result<(), StoreError> {
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
         let coll = self.get_collection();
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let options = mongodb::options::CountOptions::builder().limit(1).build();
+        let count = coll
+            .count_documents(live(doc! { "key": key }), options)
+            .await
+            .map_err(query_error)?;
 
-        let doc = doc! {
+        Ok(count > 0)
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let coll = self.get_collection();
+        let result = coll
+            .find_one(live(doc! { "key": key }), None)
+            .await
+            .map_err(query_error)?;
+
+        let now = DateTime::now().timestamp_millis();
+        Ok(result
+            .and_then(|doc| doc.get_datetime("expires_at").ok().copied())
+            .map(|expires_at| ((expires_at.timestamp_millis() - now).max(0) as u64).div_ceil(1000)))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let coll = self.get_collection();
+        let mut cursor = coll
+            .find(live(doc! { "key": { "$in": keys } }), None)
+            .await
+            .map_err(query_error)?;
+
+        let mut found: HashMap<String, Value> = HashMap::new();
+        while cursor.advance().await.map_err(query_error)? {
+            let doc = cursor.deserialize_current().map_err(query_error)?;
+            if let (Ok(key), Ok(value)) = (doc.get_str("key"), doc.get_str("value")) {
+                let value = serde_json::from_str::<Value>(value).map_err(|e| {
+                    StoreError::DeserializationError {
+                        key: Some(key.to_string()),
+                        source: e,
+                    }
+                })?;
+                found.insert(key.to_string(), value);
+            }
+        }
+
+        Ok(keys.iter().map(|key| found.get(*key).cloned()).collect())
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        let coll = self.get_collection();
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+
+        let mut doc = doc! {
             "key": key,
             "value": value_str
         };
+        if let Some(ttl) = ttl {
+            doc.insert("expires_at", expiry(ttl));
+        }
 
         let replace_options = mongodb::options::ReplaceOptions::builder()
             .upsert(true)
@@ -66,29 +206,178 @@ impl Store for MongoStore {
             .await
             .map(|replace_result| {
                 if replace_result.upserted_id.is_some() {
-                    log::info!("A new document was upserted");
+                    log::trace!("A new document was upserted");
                 }
                 ()
             })
-            .map_err(|e| {
-                StoreError::QueryError(format!("Failed to set the value: {}", e.to_string()))
+            .map_err(query_error)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let coll = self.get_collection();
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+
+        let mut doc = doc! {
+            "key": key,
+            "value": value_str
+        };
+        if let Some(ttl) = ttl {
+            doc.insert("expires_at", expiry(ttl));
+        }
+
+        let options = FindOneAndReplaceOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::Before)
+            .build();
+        let previous = coll
+            .find_one_and_replace(doc! { "key": key }, doc, options)
+            .await
+            .map_err(query_error)?;
+
+        // The replaced document may have expired without the TTL monitor removing it yet.
+        let now = DateTime::now();
+        previous
+            .filter(|doc| doc.get_datetime("expires_at").map_or(true, |at| *at > now))
+            .and_then(|doc| doc.get_str("value").ok().map(serde_json::from_str::<Value>))
+            .transpose()
+            .map_err(|e| StoreError::DeserializationError {
+                key: Some(key.to_string()),
+                source: e,
             })
     }
 
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let coll = self.get_collection();
+        let update = match ttl {
+            Some(ttl) => {
+                doc! { "$set": { "expires_at": expiry(ttl) } }
+            }
+            None => doc! { "$unset": { "expires_at": "" } },
+        };
+
+        coll.update_one(live(doc! { "key": key }), update, None)
+            .await
+            .map(|result| result.matched_count > 0)
+            .map_err(query_error)
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
         let coll = self.get_collection();
         coll.delete_one(doc! { "key": key }, None)
             .await
             .map(|_| ())
-            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))
+            .map_err(query_error)
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
         let coll = self.get_collection();
-        coll.delete_many(doc! { "key": { "$in": keys } }, None)
+        let result = coll
+            .find_one_and_delete(live(doc! { "key": key }), None)
             .await
-            .map(|_| ())
-            .map_err(|_| StoreError::QueryError("Failed to remove the keys".to_string()))
+            .map_err(query_error)?;
+
+        result
+            .map_or(Ok(None), |doc| {
+                doc.get("value")
+                    .and_then(Bson::as_str)
+                    .map(serde_json::from_str::<Value>)
+                    .transpose()
+            })
+            .map_err(|e| StoreError::DeserializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let coll = self.get_collection();
+        // Expired documents are left to the TTL monitor so they are not counted.
+        coll.delete_many(live(doc! { "key": { "$in": keys } }), None)
+            .await
+            .map(|result| result.deleted_count)
+            .map_err(query_error)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let coll = self.get_collection();
+        let filter = live(match prefix {
+            Some(prefix) => doc! { "key": { "$regex": format!("^{}", escape_regex(prefix)) } },
+            None => doc! {},
+        });
+        let options = mongodb::options::FindOptions::builder()
+            .projection(doc! { "key": 1 })
+            .build();
+        let mut cursor = coll.find(filter, options).await.map_err(query_error)?;
+
+        let mut keys = Vec::new();
+        while cursor.advance().await.map_err(query_error)? {
+            let doc = cursor.deserialize_current().map_err(query_error)?;
+            if let Ok(key) = doc.get_str("key") {
+                keys.push(key.to_string());
+            }
+        }
+
+        Ok(keys)
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.scan_prefix("")
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let coll = self.get_collection();
+        let filter = match prefix {
+            "" => doc! {},
+            prefix => doc! { "key": { "$regex": format!("^{}", escape_regex(prefix)) } },
+        };
+        let options = mongodb::options::FindOptions::builder()
+            .projection(doc! { "key": 1, "value": 1 })
+            .batch_size(ITER_BATCH_SIZE as u32)
+            .build();
+
+        // The driver cursor already fetches documents one batch at a time as it is polled.
+        let cursor = async move { coll.find(live(filter), options).await.map_err(query_error) };
+        let entries = stream::once(cursor)
+            .map_ok(|cursor| {
+                cursor.map(|doc| {
+                    let doc = doc.map_err(query_error)?;
+                    let key = doc
+                        .get_str("key")
+                        .map_err(|e| StoreError::QueryError(e.to_string()))?
+                        .to_string();
+                    let value = doc
+                        .get_str("value")
+                        .map_err(|e| StoreError::QueryError(e.to_string()))?;
+                    let value = serde_json::from_str(value).map_err(|e| {
+                        StoreError::DeserializationError {
+                            key: Some(key.to_string()),
+                            source: e,
+                        }
+                    })?;
+                    Ok((key, value))
+                })
+            })
+            .try_flatten();
+        Box::pin(entries)
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let coll = self.get_collection();
+        let count = coll
+            .count_documents(live(doc! {}), None)
+            .await
+            .map_err(query_error)?;
+
+        Ok(count as usize)
     }
 
     async fn clear(&self) -> Result<(), StoreError> {
@@ -96,6 +385,15 @@ impl Store for MongoStore {
         coll.delete_many(doc! {}, None)
             .await
             .map(|_| ())
-            .map_err(|_| StoreError::QueryError("Failed to clear the collection".to_string()))
+            .map_err(query_error)
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let coll = self.get_collection();
+        let filter = doc! { "key": { "$regex": format!("^{}", escape_regex(prefix)) } };
+        coll.delete_many(live(filter), None)
+            .await
+            .map(|result| result.deleted_count)
+            .map_err(query_error)
     }
 }
@@ -1,7 +1,15 @@
-pub use sqlx::{mysql::MySqlPoolOptions, MySqlPool};
-use std::sync::Arc;
+pub use sqlx::{
+    mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlSslMode},
+    MySqlPool,
+};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
-use crate::{StoreError, DEFAUTL_NAMESPACE_NAME};
+use crate::{
+    utils::{
+        check_root_cert, connect_with_retry, connection_error, Columns, DEFAULT_RETRY_BACKOFF,
+    },
+    StoreError, DEFAULT_NAMESPACE_NAME,
+};
 
 use super::MySqlStore;
 
@@ -50,6 +58,14 @@ pub struct MySqlStoreBuilder {
     uri: Option<String>,
     pool: Option<Arc<MySqlPool>>,
     table_name: Option<String>,
+    columns: Columns,
+    timestamps: bool,
+    auto_create: bool,
+    cleanup_interval: Option<Duration>,
+    ssl_mode: Option<MySqlSslMode>,
+    ssl_root_cert: Option<PathBuf>,
+    connect_retries: u32,
+    retry_backoff: Option<Duration>,
 }
 
 /// Creates a new builder instance with default configuration.
@@ -62,6 +78,14 @@ impl MySqlStoreBuilder {
             uri: None,
             pool: None,
             table_name: None,
+            columns: Columns::default(),
+            timestamps: false,
+            auto_create: true,
+            cleanup_interval: None,
+            ssl_mode: None,
+            ssl_root_cert: None,
+            connect_retries: 0,
+            retry_backoff: None,
         }
     }
 
@@ -91,6 +115,77 @@ impl MySqlStoreBuilder {
         self
     }
 
+    /// Sets the name of the column holding the keys.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn key_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.key = name.into();
+        self
+    }
+
+    /// Sets the name of the column holding the JSON-encoded values.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn value_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.value = name.into();
+        self
+    }
+
+    /// Sets the name of the column holding the expiry timestamps.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `ttl`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn ttl_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.ttl = name.into();
+        self
+    }
+
+    /// Records when each entry was created and last updated.
+    ///
+    /// Adds `created_at` and `updated_at` `TIMESTAMP` columns, which MySQL maintains
+    /// itself: `updated_at` changes whenever a write alters the row, including its TTL,
+    /// and stays put when a write leaves the row unchanged. `initialize` adds them to an
+    /// existing table as well. Overwriting a key keeps its `created_at`. The timestamps
+    /// are read, as unix seconds, with `Store::get_with_meta`. Disabled by default, so
+    /// existing tables are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to maintain the timestamp columns.
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.timestamps = enabled;
+        self
+    }
+
+    /// Sets whether `initialize` creates and migrates the table.
+    ///
+    /// Enabled by default. Disable it when the database user has no DDL rights and the
+    /// table is provisioned ahead of time: `initialize` then only checks that the table
+    /// exists with the configured columns, and returns a `StoreError::QueryError` naming
+    /// what is missing otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to run `CREATE TABLE` and the schema migrations.
+    pub fn auto_create(mut self, enabled: bool) -> Self {
+        self.auto_create = enabled;
+        self
+    }
+
     /// Uses an existing connection pool for the `MySqlStore`.
     ///
     /// This method allows for using an already configured `MySqlPool`. If set,
@@ -104,6 +199,74 @@ impl MySqlStoreBuilder {
         self
     }
 
+    /// Sets how many times the initial connection is retried before `build` gives up.
+    ///
+    /// Retries wait `retry_backoff`, doubling the delay after each failed attempt, and
+    /// every failed attempt is logged. Only applies when the pool is created from `uri`. Defaults to zero, so a failed
+    /// first attempt is returned immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - The number of attempts made after the first one fails.
+    pub fn connect_retries(mut self, retries: u32) -> Self {
+        self.connect_retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first connection retry.
+    ///
+    /// The delay doubles after each further failed attempt. Defaults to 500 milliseconds.
+    /// Has no effect unless `connect_retries` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The delay before the first retry.
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = Some(backoff);
+        self
+    }
+
+    /// Sets whether and how connections are secured with TLS.
+    ///
+    /// Overrides any `ssl-mode` given in the URI. Only applies when the pool is created
+    /// from `uri`; ignored if an existing `pool` is supplied. If not set, the URI's
+    /// `ssl-mode`, or `PREFERRED`, is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The TLS mode, e.g. `MySqlSslMode::VerifyIdentity` for managed databases.
+    pub fn ssl_mode(mut self, mode: MySqlSslMode) -> Self {
+        self.ssl_mode = Some(mode);
+        self
+    }
+
+    /// Sets the CA certificate used to verify the server's certificate.
+    ///
+    /// Only applies when the pool is created from `uri`. `build` fails with
+    /// `StoreError::ConnectionError` if the file does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of a PEM file holding the CA certificate.
+    pub fn ssl_root_cert<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.ssl_root_cert = Some(path.into());
+        self
+    }
+
+    /// Enables a background task that deletes expired rows every `interval`.
+    ///
+    /// Expired rows are never returned by reads, but without a sweep they stay in the
+    /// table until overwritten. If not set, no background task is started and
+    /// `MySqlStore::cleanup_expired` can be called manually instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two sweeps.
+    pub fn cleanup_interval(mut self, interval: Duration) -> Self {
+        self.cleanup_interval = Some(interval);
+        self
+    }
+
     /// Builds the `MySqlStore` based on the provided configurations.
     ///
     /// Finalizes the builder and creates a `MySqlStore` instance. It requires
@@ -121,19 +284,46 @@ impl MySqlStoreBuilder {
                 let uri = self
                     .uri
                     .expect("MySqlStore requires either a URI or an existing pool to be set");
-                Arc::new(MySqlPoolOptions::new().connect(&uri).await.map_err(|_| {
-                    StoreError::ConnectionError("Failed to connect to the database".to_string())
-                })?)
+                let mut connect_options = MySqlConnectOptions::from_str(&uri)
+                    .map_err(|e| connection_error(e, "Invalid database URI"))?;
+                if let Some(mode) = self.ssl_mode {
+                    connect_options = connect_options.ssl_mode(mode);
+                }
+                if let Some(path) = &self.ssl_root_cert {
+                    check_root_cert(path)?;
+                    connect_options = connect_options.ssl_ca(path);
+                }
+                let pool = connect_with_retry(
+                    self.connect_retries,
+                    self.retry_backoff.unwrap_or(DEFAULT_RETRY_BACKOFF),
+                    || MySqlPoolOptions::new().connect_with(connect_options.clone()),
+                )
+                .await
+                .map_err(|e| connection_error(e, "Failed to connect to the database"))?;
+                Arc::new(pool)
             }
         };
         let table_name = match &self.table_name {
             Some(table_name) => table_name.to_string(),
             None => {
-                log::warn!("Table name not set, using default table name");
-                DEFAUTL_NAMESPACE_NAME.to_string()
+                log::debug!("Table name not set, using default table name");
+                DEFAULT_NAMESPACE_NAME.to_string()
             }
         };
 
-        Ok(MySqlStore { pool, table_name })
+        let mut store = MySqlStore {
+            pool,
+            table_name,
+            columns: self.columns,
+            timestamps: self.timestamps,
+            auto_create: self.auto_create,
+            cleanup_task: None,
+        };
+
+        if let Some(interval) = self.cleanup_interval {
+            store.spawn_cleanup(interval);
+        }
+
+        Ok(store)
     }
 }
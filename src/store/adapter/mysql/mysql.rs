@@ -1,14 +1,29 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
 use serde_json::Value;
-use sqlx::{mysql::MySqlPool, Row};
+use sqlx::{
+    mysql::{MySqlDatabaseError, MySqlPool},
+    Row,
+};
+use tokio::task::JoinHandle;
 
-use crate::{Store, StoreError};
+use crate::{
+    utils::{
+        check_table, clamp_i64, expand_sql, expires_at, is_transient_sqlx, like_prefix_pattern,
+        now_secs, quote_identifier, Columns, ITER_BATCH_SIZE,
+    },
+    BatchOp, EntryMeta, EntryStream, ExportEntry, ExportStream, Store, StoreError,
+};
 
 pub struct MySqlStore {
     pub(crate) pool: Arc<MySqlPool>,
     pub(crate) table_name: String,
+    pub(crate) columns: Columns,
+    pub(crate) timestamps: bool,
+    pub(crate) auto_create: bool,
+    pub(crate) cleanup_task: Option<JoinHandle<()>>,
 }
 
 /// Builder for creating a `MySqlStore`.
@@ -56,17 +71,175 @@ impl MySqlStore {
     fn get_table_name(&self) -> String {
         self.table_name.clone()
     }
+
+    /// Expands a SQL template with the table name and the backtick-quoted column names.
+    fn sql(&self, template: &str) -> String {
+        expand_sql(template, &self.get_table_name(), &self.columns, '`')
+    }
+
+    /// Checks whether the table has a column named `name`.
+    async fn has_column(&self, name: &str) -> Result<bool, StoreError> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND COLUMN_NAME = ?",
+        )
+        .bind(self.get_table_name())
+        .bind(name)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to inspect the table: {}", e)))?;
+        Ok(count > 0)
+    }
+
+    /// Checks that the pre-provisioned table exists with the configured columns.
+    async fn verify_table(&self) -> Result<(), StoreError> {
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT COLUMN_NAME FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?",
+        )
+        .bind(self.get_table_name())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to inspect the table: {}", e)))?;
+
+        check_table(
+            &self.get_table_name(),
+            &columns,
+            &self.columns,
+            self.timestamps,
+        )
+    }
+
+    /// Checks whether the key column alone is covered by a primary key or unique index.
+    async fn has_unique_key(&self) -> Result<bool, StoreError> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM (SELECT INDEX_NAME FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND NON_UNIQUE = 0 GROUP BY INDEX_NAME HAVING COUNT(*) = 1 AND MAX(COLUMN_NAME) = ?) AS unique_keys",
+        )
+        .bind(self.get_table_name())
+        .bind(&self.columns.key)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to inspect the table: {}", e)))?;
+        Ok(count > 0)
+    }
+
+    /// Streams the live entries whose key starts with `prefix`, in key order, along with
+    /// their absolute expiry.
+    fn scan(&self, prefix: &str) -> ExportStream<'_> {
+        // Keyset pagination: each batch resumes after the last key of the previous one.
+        let query = self.sql("SELECT {key}, {value}, {ttl} FROM {table} WHERE (? IS NULL OR {key} > ?) AND {key} LIKE CAST(? AS BINARY) ESCAPE '\\\\' AND ({ttl} IS NULL OR {ttl} > ?) ORDER BY {key} LIMIT ?");
+        let pattern = like_prefix_pattern(prefix);
+        let batches = stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| {
+            let query = query.clone();
+            let pattern = pattern.clone();
+            async move {
+                let Some(after) = cursor else {
+                    return Ok(None);
+                };
+                let rows = sqlx::query(&query)
+                    .bind(after.clone())
+                    .bind(after)
+                    .bind(pattern)
+                    .bind(now_secs() as i64)
+                    .bind(ITER_BATCH_SIZE as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+                    .map_err(|e| query_error(e, "Failed to fetch the entries"))?;
+
+                let next = match rows.last() {
+                    Some(row) if rows.len() == ITER_BATCH_SIZE => Some(Some(row.get(0))),
+                    _ => None,
+                };
+                let entries: Vec<Result<ExportEntry, StoreError>> = rows
+                    .into_iter()
+                    .map(|row| {
+                        let key: String = row.get(0);
+                        let value = serde_json::from_str(row.get(1)).map_err(|e| {
+                            StoreError::DeserializationError {
+                                key: Some(key.clone()),
+                                source: e,
+                            }
+                        })?;
+                        let expires_at = row.get::<Option<i64>, _>(2).map(|ttl| ttl as u64);
+                        Ok((key, value, expires_at))
+                    })
+                    .collect();
+                Ok::<_, StoreError>(Some((stream::iter(entries), next)))
+            }
+        });
+        Box::pin(batches.try_flatten())
+    }
+
+    fn delete_expired_query(&self) -> String {
+        self.sql("DELETE FROM {table} WHERE {ttl} IS NOT NULL AND {ttl} <= ?")
+    }
+
+    /// Deletes every row whose TTL has passed.
+    ///
+    /// Expired rows are already hidden from reads, so this only reclaims the space they
+    /// use. It can be called manually or run periodically via
+    /// `MySqlStoreBuilder::cleanup_interval`.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows deleted.
+    pub async fn cleanup_expired(&self) -> Result<u64, StoreError> {
+        delete_expired(&self.pool, &self.delete_expired_query()).await
+    }
+
+    /// Starts a background task that calls `cleanup_expired` every `interval`.
+    pub(crate) fn spawn_cleanup(&mut self, interval: Duration) {
+        let pool = self.pool.clone();
+        let query = self.delete_expired_query();
+        self.cleanup_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; wait a full interval before sweeping.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match delete_expired(&pool, &query).await {
+                    Ok(deleted) => log::debug!("Removed {} expired rows", deleted),
+                    Err(e) => log::warn!("Failed to remove expired rows: {}", e),
+                }
+            }
+        }));
+    }
+}
+
+impl Drop for MySqlStore {
+    fn drop(&mut self) {
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
+        }
+    }
+}
+
+// The `ttl` column holds an absolute expiry in UTC epoch seconds. It is always compared
+// against `now_secs()` from the client, never the server's clock, so reads and writes
+// agree on when a row expires.
+
+async fn delete_expired(pool: &MySqlPool, query: &str) -> Result<u64, StoreError> {
+    let result = sqlx::query(query)
+        .bind(now_secs() as i64)
+        .execute(pool)
+        .await
+        .map_err(|e| query_error(e, "Failed to remove expired rows"))?;
+
+    // MySQL has no `RETURNING`, but a plain `DELETE` reports exactly the rows it removed.
+    Ok(result.rows_affected())
 }
 
 #[async_trait]
 impl Store for MySqlStore {
     async fn initialize(&self) -> Result<(), StoreError> {
-        let sql = format!(
-            "CREATE TABLE IF NOT EXISTS {} (
-            `key` VARCHAR(255) PRIMARY KEY,
-            `value` TEXT NOT NULL
+        if !self.auto_create {
+            return self.verify_table().await;
+        }
+
+        let sql = self.sql(
+            "CREATE TABLE IF NOT EXISTS {table} (
+            {key} VARCHAR(255) PRIMARY KEY,
+            {value} TEXT NOT NULL,
+            {ttl} BIGINT NULL
         ) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci",
-            self.get_table_name()
         );
 
         sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
@@ -76,85 +249,737 @@ impl Store for MySqlStore {
             ))
         })?;
 
+        // Tables created by earlier versions have no TTL column, and MySQL has no
+        // `ADD COLUMN IF NOT EXISTS`.
+        if !self.has_column(&self.columns.ttl).await? {
+            let sql = self.sql("ALTER TABLE {table} ADD COLUMN {ttl} BIGINT NULL");
+            sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+                StoreError::QueryError(format!("Failed to add the ttl column: {}", e))
+            })?;
+            log::info!("Added the ttl column to table {}", self.get_table_name());
+        }
+
+        // Writes upsert with `ON DUPLICATE KEY UPDATE`, which only replaces the existing row
+        // if the key is unique. A table created elsewhere may lack the primary key.
+        if !self.has_unique_key().await? {
+            let index = quote_identifier(&format!("{}_key_unique", self.get_table_name()), '`');
+            let sql = self.sql(&format!(
+                "ALTER TABLE {{table}} ADD UNIQUE INDEX {} ({{key}})",
+                index
+            ));
+            sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+                StoreError::QueryError(format!(
+                    "Failed to add a unique index on the key column, check for duplicate keys: {}",
+                    e
+                ))
+            })?;
+            log::info!(
+                "Added a unique index on the key column of table {}",
+                self.get_table_name()
+            );
+        }
+
+        if self.timestamps {
+            let columns = [
+                ("created_at", "TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP"),
+                (
+                    "updated_at",
+                    "TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP",
+                ),
+            ];
+            for (column, definition) in columns {
+                if self.has_column(column).await? {
+                    continue;
+                }
+                let sql = self.sql(&format!(
+                    "ALTER TABLE {{table}} ADD COLUMN {} {}",
+                    column, definition
+                ));
+                sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+                    StoreError::QueryError(format!("Failed to add the {} column: {}", column, e))
+                })?;
+                log::info!(
+                    "Added the {} column to table {}",
+                    column,
+                    self.get_table_name()
+                );
+            }
+        }
+
         Ok(())
     }
 
+    async fn health_check(&self) -> Result<(), StoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let query = format!(
-            "SELECT `value` FROM {} WHERE `key` = ?",
-            self.get_table_name()
-        );
+        let query = self
+            .sql("SELECT {value} FROM {table} WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?)");
         let result = sqlx::query(&query)
             .bind(key)
+            .bind(now_secs() as i64)
             .fetch_optional(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
 
-        Ok(result.and_then(|row| serde_json::from_str(row.get("value")).ok()))
+        result
+            .map(|row| {
+                serde_json::from_str(row.get(0)).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            })
+            .transpose()
     }
 
-    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
-        if ttl.is_some() {
-            log::warn!("TTL is not supported by the MySQL store");
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        let query = self
+            .sql("SELECT 1 FROM {table} WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?) LIMIT 1");
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now_secs() as i64)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to check the key"))?;
+
+        Ok(result.is_some())
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        if !self.timestamps {
+            return Ok(self
+                .get(key)
+                .await?
+                .map(|value| (value, EntryMeta::default())));
         }
 
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let query = self.sql("SELECT {value}, CAST(UNIX_TIMESTAMP(created_at) AS SIGNED), CAST(UNIX_TIMESTAMP(updated_at) AS SIGNED) FROM {table} WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?)");
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now_secs() as i64)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
 
-        let sql = format!(
-            "INSERT INTO {} (`key`, `value`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `value` = VALUES(`value`)",
-            self.get_table_name()
-        );
+        result
+            .map(|row| {
+                let value = serde_json::from_str(row.get(0)).map_err(|e| {
+                    StoreError::DeserializationError {
+                        key: Some(key.to_string()),
+                        source: e,
+                    }
+                })?;
+                let meta = EntryMeta {
+                    created_at: row.get::<Option<i64>, _>(1).map(|t| t as u64),
+                    updated_at: row.get::<Option<i64>, _>(2).map(|t| t as u64),
+                };
+                Ok((value, meta))
+            })
+            .transpose()
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let query =
+            self.sql("SELECT {ttl} FROM {table} WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?)");
+        let now = now_secs() as i64;
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the TTL"))?;
+
+        Ok(result
+            .and_then(|row| row.get::<Option<i64>, _>(0))
+            .map(|ttl| (ttl - now) as u64))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys_placeholder: String = keys.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let query = self.sql(&format!("SELECT {{key}}, {{value}} FROM {{table}} WHERE {{key}} IN ({}) AND ({{ttl}} IS NULL OR {{ttl}} > ?)", keys_placeholder));
+
+        let mut query_builder = sqlx::query(&query);
+        for key in keys {
+            query_builder = query_builder.bind(key);
+        }
+
+        let rows = query_builder
+            .bind(now_secs() as i64)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the values"))?;
+
+        let found = rows
+            .into_iter()
+            .map(|row| {
+                let key: String = row.get(0);
+                let value = serde_json::from_str(row.get(1)).map_err(|e| {
+                    StoreError::DeserializationError {
+                        key: Some(key.clone()),
+                        source: e,
+                    }
+                })?;
+                Ok((key, value))
+            })
+            .collect::<Result<HashMap<String, Value>, StoreError>>()?;
+
+        Ok(keys.iter().map(|key| found.get(*key).cloned()).collect())
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+
+        let sql = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE {value} = VALUES({value}), {ttl} = VALUES({ttl})");
         sqlx::query(&sql)
             .bind(key)
             .bind(value_str)
+            .bind(expires_at(ttl).map(clamp_i64))
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to set the value"))?;
 
         Ok(())
     }
 
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let values_placeholder: String = entries
+            .iter()
+            .map(|_| "(?, ?, ?)")
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = self.sql(&format!("INSERT INTO {{table}} ({{key}}, {{value}}, {{ttl}}) VALUES {} ON DUPLICATE KEY UPDATE {{value}} = VALUES({{value}}), {{ttl}} = VALUES({{ttl}})", values_placeholder));
+
+        let mut query_builder = sqlx::query(&sql);
+        for (key, value, ttl) in entries {
+            let value_str =
+                serde_json::to_string(value).map_err(|e| StoreError::SerializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })?;
+            query_builder = query_builder
+                .bind(key)
+                .bind(value_str)
+                .bind(expires_at(*ttl).map(clamp_i64));
+        }
+
+        query_builder
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the values"))?;
+
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let new_str = serde_json::to_string(&new).map_err(|e| StoreError::SerializationError {
+            key: Some(key.to_string()),
+            source: e,
+        })?;
+        let ttl = expires_at(ttl).map(clamp_i64);
+        let now = now_secs() as i64;
+
+        let result = match expected {
+            Some(expected) => {
+                let expected_str = serde_json::to_string(&expected)
+                    .map_err(|e| StoreError::SerializationError { key: Some(key.to_string()), source: e })?;
+                // The table's collation ignores case and trailing spaces, so both sides are
+                // also compared as bytes; the plain key comparison keeps the primary key
+                // index in use.
+                let sql = self.sql("UPDATE {table} SET {value} = ?, {ttl} = ? WHERE {key} = ? AND {key} = CAST(? AS BINARY) AND {value} = CAST(? AS BINARY) AND ({ttl} IS NULL OR {ttl} > ?)");
+                sqlx::query(&sql)
+                    .bind(new_str)
+                    .bind(ttl)
+                    .bind(key)
+                    .bind(key)
+                    .bind(expected_str)
+                    .bind(now)
+                    .execute(&*self.pool)
+                    .await
+            }
+            None => {
+                // An expired row counts as absent. Dropping it first lets `INSERT IGNORE`
+                // stay the single atomic step that decides who wins.
+                let delete = self.sql("DELETE FROM {table} WHERE {key} = ? AND {ttl} IS NOT NULL AND {ttl} <= ?");
+                sqlx::query(&delete)
+                    .bind(key)
+                    .bind(now)
+                    .execute(&*self.pool)
+                    .await
+                    .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+                let sql = self.sql("INSERT IGNORE INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?)");
+                sqlx::query(&sql)
+                    .bind(key)
+                    .bind(new_str)
+                    .bind(ttl)
+                    .execute(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Locks the row, or the gap where it would go, until the transaction ends.
+        let select = self.sql("SELECT {value}, {ttl} FROM {table} WHERE {key} = ? FOR UPDATE");
+        let result = sqlx::query(&select)
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        let upsert = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE {value} = VALUES({value}), {ttl} = VALUES({ttl})");
+        sqlx::query(&upsert)
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the value"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        let now = now_secs() as i64;
+        result
+            .filter(|row| row.get::<Option<i64>, _>(1).is_none_or(|ttl| ttl > now))
+            .map(|row| {
+                serde_json::from_str(row.get(0)).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            })
+            .transpose()
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Make sure the row exists so that `FOR UPDATE` always has a row to lock.
+        let insert = self.sql("INSERT IGNORE INTO {table} ({key}, {value}) VALUES (?, '0')");
+        sqlx::query(&insert)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to increment the value"))?;
+
+        let select = self.sql("SELECT {value}, {ttl} FROM {table} WHERE {key} = ? FOR UPDATE");
+        let row = sqlx::query(&select)
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to increment the value"))?;
+
+        // An expired row is restarted from `delta` rather than incremented.
+        let ttl: Option<i64> = row.get(1);
+        let (current, ttl) = match ttl {
+            Some(ttl) if ttl <= now_secs() as i64 => (0, None),
+            ttl => {
+                let current: i64 = row
+                    .get::<&str, _>(0)
+                    .parse()
+                    .map_err(|_| StoreError::NotAnInteger(key.to_string()))?;
+                (current, ttl)
+            }
+        };
+        let new = current
+            .checked_add(delta)
+            .ok_or_else(|| StoreError::QueryError("Increment would overflow".to_string()))?;
+
+        let update = self.sql("UPDATE {table} SET {value} = ?, {ttl} = ? WHERE {key} = ?");
+        sqlx::query(&update)
+            .bind(new.to_string())
+            .bind(ttl)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to increment the value"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(new)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let query = self
+            .sql("UPDATE {table} SET {ttl} = ? WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?)");
+        // The connection reports matched rather than changed rows, so re-applying the
+        // same expiry still counts as an update.
+        let result = sqlx::query(&query)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .bind(key)
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to update the TTL"))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        // `LEAST` keeps the sum within BIGINT; a NULL ttl stays NULL.
+        let query = self.sql(
+            "UPDATE {table} SET {ttl} = LEAST({ttl}, 9223372036854775807 - ?) + ? WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?)",
+        );
+        let by_secs = clamp_i64(by_secs);
+        let result = sqlx::query(&query)
+            .bind(by_secs)
+            .bind(by_secs)
+            .bind(key)
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to extend the TTL"))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE `key` = ?", self.get_table_name());
+        let query = self.sql("DELETE FROM {table} WHERE {key} = ?");
         sqlx::query(&query)
             .bind(key)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
 
         Ok(())
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let select = self.sql("SELECT {value}, {ttl} FROM {table} WHERE {key} = ? FOR UPDATE");
+        let result = sqlx::query(&select)
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        // A value that cannot be decoded is kept: returning before the delete drops the
+        // transaction, which releases the row lock.
+        let now = now_secs() as i64;
+        let value = result
+            .filter(|row| row.get::<Option<i64>, _>(1).is_none_or(|ttl| ttl > now))
+            .map(|row| {
+                serde_json::from_str(row.get(0)).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            })
+            .transpose()?;
+
+        let delete = self.sql("DELETE FROM {table} WHERE {key} = ?");
+        sqlx::query(&delete)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+        Ok(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
         let keys_placeholder: String = keys.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
-        let query = format!(
-            "DELETE FROM {} WHERE `key` IN ({})",
-            self.get_table_name(),
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Expired rows are deleted too, but they were not present from the caller's view,
+        // so drop them before the delete whose row count is reported.
+        let delete_expired = self.sql(&format!(
+            "DELETE FROM {{table}} WHERE {{key}} IN ({}) AND {{ttl}} IS NOT NULL AND {{ttl}} <= ?",
             keys_placeholder
-        );
+        ));
+        let mut query_builder = sqlx::query(&delete_expired);
+        for key in keys {
+            query_builder = query_builder.bind(key);
+        }
+        query_builder
+            .bind(now_secs() as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
 
+        let query = self.sql(&format!(
+            "DELETE FROM {{table}} WHERE {{key}} IN ({})",
+            keys_placeholder
+        ));
         let mut query_builder = sqlx::query(&query);
         for key in keys {
             query_builder = query_builder.bind(key);
         }
+        let result = query_builder
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
 
-        query_builder
-            .execute(&*self.pool)
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let set = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE {value} = VALUES({value}), {ttl} = VALUES({ttl})");
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = ?");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Returning early drops `tx`, which rolls back everything applied so far.
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = serde_json::to_string(value).map_err(|e| {
+                        StoreError::SerializationError {
+                            key: Some(key.to_string()),
+                            source: e,
+                        }
+                    })?;
+                    sqlx::query(&set)
+                        .bind(key)
+                        .bind(value_str)
+                        .bind(expires_at(*ttl).map(clamp_i64))
+                        .execute(&mut *tx)
+                        .await
+                }
+                BatchOp::Remove { key } => sqlx::query(&remove).bind(key).execute(&mut *tx).await,
+            }
+            .map_err(|e| query_error(e, "Failed to apply the batch"))?;
+        }
+
+        tx.commit()
             .await
-            .map_err(|_| StoreError::QueryError("Failed to remove the keys".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
 
         Ok(())
     }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let exists = self.sql(
+            "SELECT 1 FROM {table} WHERE {key} = ? AND ({ttl} IS NULL OR {ttl} > ?) FOR UPDATE",
+        );
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = ?");
+        let rename = self.sql("UPDATE {table} SET {key} = ? WHERE {key} = ?");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let found = sqlx::query(&exists)
+            .bind(from)
+            .bind(now_secs() as i64)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?
+            .is_some();
+        if !found || from == to {
+            return Ok(found);
+        }
+
+        // Clear the destination first, so the update cannot hit its unique key.
+        sqlx::query(&remove)
+            .bind(to)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+        sqlx::query(&rename)
+            .bind(to)
+            .bind(from)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let rows = match prefix {
+            Some(prefix) => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {key} LIKE CAST(? AS BINARY) ESCAPE '\\\\' AND ({ttl} IS NULL OR {ttl} > ?)");
+                sqlx::query(&query)
+                    .bind(like_prefix_pattern(prefix))
+                    .bind(now_secs() as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+            None => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {ttl} IS NULL OR {ttl} > ?");
+                sqlx::query(&query)
+                    .bind(now_secs() as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to list the keys"))?;
+
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        let query = self.sql("SELECT {key}, {value} FROM {table} WHERE {key} >= ? AND {key} < ? AND ({ttl} IS NULL OR {ttl} > ?) ORDER BY {key}");
+        let rows = sqlx::query_as::<_, (String, String)>(&query)
+            .bind(start)
+            .bind(end)
+            .bind(now_secs() as i64)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the range"))?;
+
+        rows.into_iter()
+            .map(|(key, value)| {
+                let value =
+                    serde_json::from_str(&value).map_err(|e| StoreError::DeserializationError {
+                        key: Some(key.clone()),
+                        source: e,
+                    })?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(self.export().map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        Box::pin(self.scan(prefix).map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.scan("")
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let query =
+            self.sql("SELECT COUNT(*) AS count FROM {table} WHERE {ttl} IS NULL OR {ttl} > ?");
+        let row = sqlx::query(&query)
+            .bind(now_secs() as i64)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to count the entries"))?;
+
+        Ok(row.get::<i64, _>("count") as usize)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {}", self.get_table_name());
+        let query = self.sql("DELETE FROM {table}");
 
         sqlx::query(&query)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to clear the table".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to clear the table"))?;
 
         Ok(())
     }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        // Expired rows are left for `cleanup_expired`, so only live keys are counted. The
+        // binary pattern keeps the table's case-insensitive collation out of the match.
+        let query = self.sql(
+            "DELETE FROM {table} WHERE {key} LIKE CAST(? AS BINARY) ESCAPE '\\\\' AND ({ttl} IS NULL OR {ttl} > ?)",
+        );
+        let result = sqlx::query(&query)
+            .bind(like_prefix_pattern(prefix))
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        if let Some(task) = &self.cleanup_task {
+            task.abort();
+        }
+        self.pool.close().await;
+        Ok(())
+    }
+}
+
+/// Maps a failed query to `StoreError::Transient` when retrying it may succeed and to
+/// `StoreError::QueryError` with `message` otherwise.
+///
+/// Lock wait timeouts (1205), deadlocks (1213) and running out of connections (1040) are
+/// transient, as are I/O errors and pool timeouts.
+fn query_error(e: sqlx::Error, message: &str) -> StoreError {
+    let transient = is_transient_sqlx(&e, |e| {
+        e.try_downcast_ref::<MySqlDatabaseError>()
+            .is_some_and(|e| matches!(e.number(), 1040 | 1205 | 1213))
+    });
+    if transient {
+        StoreError::Transient(format!("{}: {}", message, e))
+    } else {
+        StoreError::QueryError(message.to_string())
+    }
 }
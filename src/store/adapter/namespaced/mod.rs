@@ -0,0 +1,2 @@
+mod namespaced;
+pub use namespaced::*;
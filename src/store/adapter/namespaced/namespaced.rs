@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, Store, StoreError, Ttl};
+
+/// A store that prefixes every key of an inner store with `"{prefix}:"`.
+///
+/// Keys are prefixed on the way in and stripped again by `keys`, so callers only ever see
+/// their own keys. `len` and `clear` are limited to the keys under the prefix, which lets
+/// several namespaced stores share one inner store without seeing each other's data.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, namespaced::NamespacedStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let inner = Arc::new(InMemoryStore::new());
+/// let users = Keyv::try_new(NamespacedStore::new(inner.clone(), "users")).await.unwrap();
+/// let sessions = Keyv::try_new(NamespacedStore::new(inner, "sessions")).await.unwrap();
+///
+/// users.set("1", "alice").await.unwrap();
+/// assert!(sessions.get("1").await.unwrap().is_none());
+/// # };
+/// ```
+pub struct NamespacedStore {
+    inner: Arc<dyn Store>,
+    prefix: String,
+}
+
+impl NamespacedStore {
+    /// Creates a store that keeps its keys under `prefix` in `inner`.
+    pub fn new<S: Into<String>>(inner: Arc<dyn Store>, prefix: S) -> Self {
+        Self {
+            inner,
+            prefix: prefix.into(),
+        }
+    }
+
+    fn get_key(&self, key: &str) -> String {
+        format!("{}:{}", self.prefix, key)
+    }
+}
+
+#[async_trait]
+impl Store for NamespacedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(&self.get_key(key)).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(&self.get_key(key)).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let namespaced_keys: Vec<String> = keys.iter().map(|key| self.get_key(key)).collect();
+        let namespaced_keys: Vec<&str> = namespaced_keys.iter().map(String::as_str).collect();
+        self.inner.get_many(&namespaced_keys).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner.get_with_meta(&self.get_key(key)).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(&self.get_key(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(&self.get_key(key), value, ttl).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner
+            .set_with_ttl(&self.get_key(key), value, ttl)
+            .await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set_bytes(&self.get_key(key), value, ttl).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.inner.get_bytes(&self.get_key(key)).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let namespaced_keys: Vec<String> = entries
+            .iter()
+            .map(|(key, _, _)| self.get_key(key))
+            .collect();
+        let entries: Vec<(&str, Value, Option<u64>)> = namespaced_keys
+            .iter()
+            .zip(entries)
+            .map(|(key, (_, value, ttl))| (key.as_str(), value.clone(), *ttl))
+            .collect();
+        self.inner.set_many(&entries).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.inner
+            .compare_and_swap(&self.get_key(key), expected, new, ttl)
+            .await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.inner.swap(&self.get_key(key), value, ttl).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.inner.increment(&self.get_key(key), delta).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(&self.get_key(key), ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(&self.get_key(key), by_secs).await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.inner
+            .set_json_path(&self.get_key(key), path, value)
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(&self.get_key(key)).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get_and_remove(&self.get_key(key)).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let namespaced_keys: Vec<String> = keys.iter().map(|key| self.get_key(key)).collect();
+        let namespaced_keys: Vec<&str> = namespaced_keys.iter().map(String::as_str).collect();
+        self.inner.remove_many(&namespaced_keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let ops: Vec<BatchOp> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => BatchOp::Set {
+                    key: self.get_key(key),
+                    value: value.clone(),
+                    ttl: *ttl,
+                },
+                BatchOp::Remove { key } => BatchOp::Remove {
+                    key: self.get_key(key),
+                },
+            })
+            .collect();
+        self.inner.apply_batch(&ops).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.inner
+            .rename(&self.get_key(from), &self.get_key(to))
+            .await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let namespace_len = self.get_key("").len();
+        let keys = self
+            .inner
+            .keys(Some(&self.get_key(prefix.unwrap_or(""))))
+            .await?;
+        Ok(keys
+            .into_iter()
+            .map(|key| key[namespace_len..].to_string())
+            .collect())
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        let namespace_len = self.get_key("").len();
+        let entries = self
+            .inner
+            .range(&self.get_key(start), &self.get_key(end))
+            .await?;
+        Ok(entries
+            .into_iter()
+            .map(|(key, value)| (key[namespace_len..].to_string(), value))
+            .collect())
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.scan_prefix("")
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let namespace_len = self.get_key("").len();
+        Box::pin(
+            self.inner
+                .scan_prefix(&self.get_key(prefix))
+                .map_ok(move |(key, value)| (key[namespace_len..].to_string(), value)),
+        )
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear_prefix(&self.get_key("")).await?;
+        Ok(())
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.inner.clear_prefix(&self.get_key(prefix)).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
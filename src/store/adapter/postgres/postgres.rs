@@ -1,15 +1,119 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
 use serde_json::Value;
-use sqlx::{PgPool, Row};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    types::Json,
+    PgPool, Postgres, Row, Transaction,
+};
+use tokio::task::JoinHandle;
 
-use crate::{Store, StoreError};
+use crate::{
+    json_path::{JsonPath, PathSegment},
+    utils::{
+        check_table, clamp_i64, expand_sql, expires_at, is_transient_sqlx, like_prefix_pattern,
+        now_secs, Columns, ITER_BATCH_SIZE,
+    },
+    BatchOp, EntryMeta, EntryStream, ExportEntry, ExportStream, Store, StoreError,
+};
+
+/// How a `PostgresStore` keeps values in its value column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueType {
+    /// JSON encoded as `text`, the layout of tables created by earlier versions.
+    #[default]
+    Text,
+    /// Native `jsonb`, which Postgres can index and query server-side.
+    Jsonb,
+}
+
+impl ValueType {
+    fn sql_type(&self) -> &'static str {
+        match self {
+            ValueType::Text => "TEXT",
+            ValueType::Jsonb => "JSONB",
+        }
+    }
+}
+
+/// An operation whose SQL `PostgresStore::explain` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlOperation {
+    /// `get`: `$1` is the key and `$2` the current unix time in seconds.
+    Get,
+    /// `set`: `$1` is the key, `$2` the JSON value and `$3` the unix time the entry
+    /// expires at, or `NULL`.
+    Set,
+    /// `remove`: `$1` is the key.
+    Remove,
+}
+
+impl SqlOperation {
+    fn template(&self) -> &'static str {
+        match self {
+            SqlOperation::Get => {
+                "SELECT {value} FROM {table} WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $2)"
+            }
+            SqlOperation::Set => {
+                "INSERT INTO {table} ({key}, {value}, {ttl}) VALUES ($1, $2, $3) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {ttl} = EXCLUDED.{ttl}"
+            }
+            SqlOperation::Remove => "DELETE FROM {table} WHERE {key} = $1",
+        }
+    }
+}
+
+/// The current unix time in whole seconds, as a SQL expression.
+const EPOCH_NOW: &str = "floor(extract(epoch FROM now()))::bigint";
+
+/// A change to the table layout, applied by `initialize` to tables created by an
+/// earlier version of the crate.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    /// The column the migration adds; it only runs while the table lacks that column.
+    column: fn(&Columns) -> &str,
+    /// Whether the migration only applies to stores built with `timestamps(true)`.
+    timestamps: bool,
+    sql: &'static str,
+}
+
+/// Every migration, oldest first.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "added the ttl column",
+        column: |columns| &columns.ttl,
+        timestamps: false,
+        sql: "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS {ttl} BIGINT",
+    },
+    Migration {
+        version: 2,
+        description: "added the created_at column",
+        column: |_| "created_at",
+        timestamps: true,
+        sql: "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS created_at BIGINT DEFAULT floor(extract(epoch FROM now()))::bigint",
+    },
+    Migration {
+        version: 3,
+        description: "added the updated_at column",
+        column: |_| "updated_at",
+        timestamps: true,
+        sql: "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS updated_at BIGINT DEFAULT floor(extract(epoch FROM now()))::bigint",
+    },
+];
 
 pub struct PostgresStore {
     pub(crate) pool: Arc<PgPool>,
     pub(crate) table_name: String,
     pub(crate) schema: Option<String>,
+    pub(crate) columns: Columns,
+    pub(crate) value_type: ValueType,
+    pub(crate) timestamps: bool,
+    pub(crate) auto_create: bool,
+    pub(crate) cleanup_task: Option<JoinHandle<()>>,
 }
 
 impl PostgresStore {
@@ -19,15 +123,278 @@ impl PostgresStore {
             None => self.table_name.clone(),
         }
     }
+
+    /// Expands a SQL template with the table name and the double-quoted column names.
+    fn sql(&self, template: &str) -> String {
+        expand_sql(template, &self.get_table_name(), &self.columns, '"')
+    }
+
+    /// Returns the SQL this store runs for `operation`, without running it.
+    ///
+    /// The statement uses the configured schema, table and column names, quoted the way
+    /// the store quotes them. Values are never interpolated: they appear as the `$1`,
+    /// `$2`, ... placeholders described on `SqlOperation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation to explain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::adapter::postgres::{PostgresStoreBuilder, SqlOperation};
+    /// # use sqlx::postgres::PgPoolOptions;
+    /// # use std::sync::Arc;
+    /// # async {
+    /// // A lazy pool does not connect until a query runs.
+    /// let pool = PgPoolOptions::new()
+    ///     .connect_lazy("postgresql://localhost/app")
+    ///     .unwrap();
+    /// let store = PostgresStoreBuilder::new()
+    ///     .pool(Arc::new(pool))
+    ///     .table_name("cache")
+    ///     .key_column("id")
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     store.explain(SqlOperation::Remove),
+    ///     r#"DELETE FROM cache WHERE "id" = $1"#
+    /// );
+    /// # };
+    /// ```
+    pub fn explain(&self, operation: SqlOperation) -> String {
+        self.sql(operation.template())
+    }
+
+    /// Binds `value` in the representation used by the value column.
+    fn bind_value<'q>(
+        &self,
+        query: Query<'q, Postgres, PgArguments>,
+        value: &Value,
+    ) -> Result<Query<'q, Postgres, PgArguments>, StoreError> {
+        Ok(match self.value_type {
+            ValueType::Text => query.bind(serde_json::to_string(value).map_err(|e| {
+                StoreError::SerializationError {
+                    key: None,
+                    source: e,
+                }
+            })?),
+            ValueType::Jsonb => query.bind(Json(value.clone())),
+        })
+    }
+
+    /// Reads the value column at `index` of `row`, which holds the value of `key`.
+    fn decode_value(&self, row: &PgRow, index: usize, key: &str) -> Result<Value, StoreError> {
+        match self.value_type {
+            ValueType::Text => {
+                let value: &str = row
+                    .try_get(index)
+                    .map_err(|e| StoreError::QueryError(e.to_string()))?;
+                serde_json::from_str(value).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            }
+            ValueType::Jsonb => row
+                .try_get::<Json<Value>, _>(index)
+                .map(|Json(value)| value)
+                .map_err(|e| StoreError::QueryError(e.to_string())),
+        }
+    }
+
+    /// Installs the trigger that refreshes `updated_at` whenever a value is rewritten.
+    ///
+    /// Inserts take `created_at` and `updated_at` from the column defaults, and upserts
+    /// leave `created_at` alone, so no write query needs to mention either column.
+    async fn create_updated_at_trigger(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), StoreError> {
+        let function = match &self.schema {
+            Some(schema) => format!("{}.keyv_touch_updated_at", schema),
+            None => "keyv_touch_updated_at".to_string(),
+        };
+        let sql = format!(
+            "CREATE OR REPLACE FUNCTION {}() RETURNS trigger LANGUAGE plpgsql AS $$
+            BEGIN
+                NEW.updated_at := {};
+                RETURN NEW;
+            END
+            $$",
+            function, EPOCH_NOW
+        );
+        sqlx::query(&sql).execute(&mut **tx).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to create the trigger function: {}", e))
+        })?;
+
+        let exists = sqlx::query(
+            "SELECT 1 FROM pg_trigger WHERE tgrelid = to_regclass($1) AND tgname = 'keyv_touch_updated_at'",
+        )
+        .bind(self.get_table_name())
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to read the triggers: {}", e)))?
+        .is_some();
+        if exists {
+            return Ok(());
+        }
+
+        let sql = self.sql(&format!(
+            "CREATE TRIGGER keyv_touch_updated_at BEFORE UPDATE OF {{value}} ON {{table}} FOR EACH ROW EXECUTE FUNCTION {}()",
+            function
+        ));
+        sqlx::query(&sql)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to create the trigger: {}", e)))?;
+        log::info!(
+            "Added the updated_at trigger to table {}",
+            self.get_table_name()
+        );
+
+        Ok(())
+    }
+
+    /// Checks that the pre-provisioned table exists with the configured columns.
+    async fn verify_table(&self) -> Result<(), StoreError> {
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT c.column_name::text FROM information_schema.columns c
+            JOIN pg_class t ON t.relname = c.table_name
+            JOIN pg_namespace n ON n.oid = t.relnamespace AND n.nspname = c.table_schema
+            WHERE t.oid = to_regclass($1)",
+        )
+        .bind(self.get_table_name())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to read the table layout: {}", e)))?;
+
+        check_table(
+            &self.get_table_name(),
+            &columns,
+            &self.columns,
+            self.timestamps,
+        )
+    }
+
+    /// Streams the live entries whose key starts with `prefix`, in key order, along with
+    /// their absolute expiry.
+    fn scan(&self, prefix: &str) -> ExportStream<'_> {
+        // Keyset pagination: each batch resumes after the last key of the previous one.
+        let query = self.sql("SELECT {key}, {value}, {ttl} FROM {table} WHERE ($1::TEXT IS NULL OR {key} > $1) AND {key} LIKE $2 ESCAPE '\\' AND ({ttl} IS NULL OR {ttl} > $3) ORDER BY {key} LIMIT $4");
+        let pattern = like_prefix_pattern(prefix);
+        let batches = stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| {
+            let query = query.clone();
+            let pattern = pattern.clone();
+            async move {
+                let Some(after) = cursor else {
+                    return Ok(None);
+                };
+                let rows = sqlx::query(&query)
+                    .bind(after)
+                    .bind(pattern)
+                    .bind(now_secs() as i64)
+                    .bind(ITER_BATCH_SIZE as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+                    .map_err(|e| query_error(e, "Failed to fetch the entries"))?;
+
+                let next = match rows.last() {
+                    Some(row) if rows.len() == ITER_BATCH_SIZE => Some(Some(row.get(0))),
+                    _ => None,
+                };
+                let entries: Vec<Result<ExportEntry, StoreError>> = rows
+                    .into_iter()
+                    .map(|row| {
+                        let key: String = row.get(0);
+                        let value = self.decode_value(&row, 1, &key)?;
+                        let expires_at = row.get::<Option<i64>, _>(2).map(|ttl| ttl as u64);
+                        Ok((key, value, expires_at))
+                    })
+                    .collect();
+                Ok::<_, StoreError>(Some((stream::iter(entries), next)))
+            }
+        });
+        Box::pin(batches.try_flatten())
+    }
+
+    fn delete_expired_query(&self) -> String {
+        self.sql("DELETE FROM {table} WHERE {ttl} IS NOT NULL AND {ttl} <= $1")
+    }
+
+    /// Deletes every row whose TTL has passed.
+    ///
+    /// Expired rows are already hidden from reads, so this only reclaims the space they
+    /// use. It can be called manually or run periodically via
+    /// `PostgresStoreBuilder::cleanup_interval`.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows deleted.
+    pub async fn cleanup_expired(&self) -> Result<u64, StoreError> {
+        delete_expired(&self.pool, &self.delete_expired_query()).await
+    }
+
+    /// Starts a background task that calls `cleanup_expired` every `interval`.
+    pub(crate) fn spawn_cleanup(&mut self, interval: Duration) {
+        let pool = self.pool.clone();
+        let query = self.delete_expired_query();
+        self.cleanup_task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; wait a full interval before sweeping.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match delete_expired(&pool, &query).await {
+                    Ok(deleted) => log::debug!("Removed {} expired rows", deleted),
+                    Err(e) => log::warn!("Failed to remove expired rows: {}", e),
+                }
+            }
+        }));
+    }
+}
+
+impl Drop for PostgresStore {
+    fn drop(&mut self) {
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
+        }
+    }
+}
+
+async fn delete_expired(pool: &PgPool, query: &str) -> Result<u64, StoreError> {
+    let result = sqlx::query(query)
+        .bind(now_secs() as i64)
+        .execute(pool)
+        .await
+        .map_err(|e| query_error(e, "Failed to remove expired rows"))?;
+
+    Ok(result.rows_affected())
 }
 
 #[async_trait]
 impl Store for PostgresStore {
     async fn initialize(&self) -> Result<(), StoreError> {
+        if !self.auto_create {
+            return self.verify_table().await;
+        }
+
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                StoreError::QueryError(format!("Failed to start a transaction: {}", e))
+            })?;
+
+        // Serialises concurrent initialisations of the same table; released on commit.
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(self.get_table_name())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to lock the table: {}", e)))?;
+
         if let Some(ref schema) = self.schema {
             let create_schema_sql = format!("CREATE SCHEMA IF NOT EXISTS {}", schema);
             sqlx::query(&create_schema_sql)
-                .execute(&*self.pool)
+                .execute(&mut *tx)
                 .await
                 .map_err(|e| {
                     StoreError::QueryError(format!(
@@ -38,88 +405,659 @@ impl Store for PostgresStore {
                 })?;
         }
 
-        let sql = format!(
-            "CREATE TABLE IF NOT EXISTS {} (
-            key VARCHAR PRIMARY KEY,
-            value TEXT NOT NULL
+        let timestamp_columns = if self.timestamps {
+            format!(
+                ",
+            created_at BIGINT DEFAULT {EPOCH_NOW},
+            updated_at BIGINT DEFAULT {EPOCH_NOW}"
+            )
+        } else {
+            String::new()
+        };
+        let sql = self.sql(&format!(
+            "CREATE TABLE IF NOT EXISTS {{table}} (
+            {{key}} VARCHAR PRIMARY KEY,
+            {{value}} {} NOT NULL,
+            {{ttl}} BIGINT{}
         )",
-            self.get_table_name()
-        );
+            self.value_type.sql_type(),
+            timestamp_columns
+        ));
 
-        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+        sqlx::query(&sql).execute(&mut *tx).await.map_err(|e| {
             StoreError::QueryError(format!(
                 "Failed to initialize the database table: {}",
                 e.to_string()
             ))
         })?;
 
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT c.column_name::text FROM information_schema.columns c
+            JOIN pg_class t ON t.relname = c.table_name
+            JOIN pg_namespace n ON n.oid = t.relnamespace AND n.nspname = c.table_schema
+            WHERE t.oid = to_regclass($1)",
+        )
+        .bind(self.get_table_name())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to read the table layout: {}", e)))?;
+
+        for migration in MIGRATIONS {
+            if migration.timestamps && !self.timestamps {
+                continue;
+            }
+            if columns
+                .iter()
+                .any(|c| c == (migration.column)(&self.columns))
+            {
+                continue;
+            }
+            sqlx::query(&self.sql(migration.sql))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    StoreError::QueryError(format!(
+                        "Failed to apply migration {}: {}",
+                        migration.version, e
+                    ))
+                })?;
+            log::info!(
+                "Applied migration {} to table {}: {}",
+                migration.version,
+                self.get_table_name(),
+                migration.description
+            );
+        }
+
+        if self.timestamps {
+            self.create_updated_at_trigger(&mut tx).await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to commit the transaction: {}", e))
+        })?;
+
         Ok(())
     }
 
+    async fn health_check(&self) -> Result<(), StoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let query = format!("SELECT value FROM {} WHERE key = $1", self.get_table_name());
+        let query = self.explain(SqlOperation::Get);
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now_secs() as i64)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        result
+            .map(|row| self.decode_value(&row, 0, key))
+            .transpose()
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        let query = self.sql(
+            "SELECT 1 FROM {table} WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $2) LIMIT 1",
+        );
         let result = sqlx::query(&query)
             .bind(key)
+            .bind(now_secs() as i64)
             .fetch_optional(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to check the key"))?;
 
-        Ok(result.and_then(|row| serde_json::from_str(row.get("value")).ok()))
+        Ok(result.is_some())
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        if !self.timestamps {
+            return Ok(self
+                .get(key)
+                .await?
+                .map(|value| (value, EntryMeta::default())));
+        }
+
+        let query = self.sql("SELECT {value}, created_at, updated_at FROM {table} WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $2)");
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now_secs() as i64)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        result
+            .map(|row| {
+                let meta = EntryMeta {
+                    created_at: row.get::<Option<i64>, _>(1).map(|t| t as u64),
+                    updated_at: row.get::<Option<i64>, _>(2).map(|t| t as u64),
+                };
+                Ok((self.decode_value(&row, 0, key)?, meta))
+            })
+            .transpose()
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let query = self
+            .sql("SELECT {ttl} FROM {table} WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $2)");
+        let now = now_secs() as i64;
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(now)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the TTL"))?;
+
+        Ok(result
+            .and_then(|row| row.get::<Option<i64>, _>(0))
+            .map(|ttl| (ttl - now) as u64))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = self.sql("SELECT {key}, {value} FROM {table} WHERE {key} = ANY($1) AND ({ttl} IS NULL OR {ttl} > $2)");
+        let rows = sqlx::query(&query)
+            .bind(keys)
+            .bind(now_secs() as i64)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the values"))?;
+
+        let found = rows
+            .into_iter()
+            .map(|row| {
+                let key: String = row.get(0);
+                let value = self.decode_value(&row, 1, &key)?;
+                Ok((key, value))
+            })
+            .collect::<Result<HashMap<String, Value>, StoreError>>()?;
+
+        Ok(keys.iter().map(|key| found.get(*key).cloned()).collect())
     }
 
     async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
-        if ttl.is_some() {
-            log::warn!("Postgres store does not support TTL");
+        let sql = self.explain(SqlOperation::Set);
+        self.bind_value(sqlx::query(&sql).bind(key), &value)?
+            .bind(expires_at(ttl).map(clamp_i64))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the value"))?;
+
+        Ok(())
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        // A single upsert cannot touch the same row twice, so keep only the last
+        // value given for each key.
+        let mut rows: Vec<(&str, &Value, Option<i64>)> = Vec::with_capacity(entries.len());
+        for (key, value, ttl) in entries {
+            let ttl = expires_at(*ttl).map(clamp_i64);
+            match rows.iter_mut().find(|(k, _, _)| k == key) {
+                Some(row) => {
+                    row.1 = value;
+                    row.2 = ttl;
+                }
+                None => rows.push((key, value, ttl)),
+            }
         }
 
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+        if rows.is_empty() {
+            return Ok(());
+        }
 
-        let sql = format!(
-            "INSERT INTO {} (key, value) VALUES ($1, $2) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value",
-            self.get_table_name()
+        let placeholders = (0..rows.len())
+            .map(|i| format!("(${}, ${}, ${})", i * 3 + 1, i * 3 + 2, i * 3 + 3))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = self.sql(&format!("INSERT INTO {{table}} ({{key}}, {{value}}, {{ttl}}) VALUES {} ON CONFLICT({{key}}) DO UPDATE SET {{value}} = EXCLUDED.{{value}}, {{ttl}} = EXCLUDED.{{ttl}}", placeholders));
+
+        let mut query = sqlx::query(&sql);
+        for (key, value, ttl) in rows {
+            query = self.bind_value(query.bind(key), value)?.bind(ttl);
+        }
+        query
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the values"))?;
+
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let ttl = expires_at(ttl).map(clamp_i64);
+        let now = now_secs() as i64;
+
+        let result = match expected {
+            Some(expected) => {
+                // `set_json_path` writes text rendered by Postgres, so text values are
+                // compared as `jsonb` rather than character by character.
+                let matches = match self.value_type {
+                    ValueType::Text => "{value}::jsonb = $2::jsonb",
+                    ValueType::Jsonb => "{value} = $2",
+                };
+                let sql = self.sql(&format!("UPDATE {{table}} SET {{value}} = $3, {{ttl}} = $4 WHERE {{key}} = $1 AND {} AND ({{ttl}} IS NULL OR {{ttl}} > $5)", matches));
+                let query = self.bind_value(sqlx::query(&sql).bind(key), &expected)?;
+                self.bind_value(query, &new)?
+                    .bind(ttl)
+                    .bind(now)
+                    .execute(&*self.pool)
+                    .await
+            }
+            None => {
+                // Only an expired row may be replaced when the key is expected to be absent.
+                let sql = self.sql("INSERT INTO {table} AS t ({key}, {value}, {ttl}) VALUES ($1, $2, $3) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {ttl} = EXCLUDED.{ttl} WHERE t.{ttl} IS NOT NULL AND t.{ttl} <= $4");
+                self.bind_value(sqlx::query(&sql).bind(key), &new)?
+                    .bind(ttl)
+                    .bind(now)
+                    .execute(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        // Locking the row in a subquery lets the update return the value it replaces.
+        let update = self.sql("UPDATE {table} AS t SET {value} = $2, {ttl} = $3 FROM (SELECT {key}, {value}, {ttl} FROM {table} WHERE {key} = $1 FOR UPDATE) AS old WHERE t.{key} = old.{key} RETURNING old.{value}, old.{ttl}");
+        let insert = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES ($1, $2, $3) ON CONFLICT({key}) DO NOTHING");
+        let ttl = expires_at(ttl).map(clamp_i64);
+
+        loop {
+            let row = self
+                .bind_value(sqlx::query(&update).bind(key), &value)?
+                .bind(ttl)
+                .fetch_optional(&*self.pool)
+                .await
+                .map_err(|e| query_error(e, "Failed to swap the value"))?;
+            if let Some(row) = row {
+                let now = now_secs() as i64;
+                if row.get::<Option<i64>, _>(1).is_some_and(|ttl| ttl <= now) {
+                    return Ok(None);
+                }
+                return self.decode_value(&row, 0, key).map(Some);
+            }
+
+            let inserted = self
+                .bind_value(sqlx::query(&insert).bind(key), &value)?
+                .bind(ttl)
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| query_error(e, "Failed to swap the value"))?;
+            if inserted.rows_affected() == 1 {
+                return Ok(None);
+            }
+            // Another writer created the key in between; update it instead.
+        }
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let incremented = match self.value_type {
+            ValueType::Text => "(t.{value}::bigint + $3)::text",
+            ValueType::Jsonb => "to_jsonb(t.{value}::bigint + $3)",
+        };
+        // An expired row is restarted from `delta` rather than incremented.
+        let sql = self.sql(&format!("INSERT INTO {{table}} AS t ({{key}}, {{value}}) VALUES ($1, $2) ON CONFLICT({{key}}) DO UPDATE SET
+                {{value}} = CASE WHEN t.{{ttl}} IS NOT NULL AND t.{{ttl}} <= $4 THEN EXCLUDED.{{value}} ELSE {} END,
+                {{ttl}} = CASE WHEN t.{{ttl}} IS NOT NULL AND t.{{ttl}} <= $4 THEN NULL ELSE t.{{ttl}} END
+            RETURNING {{value}}", incremented));
+        let row = self
+            .bind_value(sqlx::query(&sql).bind(key), &Value::from(delta))?
+            .bind(delta)
+            .bind(now_secs() as i64)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| match e.as_database_error().and_then(|e| e.code()) {
+                // invalid_text_representation or, for jsonb, invalid_parameter_value: the
+                // stored value is not an integer.
+                Some(code) if code == "22P02" || code == "22023" => {
+                    StoreError::NotAnInteger(key.to_string())
+                }
+                _ => query_error(e, "Failed to increment the value"),
+            })?;
+
+        self.decode_value(&row, 0, key)?
+            .as_i64()
+            .ok_or_else(|| StoreError::NotAnInteger(key.to_string()))
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let query = self.sql(
+            "UPDATE {table} SET {ttl} = $2 WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $3)",
         );
-        sqlx::query(&sql)
+        let result = sqlx::query(&query)
             .bind(key)
-            .bind(value_str)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .bind(now_secs() as i64)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to update the TTL"))?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        // Capped to stay within BIGINT. `LEAST` ignores NULLs, so the ttl is kept outside
+        // of it for a NULL ttl to stay NULL.
+        let query = self.sql(
+            "UPDATE {table} SET {ttl} = {ttl} + LEAST($2, 9223372036854775807 - {ttl}) WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $3)",
+        );
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(clamp_i64(by_secs))
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to extend the TTL"))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        let path = JsonPath::parse(path)?;
+        let document = match self.value_type {
+            ValueType::Text => "{value}::jsonb",
+            ValueType::Jsonb => "{value}",
+        };
+        let updated = match self.value_type {
+            ValueType::Text => format!("jsonb_set({}, $2::text[], $3)::text", document),
+            ValueType::Jsonb => format!("jsonb_set({}, $2::text[], $3)", document),
+        };
+        // `jsonb_set` reads a numeric segment as a field of an object and as an index of
+        // an array, and appends past the end of an array, so check the parent first.
+        let parent = format!("({} #> $4::text[])", document);
+        let condition = match path.last() {
+            PathSegment::Field(_) => format!("jsonb_typeof({}) = 'object'", parent),
+            // `jsonb_array_length` fails on anything but an array, and `AND` may evaluate
+            // its operands in any order.
+            PathSegment::Index(index) => format!(
+                "CASE WHEN jsonb_typeof({0}) = 'array' THEN jsonb_array_length({0}) > {1} ELSE false END",
+                parent, index
+            ),
+        };
+        let query = self.sql(&format!(
+            "UPDATE {{table}} SET {{value}} = {} WHERE {{key}} = $1 AND ({{ttl}} IS NULL OR {{ttl}} > $5) AND {}",
+            updated, condition
+        ));
+        let result = sqlx::query(&query)
+            .bind(key)
+            .bind(path.to_pg_array())
+            .bind(Json(value))
+            .bind(path.parent().to_pg_array())
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to update the JSON path"))?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = $1", self.get_table_name());
+        let query = self.explain(SqlOperation::Remove);
         sqlx::query(&query)
             .bind(key)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
 
         Ok(())
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = ANY($1)", self.get_table_name());
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let query = self.sql("DELETE FROM {table} WHERE {key} = $1 RETURNING {value}, {ttl}");
+        let result = sqlx::query(&query)
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
 
-        sqlx::query(&query)
+        // A value that cannot be decoded is kept: returning early drops the transaction,
+        // which rolls the delete back.
+        let now = now_secs() as i64;
+        let value = result
+            .filter(|row| row.get::<Option<i64>, _>(1).is_none_or(|ttl| ttl > now))
+            .map(|row| self.decode_value(&row, 0, key))
+            .transpose()?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+        Ok(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let query = self.sql("DELETE FROM {table} WHERE {key} = ANY($1) RETURNING {ttl}");
+
+        let rows = sqlx::query(&query)
             .bind(keys)
-            .execute(&*self.pool)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
+
+        // Expired rows are deleted too, but they were not present from the caller's view.
+        let now = now_secs() as i64;
+        Ok(rows
+            .iter()
+            .filter(|row| row.get::<Option<i64>, _>(0).is_none_or(|ttl| ttl > now))
+            .count() as u64)
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let set = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES ($1, $2, $3) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {ttl} = EXCLUDED.{ttl}");
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = $1");
+        let mut tx = self
+            .pool
+            .begin()
             .await
-            .map_err(|_| StoreError::QueryError("Failed to remove the keys".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Returning early drops `tx`, which rolls back everything applied so far.
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    self.bind_value(sqlx::query(&set).bind(key), value)?
+                        .bind(expires_at(*ttl).map(clamp_i64))
+                        .execute(&mut *tx)
+                        .await
+                }
+                BatchOp::Remove { key } => sqlx::query(&remove).bind(key).execute(&mut *tx).await,
+            }
+            .map_err(|e| query_error(e, "Failed to apply the batch"))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
 
         Ok(())
     }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let exists = self.sql(
+            "SELECT 1 FROM {table} WHERE {key} = $1 AND ({ttl} IS NULL OR {ttl} > $2) FOR UPDATE",
+        );
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = $1");
+        let rename = self.sql("UPDATE {table} SET {key} = $2 WHERE {key} = $1");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let found = sqlx::query(&exists)
+            .bind(from)
+            .bind(now_secs() as i64)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?
+            .is_some();
+        if !found || from == to {
+            return Ok(found);
+        }
+
+        // Clear the destination first, so the update cannot hit its unique key.
+        sqlx::query(&remove)
+            .bind(to)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+        sqlx::query(&rename)
+            .bind(from)
+            .bind(to)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let rows = match prefix {
+            Some(prefix) => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {key} LIKE $1 ESCAPE '\\' AND ({ttl} IS NULL OR {ttl} > $2)");
+                sqlx::query(&query)
+                    .bind(like_prefix_pattern(prefix))
+                    .bind(now_secs() as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+            None => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {ttl} IS NULL OR {ttl} > $1");
+                sqlx::query(&query)
+                    .bind(now_secs() as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to list the keys"))?;
+
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        let query = self.sql("SELECT {key}, {value} FROM {table} WHERE {key} >= $1 AND {key} < $2 AND ({ttl} IS NULL OR {ttl} > $3) ORDER BY {key}");
+        let rows = sqlx::query(&query)
+            .bind(start)
+            .bind(end)
+            .bind(now_secs() as i64)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the range"))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let key: String = row.get(0);
+                let value = self.decode_value(&row, 1, &key)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(self.export().map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        Box::pin(self.scan(prefix).map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.scan("")
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let query =
+            self.sql("SELECT COUNT(*) AS count FROM {table} WHERE {ttl} IS NULL OR {ttl} > $1");
+        let row = sqlx::query(&query)
+            .bind(now_secs() as i64)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to count the entries"))?;
+
+        Ok(row.get::<i64, _>("count") as usize)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {}", self.get_table_name());
+        let query = self.sql("DELETE FROM {table}");
 
         sqlx::query(&query)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to clear the table".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to clear the table"))?;
 
         Ok(())
     }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        // Expired rows are left for the cleanup task, so only live keys are counted.
+        let query = self.sql(
+            "DELETE FROM {table} WHERE {key} LIKE $1 ESCAPE '\\' AND ({ttl} IS NULL OR {ttl} > $2)",
+        );
+        let result = sqlx::query(&query)
+            .bind(like_prefix_pattern(prefix))
+            .bind(now_secs() as i64)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        if let Some(task) = &self.cleanup_task {
+            task.abort();
+        }
+        self.pool.close().await;
+        Ok(())
+    }
+}
+
+/// Maps a failed query to `StoreError::Transient` when retrying it may succeed and to
+/// `StoreError::QueryError` with `message` otherwise.
+///
+/// Serialization failures, deadlocks, lock timeouts and connection exceptions (SQLSTATE
+/// class `08`) are transient, as are I/O errors and pool timeouts.
+fn query_error(e: sqlx::Error, message: &str) -> StoreError {
+    let transient = is_transient_sqlx(&e, |e| match e.code() {
+        Some(code) => {
+            matches!(&*code, "40001" | "40P01" | "55P03" | "57P03") || code.starts_with("08")
+        }
+        None => false,
+    });
+    if transient {
+        StoreError::Transient(format!("{}: {}", message, e))
+    } else {
+        StoreError::QueryError(message.to_string())
+    }
 }
@@ -0,0 +1,2 @@
+mod readonly;
+pub use readonly::*;
@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// A store that only lets reads through to an inner store.
+///
+/// Every write, removal, clear and `optimize` fails with `StoreError::ReadOnly` without
+/// touching the inner store, which guards a read replica against accidental writes.
+/// `initialize` only runs `health_check`, since creating or migrating a table is itself
+/// a write; the table must already exist.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, readonly::ReadOnlyStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let keyv = Keyv::try_new(ReadOnlyStore::new(Arc::new(InMemoryStore::new())))
+///     .await
+///     .unwrap();
+/// assert!(keyv.get("user").await.unwrap().is_none());
+/// assert!(keyv.set("user", "alice").await.is_err());
+/// # };
+/// ```
+pub struct ReadOnlyStore {
+    inner: Arc<dyn Store>,
+}
+
+impl ReadOnlyStore {
+    /// Creates a store that rejects writes to `inner`.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Store for ReadOnlyStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.inner.get_many(keys).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, _key: &str, _value: Value, _ttl: Option<u64>) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn set_with_ttl(&self, _key: &str, _value: Value, _ttl: Ttl) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn set_bytes(
+        &self,
+        _key: &str,
+        _value: &[u8],
+        _ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.inner.get_bytes(key).await
+    }
+
+    async fn set_many(&self, _entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn set_group(
+        &self,
+        _entries: &[(&str, Value)],
+        _ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn compare_and_swap(
+        &self,
+        _key: &str,
+        _expected: Option<Value>,
+        _new: Value,
+        _ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn set_nx(
+        &self,
+        _key: &str,
+        _value: Value,
+        _ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn swap(
+        &self,
+        _key: &str,
+        _value: Value,
+        _ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn increment(&self, _key: &str, _delta: i64) -> Result<i64, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn expire(&self, _key: &str, _ttl: Option<u64>) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn extend_ttl(&self, _key: &str, _by_secs: u64) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get_field(key, field).await
+    }
+
+    async fn set_field(
+        &self,
+        _key: &str,
+        _field: &str,
+        _value: Value,
+        _ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn set_json_path(
+        &self,
+        _key: &str,
+        _path: &str,
+        _value: Value,
+    ) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn remove_field(&self, _key: &str, _field: &str) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn remove(&self, _key: &str) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn get_and_remove(&self, _key: &str) -> Result<Option<Value>, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn remove_many(&self, _keys: &[&str]) -> Result<u64, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn apply_batch(&self, _ops: &[BatchOp]) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn rename(&self, _from: &str, _to: &str) -> Result<bool, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.inner.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn import(&self, _entries: ExportStream<'_>) -> Result<u64, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.inner.is_empty().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn clear_prefix(&self, _prefix: &str) -> Result<u64, StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        Err(StoreError::ReadOnly)
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
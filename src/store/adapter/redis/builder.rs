@@ -1,16 +1,25 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-pub use redis::Client;
+use redis::IntoConnectionInfo;
+pub use redis::{cluster::ClusterClient, Client};
 
-use crate::StoreError;
+use crate::{
+    utils::{connect_with_retry, DEFAULT_RETRY_BACKOFF},
+    JsonSerializer, Serializer, StoreError,
+};
 
-use super::RedisStore;
+use super::{connection::RedisClient, RedisStore};
 
 pub struct RedisStoreBuilder {
     connection_string: Option<String>,
     client: Option<Arc<Client>>,
+    cluster_nodes: Option<Vec<String>>,
     default_ttl: Option<u64>,
     namespace: Option<String>, // Adding namespace option
+    serializer: Option<Arc<dyn Serializer>>,
+    connect_retries: u32,
+    retry_backoff: Option<Duration>,
+    max_connections: Option<u32>,
 }
 
 /// Builder for creating a `RedisStore`.
@@ -62,6 +71,23 @@ pub struct RedisStoreBuilder {
 /// # }
 /// ```
 ///
+/// ## Connecting to a Cluster
+///
+/// ```rust,no_run
+/// # use keyv::adapter::redis::{RedisStoreBuilder};
+/// # #[tokio::main]
+/// # async fn main() {
+/// let store = RedisStoreBuilder::new()
+///     .cluster(vec![
+///         "redis://10.0.0.1:6379".to_string(),
+///         "redis://10.0.0.2:6379".to_string(),
+///     ])
+///     .build()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+///
 /// These examples demonstrate the versatility of `RedisStoreBuilder` in configuring a `RedisStore`, either through direct connection parameters or by leveraging an existing client setup.
 impl RedisStoreBuilder {
     /// Creates a new builder instance with default configuration.
@@ -72,8 +98,13 @@ impl RedisStoreBuilder {
         Self {
             connection_string: None,
             client: None,
+            cluster_nodes: None,
             default_ttl: None,
             namespace: None,
+            serializer: None,
+            connect_retries: 0,
+            retry_backoff: None,
+            max_connections: None,
         }
     }
 
@@ -114,6 +145,22 @@ impl RedisStoreBuilder {
         self
     }
 
+    /// Connects to a Redis cluster through the given seed nodes.
+    ///
+    /// The cluster client discovers the remaining nodes from the seeds, routes every
+    /// command to the node owning its key and follows `MOVED`/`ASK` redirections. The
+    /// password and TLS settings of the first seed URL are used for every node. See
+    /// `RedisStore` for the operations whose keys must share a slot. If set, `uri` is
+    /// ignored; `client` still takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Connection strings of one or more cluster nodes.
+    pub fn cluster(mut self, nodes: Vec<String>) -> Self {
+        self.cluster_nodes = Some(nodes);
+        self
+    }
+
     /// Sets the default TTL (time to live) for the keys in the `RedisStore`.
     ///
     /// This method configures the default TTL for the keys. If not set,
@@ -127,6 +174,66 @@ impl RedisStoreBuilder {
         self
     }
 
+    /// Sets the format used to encode values before they are sent to Redis.
+    ///
+    /// If not set, `JsonSerializer` is used, which keeps existing data readable. With a
+    /// binary format, `increment` falls back to a `WATCH`/`MULTI` transaction because
+    /// Redis `INCR` only understands decimal text.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - The serializer applied to every value.
+    pub fn serializer(mut self, serializer: Arc<dyn Serializer>) -> Self {
+        self.serializer = Some(serializer);
+        self
+    }
+
+    /// Sets how many times the initial connection is retried before `build` gives up.
+    ///
+    /// Retries wait `retry_backoff`, doubling the delay after each failed attempt, and
+    /// every failed attempt is logged. Only applies when the client is created from `uri`
+    /// or `cluster`; a connection is then opened during `build` to check that the server
+    /// is reachable. Defaults to zero, so a failed first attempt is returned immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - The number of attempts made after the first one fails.
+    pub fn connect_retries(mut self, retries: u32) -> Self {
+        self.connect_retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first connection retry.
+    ///
+    /// The delay doubles after each further failed attempt. Defaults to 500 milliseconds.
+    /// Has no effect unless `connect_retries` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The delay before the first retry.
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = Some(backoff);
+        self
+    }
+
+    /// Keeps up to `max` connections open in a pool shared by all calls.
+    ///
+    /// Without a pool, every call opens its own connection and closes it afterwards,
+    /// which is enough for light use. With one, calls reuse idle connections and up to
+    /// `max` of them run in parallel; further calls wait up to 30 seconds for a
+    /// connection to be returned, on a blocking thread rather than the async worker,
+    /// then fail with `StoreError::ConnectionError`.
+    /// Connections are opened on demand. `keys`, `iter` and `clear_prefix` talk to each
+    /// server through connections of their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of open connections, at least 1.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = Some(max.max(1));
+        self
+    }
+
     /// Builds the `RedisStore` based on the provided configurations.
     ///
     /// Finalizes the builder process and creates a `RedisStore` instance.
@@ -137,23 +244,59 @@ impl RedisStoreBuilder {
     /// This method returns a `Result` which, on success, contains the initialized `RedisStore`.
     /// On failure, it returns a `StoreError` indicating what went wrong during the initialization.
     pub async fn build(self) -> Result<RedisStore, StoreError> {
-        let client = match self.client {
-            Some(client) => client,
-            None => {
+        let client = match (self.client, self.cluster_nodes) {
+            (Some(client), _) => RedisClient::Single(client),
+            (None, Some(nodes)) => {
+                let seed = nodes
+                    .first()
+                    .ok_or_else(|| {
+                        StoreError::ConnectionError("No cluster node was given".to_string())
+                    })?
+                    .as_str()
+                    .into_connection_info()
+                    .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+                let client = ClusterClient::new(nodes)
+                    .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+                RedisClient::Cluster {
+                    client: Arc::new(client),
+                    seed,
+                }
+            }
+            (None, None) => {
                 let connection_string = self
                     .connection_string
-                    .expect("A connection string or an existing client must be set");
-                Arc::new(
-                    Client::open(connection_string)
-                        .map_err(|e| StoreError::ConnectionError(e.to_string()))?,
-                )
+                    .expect("A connection string, cluster nodes or an existing client must be set");
+                let client = Client::open(connection_string)
+                    .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+                RedisClient::Single(Arc::new(client))
             }
         };
 
+        // Opening a client does not connect, so only probe the server when asked to wait
+        // for it.
+        if self.connect_retries > 0 {
+            connect_with_retry(
+                self.connect_retries,
+                self.retry_backoff.unwrap_or(DEFAULT_RETRY_BACKOFF),
+                || async { client.get_connection().map(|_| ()) },
+            )
+            .await
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+        }
+
+        let pool = self.max_connections.map(|max| {
+            r2d2::Pool::builder()
+                .max_size(max)
+                .min_idle(Some(0))
+                .build_unchecked(client.clone())
+        });
+
         Ok(RedisStore {
             client,
+            pool,
             default_ttl: self.default_ttl,
             namespace: self.namespace,
+            serializer: self.serializer.unwrap_or_else(|| Arc::new(JsonSerializer)),
         })
     }
 }
@@ -0,0 +1,178 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use redis::{
+    cluster::{ClusterClient, ClusterConnection},
+    Client, ConnectionAddr, ConnectionInfo, ConnectionLike, RedisError, RedisResult, Value,
+};
+
+/// The client a `RedisStore` talks through: a single server or a cluster.
+#[derive(Clone)]
+pub(crate) enum RedisClient {
+    Single(Arc<Client>),
+    Cluster {
+        client: Arc<ClusterClient>,
+        /// Connection settings of one seed node, reused to reach each primary directly.
+        seed: ConnectionInfo,
+    },
+}
+
+impl RedisClient {
+    pub(crate) fn get_connection(&self) -> RedisResult<RedisConnection> {
+        match self {
+            RedisClient::Single(client) => client.get_connection().map(RedisConnection::Single),
+            RedisClient::Cluster { client, .. } => client
+                .get_connection()
+                .map(|conn| RedisConnection::Cluster(Box::new(conn))),
+        }
+    }
+
+    /// Returns one client per server holding a share of the keyspace.
+    ///
+    /// Commands such as `SCAN` only see the keys of the server they run on, so they must
+    /// be sent to every primary of a cluster in turn.
+    pub(crate) fn scan_clients(&self) -> RedisResult<Vec<Client>> {
+        let (client, seed) = match self {
+            RedisClient::Single(client) => return Ok(vec![client.as_ref().clone()]),
+            RedisClient::Cluster { client, seed } => (client, seed),
+        };
+
+        let mut conn = client.get_connection()?;
+        let slots: Value = redis::cmd("CLUSTER").arg("SLOTS").query(&mut conn)?;
+
+        // Each slot range is `[start, end, [host, port, id, ...], replicas...]`.
+        let mut primaries: Vec<(String, u16)> = Vec::new();
+        if let Value::Bulk(ranges) = slots {
+            for range in ranges {
+                let Value::Bulk(range) = range else { continue };
+                let Some(Value::Bulk(primary)) = range.get(2) else {
+                    continue;
+                };
+                let (Some(host), Some(port)) = (primary.first(), primary.get(1)) else {
+                    continue;
+                };
+                let primary = (
+                    redis::from_redis_value(host)?,
+                    redis::from_redis_value(port)?,
+                );
+                if !primaries.contains(&primary) {
+                    primaries.push(primary);
+                }
+            }
+        }
+
+        primaries
+            .into_iter()
+            .map(|(primary_host, primary_port)| {
+                let mut info = seed.clone();
+                if let ConnectionAddr::Tcp(host, port) | ConnectionAddr::TcpTls { host, port, .. } =
+                    &mut info.addr
+                {
+                    *host = primary_host;
+                    *port = primary_port;
+                }
+                Client::open(info)
+            })
+            .collect()
+    }
+}
+
+/// Lets `RedisStoreBuilder::max_connections` keep connections open in an `r2d2` pool.
+impl r2d2::ManageConnection for RedisClient {
+    type Connection = RedisConnection;
+    type Error = RedisError;
+
+    fn connect(&self) -> RedisResult<RedisConnection> {
+        self.get_connection()
+    }
+
+    fn is_valid(&self, conn: &mut RedisConnection) -> RedisResult<()> {
+        redis::cmd("PING").query(conn)
+    }
+
+    fn has_broken(&self, conn: &mut RedisConnection) -> bool {
+        !conn.is_open()
+    }
+}
+
+/// A connection opened by `RedisClient`.
+///
+/// Cluster connections route each command to the node owning its key and follow
+/// `MOVED`/`ASK` redirections. Multi-key commands whose keys span several slots are only
+/// split by the client for `MGET`, `DEL` and `EXISTS`; anything else, including `MULTI`
+/// transactions, must keep its keys in one slot.
+pub(crate) enum RedisConnection {
+    Single(redis::Connection),
+    Cluster(Box<ClusterConnection>),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        cmd: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+
+    fn check_connection(&mut self) -> bool {
+        match self {
+            RedisConnection::Single(conn) => conn.check_connection(),
+            RedisConnection::Cluster(conn) => conn.check_connection(),
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        match self {
+            RedisConnection::Single(conn) => conn.is_open(),
+            RedisConnection::Cluster(conn) => conn.is_open(),
+        }
+    }
+}
+
+/// A connection used for a single store call: opened for it, or borrowed from the pool
+/// and returned to it on drop.
+pub(crate) enum PooledConnection {
+    Owned(RedisConnection),
+    Pooled(r2d2::PooledConnection<RedisClient>),
+}
+
+impl Deref for PooledConnection {
+    type Target = RedisConnection;
+
+    fn deref(&self) -> &RedisConnection {
+        match self {
+            PooledConnection::Owned(conn) => conn,
+            PooledConnection::Pooled(conn) => conn,
+        }
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut RedisConnection {
+        match self {
+            PooledConnection::Owned(conn) => conn,
+            PooledConnection::Pooled(conn) => conn,
+        }
+    }
+}
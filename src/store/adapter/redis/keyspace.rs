@@ -0,0 +1,186 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use redis::{Client, RedisResult};
+
+use crate::adapter::events::StoreEvent;
+
+/// How long a blocked read waits before checking whether the listener was stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Delay before reconnecting when `KeyspaceListener::with_reconnect_delay` is not set.
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Turns Redis keyspace notifications into `StoreEvent`s, so that changes made by other
+/// processes reach the same subscribers as local writes.
+///
+/// The listener subscribes to the `__keyevent@<db>__:set` and `__keyevent@<db>__:del`
+/// channels and reports them as `StoreEvent::Set` and `StoreEvent::Removed`. Other
+/// commands, such as `INCRBY`, `RENAME` or keys expiring, are not forwarded. Redis only
+/// publishes these notifications once they are enabled on the server, for example with
+/// `CONFIG SET notify-keyspace-events E$g`; without that the listener receives nothing.
+///
+/// Notifications are sent for every client, this process included, so a write made
+/// through `Keyv` is reported twice: once by `Keyv` itself and once by the listener.
+/// Redis delivers them at most once, so changes made while the listener is reconnecting
+/// are missed. With a namespace, only keys under it are forwarded, without the prefix.
+/// In a cluster, each primary known when the listener is created is followed by a
+/// connection of its own.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::{Keyv, adapter::redis::RedisStoreBuilder};
+/// # async {
+/// let store = RedisStoreBuilder::new()
+///     .uri("redis://localhost:6379")
+///     .namespace("app")
+///     .build()
+///     .await
+///     .unwrap();
+/// let listener = store.keyspace_listener().unwrap();
+///
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// let sink = keyv.clone();
+/// let _handle = listener.spawn(move |event| sink.publish(event));
+///
+/// let mut events = keyv.subscribe();
+/// // Receives the keys other services change in redis as well.
+/// let event = events.recv().await.unwrap();
+/// # };
+/// ```
+pub struct KeyspaceListener {
+    clients: Vec<Client>,
+    namespace: Option<String>,
+    reconnect_delay: Duration,
+}
+
+impl KeyspaceListener {
+    /// Creates a listener following the servers behind `clients`.
+    ///
+    /// `RedisStore::keyspace_listener` fills in the clients and the namespace of a store.
+    pub fn new(clients: Vec<Client>) -> Self {
+        Self {
+            clients,
+            namespace: None,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+        }
+    }
+
+    /// Only forwards keys under `namespace`, reporting them without the `namespace:`
+    /// prefix, as `RedisStoreBuilder::namespace` writes them.
+    pub fn with_namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Sets how long to wait before reconnecting after the connection is lost. Defaults
+    /// to one second.
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Starts listening in the background, calling `on_event` for every notification.
+    ///
+    /// Each server is followed on a thread of its own, since the subscription blocks
+    /// while waiting for messages. A lost connection is logged and reopened after the
+    /// reconnect delay, until the returned handle is stopped or dropped.
+    pub fn spawn<F>(self, on_event: F) -> KeyspaceListenerHandle
+    where
+        F: Fn(StoreEvent) + Send + Sync + 'static,
+    {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let on_event = Arc::new(on_event);
+        let prefix = self.namespace.map(|namespace| format!("{}:", namespace));
+        let threads = self
+            .clients
+            .into_iter()
+            .map(|client| {
+                let stopped = stopped.clone();
+                let on_event = on_event.clone();
+                let prefix = prefix.clone();
+                let reconnect_delay = self.reconnect_delay;
+                std::thread::spawn(move || {
+                    while !stopped.load(Ordering::Relaxed) {
+                        if let Err(e) = listen(&client, prefix.as_deref(), &stopped, &*on_event) {
+                            log::warn!("Keyspace notification listener disconnected: {}", e);
+                            std::thread::sleep(reconnect_delay);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        KeyspaceListenerHandle { stopped, threads }
+    }
+}
+
+/// Keeps a `KeyspaceListener` running. Dropping it stops the listener.
+pub struct KeyspaceListenerHandle {
+    stopped: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl KeyspaceListenerHandle {
+    /// Stops the listener and waits for its connections to close.
+    pub fn stop(mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for KeyspaceListenerHandle {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Subscribes to the notifications of one server and forwards them until `stopped` is
+/// set, or until the connection fails.
+fn listen(
+    client: &Client,
+    prefix: Option<&str>,
+    stopped: &AtomicBool,
+    on_event: &(dyn Fn(StoreEvent) + Send + Sync),
+) -> RedisResult<()> {
+    let db = client.get_connection_info().redis.db;
+    let set_channel = format!("__keyevent@{}__:set", db);
+    let del_channel = format!("__keyevent@{}__:del", db);
+
+    let mut conn = client.get_connection()?;
+    conn.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut pubsub = conn.as_pubsub();
+    pubsub.subscribe(&[&set_channel, &del_channel])?;
+
+    while !stopped.load(Ordering::Relaxed) {
+        let message = match pubsub.get_message() {
+            Ok(message) => message,
+            Err(e) if e.is_timeout() => continue,
+            Err(e) => return Err(e),
+        };
+        let key: String = message.get_payload()?;
+        let key = match prefix {
+            Some(prefix) => match key.strip_prefix(prefix) {
+                Some(key) => key.to_string(),
+                None => continue,
+            },
+            None => key,
+        };
+        if message.get_channel_name() == set_channel {
+            on_event(StoreEvent::Set { key });
+        } else {
+            on_event(StoreEvent::Removed { key });
+        }
+    }
+
+    Ok(())
+}
@@ -3,3 +3,8 @@ pub use redis::*;
 
 mod builder;
 pub use builder::*;
+
+mod connection;
+
+mod keyspace;
+pub use keyspace::*;
@@ -1,17 +1,119 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
 use async_trait::async_trait;
-use redis::{Client, Commands};
-use serde_json::Value;
+use futures::{stream, TryStreamExt};
+use redis::{cluster::cluster_pipe, Client, Commands};
+use serde_json::{Map, Value};
 
-use crate::{Store, StoreError};
+use super::{
+    connection::{PooledConnection, RedisClient, RedisConnection},
+    KeyspaceListener,
+};
+use crate::{
+    utils::{clamp_i64, ITER_BATCH_SIZE},
+    BatchOp, EntryStream, Serializer, Store, StoreError, Ttl,
+};
 
+/// Writes `ARGV[3]` to `KEYS[1]` only if its current value equals `ARGV[2]`, or if the
+/// key is absent when `ARGV[1]` is `0`. `ARGV[4]` is an expiry in seconds, `0` for none.
+const COMPARE_AND_SWAP_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+if ARGV[1] == '1' then
+    if current ~= ARGV[2] then return 0 end
+elseif current then
+    return 0
+end
+if tonumber(ARGV[4]) > 0 then
+    redis.call('SET', KEYS[1], ARGV[3], 'EX', ARGV[4])
+else
+    redis.call('SET', KEYS[1], ARGV[3])
+end
+return 1
+";
+
+/// Adds `ARGV[1]` milliseconds to the expiry of `KEYS[1]`. Answers `0` if the key does not
+/// exist and `1` otherwise, leaving a key without expiry untouched.
+const EXTEND_TTL_SCRIPT: &str = r"
+local ttl = redis.call('PTTL', KEYS[1])
+if ttl == -2 then return 0 end
+if ttl >= 0 then
+    redis.call('PEXPIRE', KEYS[1], math.min(ttl + tonumber(ARGV[1]), 9007199254740991))
+end
+return 1
+";
+
+/// A store backed by a Redis server or a Redis cluster.
+///
+/// Each call opens a connection of its own unless `RedisStoreBuilder::max_connections`
+/// sets up a pool, which lets concurrent calls reuse a bounded set of connections.
+///
+/// The store is built on the synchronous redis client and its `r2d2` pool rather than an
+/// async one. Connections are opened, or waited for, on tokio's blocking thread pool, so
+/// a slow server or an exhausted pool does not stall other tasks. The commands themselves
+/// still run on the calling task and block its worker thread for one round trip each.
+///
+/// Expiry is handled by Redis itself. In cluster mode, built with
+/// `RedisStoreBuilder::cluster`, every single-key command is routed to the node owning
+/// the key, and `get_many` and `remove_many` are split per slot by the client. Commands
+/// that need all of their keys in one slot behave differently:
+///
+/// - `set_many` is applied per key without `MULTI`/`EXEC`, so it is not atomic.
+/// - `apply_batch` still runs in one `MULTI`/`EXEC` and fails with `CROSSSLOT` unless all
+///   keys hash to the same slot. Use a hash tag, e.g. `{order:1}:status` and
+///   `{order:1}:items`, to keep related keys together.
+/// - `rename` uses `RENAME`, which fails with `CROSSSLOT` unless both keys hash to the
+///   same slot.
+/// - `keys`, `iter` and `clear_prefix` scan every primary in turn.
+///
+/// `set_field` and `remove_field` keep the object in the key's regular string value, so
+/// `get` still returns the whole record, and update it in a `WATCH`/`MULTI` transaction
+/// that retries on conflict. Concurrent writes to different fields of one key are never
+/// lost.
 pub struct RedisStore {
-    pub(crate) client: Arc<Client>,
+    pub(crate) client: RedisClient,
+    pub(crate) pool: Option<r2d2::Pool<RedisClient>>,
     pub(crate) default_ttl: Option<u64>,
     pub(crate) namespace: Option<String>,
+    pub(crate) serializer: Arc<dyn Serializer>,
 }
 impl RedisStore {
+    /// Takes a connection from the pool, or opens a new one when pooling is disabled.
+    async fn connection(&self) -> Result<PooledConnection, StoreError> {
+        let pool = self.pool.clone();
+        let client = self.client.clone();
+        blocking(move || match pool {
+            Some(pool) => pool
+                .get()
+                .map(PooledConnection::Pooled)
+                .map_err(|e| e.to_string()),
+            None => client
+                .get_connection()
+                .map(PooledConnection::Owned)
+                .map_err(|e| e.to_string()),
+        })
+        .await
+    }
+
+    /// Creates a `KeyspaceListener` following the servers and the namespace of this store,
+    /// to report the changes other clients make to its keys.
+    ///
+    /// In a cluster, the listener follows every primary known at the time of the call.
+    ///
+    /// # Returns
+    /// The listener, not started yet, or `StoreError::ConnectionError` if the primaries of
+    /// a cluster cannot be listed.
+    pub fn keyspace_listener(&self) -> Result<KeyspaceListener, StoreError> {
+        let clients = self
+            .client
+            .scan_clients()
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+        let listener = KeyspaceListener::new(clients);
+        Ok(match &self.namespace {
+            Some(namespace) => listener.with_namespace(namespace.clone()),
+            None => listener,
+        })
+    }
+
     fn get_key(&self, key: &str) -> String {
         if let Some(ref ns) = self.namespace {
             format!("{}:{}", ns, key)
@@ -19,6 +121,219 @@ impl RedisStore {
             key.to_string()
         }
     }
+
+    fn decode(&self, value: Option<Vec<u8>>) -> Result<Option<Value>, StoreError> {
+        value
+            .map(|bytes| self.serializer.deserialize(&bytes))
+            .transpose()
+    }
+
+    /// Increments a counter stored in a format redis `INCR` cannot read, using an
+    /// optimistic `WATCH`/`MULTI` transaction that keeps the key's TTL.
+    fn increment_encoded(
+        &self,
+        conn: &mut RedisConnection,
+        key: &str,
+        delta: i64,
+    ) -> Result<i64, StoreError> {
+        let namespaced_key = self.get_key(key);
+        redis::transaction(conn, &[&namespaced_key], |conn, pipe| {
+            let current: Option<Vec<u8>> = conn.get(&namespaced_key)?;
+            let current = match self.decode(current) {
+                Ok(Some(value)) => match value.as_i64() {
+                    Some(current) => current,
+                    None => return Ok(Some(Err(StoreError::NotAnInteger(key.to_string())))),
+                },
+                Ok(None) => 0,
+                Err(e) => return Ok(Some(Err(e))),
+            };
+            let Some(new) = current.checked_add(delta) else {
+                return Ok(Some(Err(StoreError::QueryError(
+                    "Increment would overflow".to_string(),
+                ))));
+            };
+            let bytes = match self.serializer.serialize(&Value::from(new)) {
+                Ok(bytes) => bytes,
+                Err(e) => return Ok(Some(Err(e))),
+            };
+
+            let response: Option<()> = pipe
+                .cmd("SET")
+                .arg(&namespaced_key)
+                .arg(bytes)
+                .arg("KEEPTTL")
+                .ignore()
+                .query(conn)?;
+            Ok(response.map(|_| Ok(new)))
+        })
+        .map_err(query_error)?
+    }
+
+    /// Applies `update` to the JSON object stored at `key` in an optimistic `WATCH`/`MULTI`
+    /// transaction. An existing key keeps its TTL; a new one expires after `ttl`.
+    async fn update_object<F>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        mut update: F,
+    ) -> Result<bool, StoreError>
+    where
+        F: FnMut(&mut Map<String, Value>) -> bool,
+    {
+        let mut conn = self.connection().await?;
+        let namespaced_key = self.get_key(key);
+        redis::transaction(&mut *conn, &[&namespaced_key], |conn, pipe| {
+            let current: Option<Vec<u8>> = conn.get(&namespaced_key)?;
+            let exists = current.is_some();
+            let mut object = match self.decode(current) {
+                Ok(Some(Value::Object(object))) => object,
+                Ok(Some(_)) => return Ok(Some(Err(StoreError::NotAnObject(key.to_string())))),
+                Ok(None) => Map::new(),
+                Err(e) => return Ok(Some(Err(e))),
+            };
+            if !update(&mut object) {
+                return Ok(Some(Ok(false)));
+            }
+            let bytes = match self.serializer.serialize(&Value::Object(object)) {
+                Ok(bytes) => bytes,
+                Err(e) => return Ok(Some(Err(e))),
+            };
+
+            pipe.cmd("SET").arg(&namespaced_key).arg(bytes);
+            if exists {
+                pipe.arg("KEEPTTL");
+            } else if let Some(expire) = ttl.or(self.default_ttl) {
+                pipe.arg("EX").arg(expire);
+            }
+            let response: Option<()> = pipe.ignore().query(conn)?;
+            Ok(response.map(|_| Ok(true)))
+        })
+        .map_err(query_error)?
+    }
+
+    /// Returns the value for `key`, computing and storing it with `f` if it is missing.
+    ///
+    /// Unlike `StoreExt::get_or_insert_with`, the write and the read back of a concurrent
+    /// caller's value happen in one `SET ... NX GET` command, so every caller that missed
+    /// returns the value that was actually stored. Requires Redis 7.0 or later.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    /// * `ttl` - The TTL, in seconds, of a newly stored value. Falls back to the store's
+    ///   default TTL.
+    /// * `f` - Produces the value to store when the key is missing.
+    ///
+    /// # Returns
+    /// - `Ok(Value)` with the existing or newly stored value.
+    /// - `Err(StoreError)` if the read or the write fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keyv::adapter::redis::RedisStoreBuilder;
+    /// # async {
+    /// let store = RedisStoreBuilder::new()
+    ///     .uri("redis://localhost:6379")
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// let config = store
+    ///     .get_or_insert_with("config", Some(60), || async { serde_json::json!({"debug": false}) })
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    pub async fn get_or_insert_with<F, Fut>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        f: F,
+    ) -> Result<Value, StoreError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Value>,
+    {
+        if let Some(value) = self.get(key).await? {
+            return Ok(value);
+        }
+
+        let value = f().await;
+        let mut conn = self.connection().await?;
+
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(self.get_key(key))
+            .arg(self.serializer.serialize(&value)?)
+            .arg("NX")
+            .arg("GET");
+        if let Some(expire) = ttl.or(self.default_ttl) {
+            cmd.arg("EX").arg(expire);
+        }
+
+        // SET NX GET replies nil when it wrote and the current value when it did not.
+        let current: Option<Vec<u8>> = cmd.query(&mut conn).map_err(query_error)?;
+        Ok(self.decode(current)?.unwrap_or(value))
+    }
+}
+
+/// Escapes the characters that have a special meaning in a redis `MATCH` glob.
+fn escape_glob(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Runs `connect` on tokio's blocking thread pool.
+///
+/// This adapter is built on the synchronous redis client. Opening a connection, or waiting
+/// for a free one in the pool, can take seconds and would stall every other task on the
+/// worker thread.
+async fn blocking<T, E>(
+    connect: impl FnOnce() -> Result<T, E> + Send + 'static,
+) -> Result<T, StoreError>
+where
+    T: Send + 'static,
+    E: ToString + Send + 'static,
+{
+    tokio::task::spawn_blocking(connect)
+        .await
+        .map_err(|e| StoreError::ConnectionError(e.to_string()))?
+        .map_err(|e| StoreError::ConnectionError(e.to_string()))
+}
+
+/// Opens a connection of its own to one server, for the commands that visit every node.
+async fn open(client: &Client) -> Result<redis::Connection, StoreError> {
+    let client = client.clone();
+    blocking(move || client.get_connection()).await
+}
+
+/// Maps a failed command to `StoreError::Transient` when retrying it may succeed and to
+/// `StoreError::QueryError` otherwise.
+///
+/// Dropped or refused connections, timeouts and the errors redis returns while it is
+/// loading its dataset, failing over or asking the client to try again are transient.
+fn query_error(e: redis::RedisError) -> StoreError {
+    let transient = e.is_io_error()
+        || e.is_timeout()
+        || e.is_connection_dropped()
+        || e.is_connection_refusal()
+        || matches!(
+            e.kind(),
+            redis::ErrorKind::TryAgain
+                | redis::ErrorKind::BusyLoadingError
+                | redis::ErrorKind::ClusterDown
+                | redis::ErrorKind::MasterDown
+        );
+    if transient {
+        StoreError::Transient(e.to_string())
+    } else {
+        StoreError::QueryError(e.to_string())
+    }
 }
 
 #[async_trait]
@@ -27,67 +342,495 @@ impl Store for RedisStore {
         Ok(()) // Redis doesn't require initialization like a DB schema.
     }
 
-    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
+    async fn health_check(&self) -> Result<(), StoreError> {
+        let mut conn = self.connection().await?;
+        let _: String = redis::cmd("PING")
+            .query(&mut conn)
             .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-        let value: Option<String> = conn
-            .get(self.get_key(key))
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
-        match value {
-            Some(val) => Ok(serde_json::from_str(&val)
-                .map(Some)
-                .map_err(|e| StoreError::SerializationError { source: e })?),
-            None => Ok(None),
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut conn = self.connection().await?;
+        let value: Option<Vec<u8>> = conn.get(self.get_key(key)).map_err(query_error)?;
+        self.decode(value)
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+        conn.exists(self.get_key(key)).map_err(query_error)
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let mut conn = self.connection().await?;
+        let millis: i64 = conn.pttl(self.get_key(key)).map_err(query_error)?;
+
+        // PTTL answers -2 for a missing key and -1 for a key without expiry.
+        Ok((millis >= 0).then(|| (millis as u64).div_ceil(1000)))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
         }
+
+        let mut conn = self.connection().await?;
+
+        let namespaced_keys: Vec<String> = keys.iter().map(|key| self.get_key(key)).collect();
+        let values: Vec<Option<Vec<u8>>> = match *conn {
+            // The cluster client splits MGET per slot and reassembles the replies in order.
+            RedisConnection::Cluster(ref mut conn) => conn.mget(namespaced_keys),
+            // Pipelined GETs share one round-trip and, unlike MGET, do not require all keys
+            // to live in the same cluster slot. Replies come back in request order.
+            RedisConnection::Single(ref mut conn) => {
+                let mut pipe = redis::pipe();
+                for key in namespaced_keys {
+                    pipe.get(key);
+                }
+                pipe.query(conn)
+            }
+        }
+        .map_err(query_error)?;
+
+        values.into_iter().map(|value| self.decode(value)).collect()
     }
 
     async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
         let ttl = ttl.or(self.default_ttl);
         let namespaced_key = self.get_key(key);
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let mut conn = self.connection().await?;
+        let value_str = self.serializer.serialize(&value)?;
 
+        // Expiry is enforced by redis itself. A plain SET discards any previous TTL, so
+        // re-setting a key without one makes it permanent without a separate PERSIST.
         if let Some(expire) = ttl {
-            conn.set_ex(&namespaced_key, value_str, expire)
-                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+            let _: () = conn
+                .set_ex(&namespaced_key, value_str, expire)
+                .map_err(query_error)?;
+        } else {
+            let _: () = conn.set(&namespaced_key, value_str).map_err(query_error)?;
+        }
+        Ok(())
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        let mut conn = self.connection().await?;
+        let value_str = self.serializer.serialize(&value)?;
+
+        // SET ... PX keeps millisecond precision where EX would round to seconds.
+        let _: () = conn
+            .pset_ex(self.get_key(key), value_str, ttl.as_millis_ceil())
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        let mut conn = self.connection().await?;
+
+        // The bytes skip the serializer, so `get` cannot decode them.
+        if let Some(expire) = ttl.or(self.default_ttl) {
+            let _: () = conn
+                .set_ex(self.get_key(key), value, expire)
+                .map_err(query_error)?;
         } else {
-            conn.set(&namespaced_key, value_str)
-                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+            let _: () = conn.set(self.get_key(key), value).map_err(query_error)?;
+        }
+        Ok(())
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        let mut conn = self.connection().await?;
+        conn.get(self.get_key(key)).map_err(query_error)
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.connection().await?;
+
+        if let RedisConnection::Cluster(ref mut conn) = *conn {
+            // Keys may live on different nodes, so each SET is routed on its own.
+            let mut pipe = cluster_pipe();
+            for (key, value, ttl) in entries {
+                let value_str = self.serializer.serialize(value)?;
+                match ttl.or(self.default_ttl) {
+                    Some(expire) => pipe.set_ex(self.get_key(key), value_str, expire).ignore(),
+                    None => pipe.set(self.get_key(key), value_str).ignore(),
+                };
+            }
+            let _: () = pipe.query(conn).map_err(query_error)?;
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (key, value, ttl) in entries {
+            let value_str = self.serializer.serialize(value)?;
+            match ttl.or(self.default_ttl) {
+                Some(expire) => pipe.set_ex(self.get_key(key), value_str, expire).ignore(),
+                None => pipe.set(self.get_key(key), value_str).ignore(),
+            };
         }
+
+        let _: () = pipe.query(&mut conn).map_err(query_error)?;
         Ok(())
     }
 
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+
+        let expected_str = expected
+            .map(|expected| self.serializer.serialize(&expected))
+            .transpose()?;
+        let new_str = self.serializer.serialize(&new)?;
+        let ttl = ttl.or(self.default_ttl).unwrap_or(0);
+
+        let swapped: i32 = redis::Script::new(COMPARE_AND_SWAP_SCRIPT)
+            .key(self.get_key(key))
+            .arg(expected_str.is_some() as i32)
+            .arg(expected_str.unwrap_or_default())
+            .arg(new_str)
+            .arg(ttl)
+            .invoke(&mut conn)
+            .map_err(query_error)?;
+
+        Ok(swapped == 1)
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(self.get_key(key))
+            .arg(self.serializer.serialize(&value)?)
+            .arg("NX");
+        if let Some(expire) = ttl.or(self.default_ttl) {
+            cmd.arg("EX").arg(expire);
+        }
+
+        // SET NX replies OK when it wrote and nil when the key already existed.
+        let written: Option<String> = cmd.query(&mut conn).map_err(query_error)?;
+        Ok(written.is_some())
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let mut conn = self.connection().await?;
+
+        // SET ... GET (Redis 6.2+) replies with the value it replaced, or nil.
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(self.get_key(key))
+            .arg(self.serializer.serialize(&value)?)
+            .arg("GET");
+        if let Some(expire) = ttl.or(self.default_ttl) {
+            cmd.arg("EX").arg(expire);
+        }
+
+        let previous: Option<Vec<u8>> = cmd.query(&mut conn).map_err(query_error)?;
+        self.decode(previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let mut conn = self.connection().await?;
+        if !self.serializer.is_plain_text() {
+            return self.increment_encoded(&mut conn, key, delta);
+        }
+        conn.incr(self.get_key(key), delta).map_err(|e| {
+            if e.to_string().contains("not an integer") {
+                StoreError::NotAnInteger(key.to_string())
+            } else {
+                query_error(e)
+            }
+        })
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+        let namespaced_key = self.get_key(key);
+
+        match ttl {
+            Some(ttl) => conn
+                .expire(namespaced_key, clamp_i64(ttl))
+                .map_err(query_error),
+            None => {
+                // PERSIST also answers 0 for a key without expiry, so check existence in
+                // the same transaction.
+                let (exists,): (bool,) = redis::pipe()
+                    .atomic()
+                    .exists(&namespaced_key)
+                    .persist(&namespaced_key)
+                    .ignore()
+                    .query(&mut conn)
+                    .map_err(query_error)?;
+                Ok(exists)
+            }
+        }
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+        let extended: i32 = redis::Script::new(EXTEND_TTL_SCRIPT)
+            .key(self.get_key(key))
+            .arg(by_secs.saturating_mul(1000).min(i64::MAX as u64))
+            .invoke(&mut conn)
+            .map_err(query_error)?;
+
+        Ok(extended == 1)
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.update_object(key, ttl, |object| {
+            object.insert(field.to_string(), value.clone());
+            true
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.update_object(key, None, |object| object.remove(field).is_some())
+            .await
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-        conn.del(self.get_key(key))
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        let mut conn = self.connection().await?;
+        let _: () = conn.del(self.get_key(key)).map_err(query_error)?;
         Ok(())
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut conn = self.connection().await?;
+        let value: Option<Vec<u8>> = conn.get_del(self.get_key(key)).map_err(query_error)?;
+        self.decode(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = self.connection().await?;
 
         let namespaced_keys: Vec<String> = keys.iter().map(|key| self.get_key(key)).collect();
 
-        conn.del(namespaced_keys)
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        conn.del(namespaced_keys).map_err(query_error)
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.connection().await?;
+
+        // MULTI/EXEC runs the queued commands without interleaving other clients, and
+        // nothing is applied if the connection drops before EXEC. Redis has no rollback,
+        // but SET and DEL cannot fail once queued. In a cluster, keys in different slots
+        // make EXEC abort the whole batch with CROSSSLOT.
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = self.serializer.serialize(value)?;
+                    match ttl.or(self.default_ttl) {
+                        Some(expire) => pipe.set_ex(self.get_key(key), value_str, expire).ignore(),
+                        None => pipe.set(self.get_key(key), value_str).ignore(),
+                    };
+                }
+                BatchOp::Remove { key } => {
+                    pipe.del(self.get_key(key)).ignore();
+                }
+            }
+        }
+
+        let _: () = pipe.query(&mut conn).map_err(query_error)?;
         Ok(())
     }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let mut conn = self.connection().await?;
+
+        // RENAME keeps the TTL and overwrites the destination. It fails when the source is
+        // missing, which is the only expected error.
+        match redis::cmd("RENAME")
+            .arg(self.get_key(from))
+            .arg(self.get_key(to))
+            .query::<()>(&mut conn)
+        {
+            Ok(()) => Ok(true),
+            Err(e) if e.to_string().contains("no such key") => Ok(false),
+            Err(e) => Err(query_error(e)),
+        }
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let clients = self
+            .client
+            .scan_clients()
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+
+        let pattern = format!("{}*", escape_glob(&self.get_key(prefix.unwrap_or(""))));
+        let namespace_len = self.get_key("").len();
+        let mut keys = Vec::new();
+        for client in clients {
+            let mut conn = open(&client).await?;
+            keys.extend(
+                conn.scan_match::<_, String>(&pattern)
+                    .map_err(query_error)?
+                    .map(|key| key[namespace_len..].to_string()),
+            );
+        }
+
+        Ok(keys)
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.scan_prefix("")
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let pattern = format!("{}*", escape_glob(&self.get_key(prefix)));
+        let namespace_len = self.get_key("").len();
+
+        // Walks the keyspace of each server with a SCAN cursor. The state is the servers,
+        // the index of the one being scanned and its cursor; `None` marks the start.
+        let batches = stream::try_unfold(None, move |state: Option<(Vec<Client>, usize, u64)>| {
+            let pattern = pattern.clone();
+            async move {
+                let (clients, node, cursor) = match state {
+                    Some(state) => state,
+                    None => (
+                        self.client
+                            .scan_clients()
+                            .map_err(|e| StoreError::ConnectionError(e.to_string()))?,
+                        0,
+                        0,
+                    ),
+                };
+                let Some(client) = clients.get(node) else {
+                    return Ok(None);
+                };
+                let mut conn = open(client).await?;
+
+                let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(ITER_BATCH_SIZE)
+                    .query(&mut conn)
+                    .map_err(query_error)?;
+
+                let mut pipe = redis::pipe();
+                for key in &keys {
+                    pipe.get(key);
+                }
+                let values: Vec<Option<Vec<u8>>> = if keys.is_empty() {
+                    Vec::new()
+                } else {
+                    pipe.query(&mut conn).map_err(query_error)?
+                };
+
+                // Keys that expired between the SCAN and the GETs come back as nil.
+                let entries: Vec<Result<(String, Value), StoreError>> = keys
+                    .into_iter()
+                    .zip(values)
+                    .filter_map(|(key, value)| {
+                        self.decode(value)
+                            .map(|value| {
+                                value.map(|value| (key[namespace_len..].to_string(), value))
+                            })
+                            .transpose()
+                    })
+                    .collect();
+                let next = match next {
+                    0 => (clients, node + 1, 0),
+                    next => (clients, node, next),
+                };
+                Ok::<_, StoreError>(Some((stream::iter(entries), Some(next))))
+            }
+        });
+        Box::pin(batches.try_flatten())
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        if self.namespace.is_some() {
+            // DBSIZE would also count keys outside of the namespace.
+            return Ok(self.keys(None).await?.len());
+        }
+
+        let mut conn = self.connection().await?;
+        redis::cmd("DBSIZE").query(&mut conn).map_err(query_error)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
         log::warn!("Clearing the Redis store is not supported.");
         Ok(())
     }
+
+    /// Removes the keys under `prefix` with a `SCAN` of the whole keyspace, deleting each
+    /// page of matches with one `DEL`.
+    ///
+    /// The scan visits every key of the database, on every primary of a cluster, not only
+    /// the matching ones, so its cost grows with the size of the database rather than
+    /// with the number of keys removed.
+    /// Keys written while the scan runs may survive it.
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let clients = self
+            .client
+            .scan_clients()
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+
+        let pattern = format!("{}*", escape_glob(&self.get_key(prefix)));
+        let mut removed = 0;
+        for client in clients {
+            let mut conn = open(&client).await?;
+            let mut cursor = 0u64;
+            loop {
+                let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(ITER_BATCH_SIZE)
+                    .query(&mut conn)
+                    .map_err(query_error)?;
+
+                // One DEL per key, since a cluster node rejects a multi-key DEL spanning
+                // slots. DEL only counts the keys that still existed.
+                if !keys.is_empty() {
+                    let mut pipe = redis::pipe();
+                    for key in &keys {
+                        pipe.del(key);
+                    }
+                    let deleted: Vec<u64> = pipe.query(&mut conn).map_err(query_error)?;
+                    removed += deleted.iter().sum::<u64>();
+                }
+                if next == 0 {
+                    break;
+                }
+                cursor = next;
+            }
+        }
+        Ok(removed)
+    }
 }
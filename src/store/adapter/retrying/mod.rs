@@ -0,0 +1,2 @@
+mod retrying;
+pub use retrying::*;
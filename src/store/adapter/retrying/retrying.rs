@@ -0,0 +1,311 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// Attempts made per call when `with_max_attempts` is not set.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry when `with_backoff` is not set.
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Longest delay between two attempts when `with_max_backoff` is not set.
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+/// A store that retries calls to an inner store failing with `StoreError::Transient`.
+///
+/// Adapters report deadlocks, lock timeouts, dropped connections and similar failures
+/// that may go away on their own as `StoreError::Transient`; every other error is
+/// returned at once. The delay before a retry doubles after each attempt, up to a cap,
+/// and is randomized between half and all of its value so that clients failing together
+/// do not retry in lockstep. Once the attempts are exhausted, the last error is returned.
+///
+/// A connection that drops after the backend applied a write but before it replied also
+/// counts as transient, so a retried write may run twice. This is harmless for `set` and
+/// `remove`, but a retried `increment` may add `delta` twice. The streams returned by
+/// `iter`, `scan_prefix` and `export` are not retried.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, retrying::RetryingStore}};
+/// # use std::{sync::Arc, time::Duration};
+/// # async {
+/// let store = RetryingStore::new(Arc::new(InMemoryStore::new()))
+///     .with_max_attempts(5)
+///     .with_backoff(Duration::from_millis(20));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct RetryingStore {
+    inner: Arc<dyn Store>,
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryingStore {
+    /// Creates a store that retries transient failures of `inner` up to 3 attempts in all,
+    /// starting with a 50ms delay.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self {
+            inner,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff: DEFAULT_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Sets how many times a call is attempted in all, including the first attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempts` - The number of attempts. `0` and `1` both disable retries.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry. Each further retry doubles it.
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - The delay before the first retry, before jitter is applied.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Caps the delay between two attempts.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_backoff` - The longest delay, before jitter is applied.
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Runs the call built by `operation`, building it again for each retry.
+    async fn run<T, F, Fut>(&self, operation: F) -> Result<T, StoreError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, StoreError>>,
+    {
+        let mut delay = self.backoff;
+        for attempt in 1..self.max_attempts {
+            match operation().await {
+                Err(e) if e.is_transient() => {
+                    let wait = jitter(delay.min(self.max_backoff));
+                    log::warn!(
+                        "Store call failed on attempt {} of {}: {}; retrying in {:?}",
+                        attempt,
+                        self.max_attempts,
+                        e,
+                        wait
+                    );
+                    tokio::time::sleep(wait).await;
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        operation().await
+    }
+}
+
+/// Picks a random delay between half of `delay` and all of it.
+fn jitter(delay: Duration) -> Duration {
+    // `RandomState` is seeded randomly per instance, which is all the randomness needed.
+    let random = RandomState::new().build_hasher().finish();
+    let half = delay / 2;
+    half + half.mul_f64(random as f64 / u64::MAX as f64)
+}
+
+#[async_trait]
+impl Store for RetryingStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.run(|| self.inner.initialize()).await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.run(|| self.inner.health_check()).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.run(|| self.inner.get(key)).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.run(|| self.inner.contains_key(key)).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.run(|| self.inner.get_many(keys)).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.run(|| self.inner.get_with_meta(key)).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.run(|| self.inner.get_ttl(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.run(|| self.inner.set(key, value.clone(), ttl)).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.run(|| self.inner.set_with_ttl(key, value.clone(), ttl))
+            .await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.run(|| self.inner.set_bytes(key, value, ttl)).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.run(|| self.inner.get_bytes(key)).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.run(|| self.inner.set_many(entries)).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.run(|| {
+            self.inner
+                .compare_and_swap(key, expected.clone(), new.clone(), ttl)
+        })
+        .await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.run(|| self.inner.set_nx(key, value.clone(), ttl))
+            .await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.run(|| self.inner.swap(key, value.clone(), ttl)).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.run(|| self.inner.increment(key, delta)).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.run(|| self.inner.expire(key, ttl)).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.run(|| self.inner.extend_ttl(key, by_secs)).await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.run(|| self.inner.get_field(key, field)).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.run(|| self.inner.set_field(key, field, value.clone(), ttl))
+            .await
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.run(|| self.inner.remove_field(key, field)).await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.run(|| self.inner.set_json_path(key, path, value.clone()))
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.run(|| self.inner.remove(key)).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.run(|| self.inner.get_and_remove(key)).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.run(|| self.inner.remove_many(keys)).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.run(|| self.inner.apply_batch(ops)).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.run(|| self.inner.rename(from, to)).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.run(|| self.inner.keys(prefix)).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.run(|| self.inner.range(start, end)).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.run(|| self.inner.len()).await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.run(|| self.inner.is_empty()).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.run(|| self.inner.clear()).await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.run(|| self.inner.clear_prefix(prefix)).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.run(|| self.inner.optimize()).await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
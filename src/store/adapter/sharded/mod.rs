@@ -0,0 +1,2 @@
+mod sharded;
+pub use sharded::*;
@@ -0,0 +1,339 @@
+use async_trait::async_trait;
+use futures::{future::try_join_all, stream, StreamExt};
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// A store that spreads keys across several backends by hashing them.
+///
+/// Every single-key operation goes to `shards[hash(key) % n]`, so a key always lives on
+/// the same shard as long as the shard list and the hasher stay the same. Adding,
+/// removing or reordering shards moves most keys to another shard, where they are no
+/// longer found. `clear`, `len`, `keys` and the streams fan out to every shard and
+/// combine the results.
+///
+/// Multi-key operations group their keys by shard before dispatching, and each shard
+/// receives one call for its group. `apply_batch`, `set_many` and `set_group` are
+/// therefore only as atomic as a single shard: a failure on one shard leaves the groups
+/// already applied to other shards in place. `rename` between keys on different shards is a read, a write
+/// and a removal, and is not atomic.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, sharded::ShardedStore}};
+/// # async {
+/// let store = ShardedStore::new(vec![
+///     Box::new(InMemoryStore::new()),
+///     Box::new(InMemoryStore::new()),
+/// ]);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("user:1", "alice").await.unwrap();
+/// # };
+/// ```
+pub struct ShardedStore {
+    shards: Vec<Box<dyn Store>>,
+    hasher: fn(&str) -> u64,
+}
+
+impl ShardedStore {
+    /// Creates a store that shards keys across `shards` with a 64-bit FNV-1a hash.
+    ///
+    /// FNV-1a does not depend on the process or the Rust version, so every client
+    /// configured with the same shards agrees on where a key lives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is empty.
+    pub fn new(shards: Vec<Box<dyn Store>>) -> Self {
+        assert!(!shards.is_empty(), "ShardedStore needs at least one shard");
+        Self {
+            shards,
+            hasher: fnv1a,
+        }
+    }
+
+    /// Sets the function used to hash keys.
+    ///
+    /// The function must return the same hash for a key in every process sharing the
+    /// shards, which rules out randomly seeded hashers such as `RandomState`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher` - Maps a key to the hash its shard is picked from.
+    pub fn with_hasher(mut self, hasher: fn(&str) -> u64) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    /// Returns the shards, in the order keys are assigned to them.
+    pub fn shards(&self) -> &[Box<dyn Store>] {
+        &self.shards
+    }
+
+    /// Returns the index of the shard holding `key`.
+    pub fn shard_index(&self, key: &str) -> usize {
+        ((self.hasher)(key) % self.shards.len() as u64) as usize
+    }
+
+    fn shard(&self, key: &str) -> &dyn Store {
+        self.shards[self.shard_index(key)].as_ref()
+    }
+
+    /// Splits `items` into one group per shard, keeping their relative order.
+    fn group_by_shard<T>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        key: impl Fn(&T) -> &str,
+    ) -> Vec<Vec<T>> {
+        let mut groups: Vec<Vec<T>> = self.shards.iter().map(|_| Vec::new()).collect();
+        for item in items {
+            groups[self.shard_index(key(&item))].push(item);
+        }
+        groups
+    }
+}
+
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[async_trait]
+impl Store for ShardedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        try_join_all(self.shards.iter().map(|shard| shard.initialize())).await?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        try_join_all(self.shards.iter().map(|shard| shard.health_check())).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.shard(key).get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.shard(key).contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let groups = self.group_by_shard(keys.iter().enumerate(), |(_, key)| key);
+        let fetched = try_join_all(groups.iter().zip(&self.shards).map(|(group, shard)| {
+            let keys: Vec<&str> = group.iter().map(|(_, key)| **key).collect();
+            async move { shard.get_many(&keys).await }
+        }))
+        .await?;
+
+        let mut values = vec![None; keys.len()];
+        for (group, group_values) in groups.iter().zip(fetched) {
+            for ((index, _), value) in group.iter().zip(group_values) {
+                values[*index] = value;
+            }
+        }
+        Ok(values)
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.shard(key).get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.shard(key).get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.shard(key).set(key, value, ttl).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.shard(key).set_with_ttl(key, value, ttl).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.shard(key).set_bytes(key, value, ttl).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.shard(key).get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        let groups = self.group_by_shard(entries.iter().cloned(), |(key, _, _)| key);
+        try_join_all(
+            groups
+                .iter()
+                .zip(&self.shards)
+                .filter(|(group, _)| !group.is_empty())
+                .map(|(group, shard)| shard.set_many(group)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.shard(key)
+            .compare_and_swap(key, expected, new, ttl)
+            .await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.shard(key).set_nx(key, value, ttl).await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.shard(key).swap(key, value, ttl).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.shard(key).increment(key, delta).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.shard(key).expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.shard(key).extend_ttl(key, by_secs).await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.shard(key).get_field(key, field).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.shard(key).set_field(key, field, value, ttl).await
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.shard(key).remove_field(key, field).await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.shard(key).set_json_path(key, path, value).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.shard(key).remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.shard(key).get_and_remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let groups = self.group_by_shard(keys.iter().copied(), |key| key);
+        let removed = try_join_all(
+            groups
+                .iter()
+                .zip(&self.shards)
+                .filter(|(group, _)| !group.is_empty())
+                .map(|(group, shard)| shard.remove_many(group)),
+        )
+        .await?;
+        Ok(removed.into_iter().sum())
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let groups = self.group_by_shard(ops.iter().cloned(), |op| op.key());
+        try_join_all(
+            groups
+                .iter()
+                .zip(&self.shards)
+                .filter(|(group, _)| !group.is_empty())
+                .map(|(group, shard)| shard.apply_batch(group)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let source = self.shard_index(from);
+        if source == self.shard_index(to) {
+            return self.shards[source].rename(from, to).await;
+        }
+
+        let Some(value) = self.shard(from).get(from).await? else {
+            return Ok(false);
+        };
+        let ttl = self.shard(from).get_ttl(from).await?;
+        self.shard(to).set(to, value, ttl).await?;
+        self.shard(from).remove(from).await?;
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let keys = try_join_all(self.shards.iter().map(|shard| shard.keys(prefix))).await?;
+        Ok(keys.into_iter().flatten().collect())
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        let ranges = try_join_all(self.shards.iter().map(|shard| shard.range(start, end))).await?;
+        let mut entries: Vec<(String, Value)> = ranges.into_iter().flatten().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(stream::iter(self.shards.iter().map(|shard| shard.iter())).flatten())
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        let prefix = prefix.to_string();
+        Box::pin(
+            stream::iter(self.shards.iter())
+                .map(move |shard| shard.scan_prefix(&prefix))
+                .flatten(),
+        )
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        Box::pin(stream::iter(self.shards.iter().map(|shard| shard.export())).flatten())
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let lens = try_join_all(self.shards.iter().map(|shard| shard.len())).await?;
+        Ok(lens.into_iter().sum())
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        try_join_all(self.shards.iter().map(|shard| shard.clear())).await?;
+        Ok(())
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let removed =
+            try_join_all(self.shards.iter().map(|shard| shard.clear_prefix(prefix))).await?;
+        Ok(removed.into_iter().sum())
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        try_join_all(self.shards.iter().map(|shard| shard.optimize())).await?;
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        try_join_all(self.shards.iter().map(|shard| shard.close())).await?;
+        Ok(())
+    }
+}
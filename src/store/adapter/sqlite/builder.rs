@@ -1,8 +1,14 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc, time::Duration};
 
-pub use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+pub use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    SqlitePool,
+};
 
-use crate::{StoreError, DEFAUTL_NAMESPACE_NAME};
+use crate::{
+    utils::{connection_error, Columns},
+    StoreError, DEFAULT_NAMESPACE_NAME,
+};
 
 use super::SqliteStore;
 
@@ -51,6 +57,11 @@ pub struct SqliteStoreBuilder {
     uri: Option<String>,
     pool: Option<Arc<SqlitePool>>,
     table_name: Option<String>,
+    columns: Columns,
+    journal_mode: Option<SqliteJournalMode>,
+    busy_timeout: Option<Duration>,
+    timestamps: bool,
+    auto_create: bool,
 }
 
 impl SqliteStoreBuilder {
@@ -59,13 +70,18 @@ impl SqliteStoreBuilder {
             uri: None,
             pool: None,
             table_name: None,
+            columns: Columns::default(),
+            journal_mode: None,
+            busy_timeout: None,
+            timestamps: false,
+            auto_create: true,
         }
     }
 
     /// Sets the table name for the `SqliteStore`.
     ///
     /// This method configures the table name to be used by the store. If not set,
-    /// `DEFAULT_TABLE_NAME` from the configuration will be used.
+    /// `DEFAULT_NAMESPACE_NAME` will be used.
     pub fn table_name<S: Into<String>>(mut self, table: S) -> Self {
         self.table_name = Some(table.into());
         self
@@ -79,6 +95,110 @@ impl SqliteStoreBuilder {
         self
     }
 
+    /// Opens a private in-memory database, mainly useful for tests.
+    ///
+    /// Equivalent to `uri("sqlite::memory:")`. The data is lost once the pool is closed.
+    pub fn in_memory(self) -> Self {
+        self.uri("sqlite::memory:")
+    }
+
+    /// Sets the journal mode applied to every connection opened from `uri`.
+    ///
+    /// If not set, `SqliteJournalMode::Wal` is used, which lets readers proceed while a
+    /// write is in progress. In-memory databases always report `memory`. Ignored if an
+    /// existing `pool` is supplied.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The `PRAGMA journal_mode` to apply.
+    pub fn journal_mode(mut self, mode: SqliteJournalMode) -> Self {
+        self.journal_mode = Some(mode);
+        self
+    }
+
+    /// Sets how long a connection waits for a lock before failing with "database is locked".
+    ///
+    /// If not set, the sqlx default of five seconds is used. Ignored if an existing `pool`
+    /// is supplied.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The `PRAGMA busy_timeout` to apply.
+    pub fn busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the name of the column holding the keys.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn key_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.key = name.into();
+        self
+    }
+
+    /// Sets the name of the column holding the JSON-encoded values.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn value_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.value = name.into();
+        self
+    }
+
+    /// Sets the name of the column holding the expiry timestamps.
+    ///
+    /// The name is quoted in every generated statement, so any identifier is accepted,
+    /// including reserved words. Defaults to `ttl`. `initialize` adds the column to a table
+    /// created without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The column name.
+    pub fn ttl_column<S: Into<String>>(mut self, name: S) -> Self {
+        self.columns.ttl = name.into();
+        self
+    }
+
+    /// Records when each entry was created and last updated.
+    ///
+    /// Adds `created_at` and `updated_at` columns, holding unix timestamps in seconds,
+    /// and triggers that keep them current. `initialize` adds them to an existing table
+    /// as well. Overwriting a key keeps its `created_at`. The timestamps are read with
+    /// `Store::get_with_meta`. Disabled by default, so existing tables are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to maintain the timestamp columns.
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.timestamps = enabled;
+        self
+    }
+
+    /// Sets whether `initialize` creates and migrates the table.
+    ///
+    /// Enabled by default. Disable it when the database user has no DDL rights and the
+    /// table is provisioned ahead of time: `initialize` then only checks that the table
+    /// exists with the configured columns, and returns a `StoreError::QueryError` naming
+    /// what is missing otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to run `CREATE TABLE` and the schema migrations.
+    pub fn auto_create(mut self, enabled: bool) -> Self {
+        self.auto_create = enabled;
+        self
+    }
+
     /// Uses an existing connection pool for the `SqliteStore`.
     ///
     /// This method allows for using an already configured `SqlitePool`. If set,
@@ -103,17 +223,32 @@ impl SqliteStoreBuilder {
                 let uri = self
                     .uri
                     .expect("SqliteStore requires either a URI or an existing pool to be set");
-                Arc::new(SqlitePoolOptions::new().connect(&uri).await.map_err(|_| {
-                    StoreError::ConnectionError("Failed to connect to the database".to_string())
-                })?)
+                let mut options = SqliteConnectOptions::from_str(&uri)
+                    .map_err(|e| connection_error(e, "Invalid database URI"))?
+                    .journal_mode(self.journal_mode.unwrap_or(SqliteJournalMode::Wal));
+                if let Some(timeout) = self.busy_timeout {
+                    options = options.busy_timeout(timeout);
+                }
+                Arc::new(
+                    SqlitePoolOptions::new()
+                        .connect_with(options)
+                        .await
+                        .map_err(|e| connection_error(e, "Failed to connect to the database"))?,
+                )
             }
         };
 
         let table_name = self.table_name.unwrap_or_else(|| {
-            log::warn!("Table name not set, using default table name");
-            DEFAUTL_NAMESPACE_NAME.to_string()
+            log::debug!("Table name not set, using default table name");
+            DEFAULT_NAMESPACE_NAME.to_string()
         });
 
-        Ok(SqliteStore { pool, table_name })
+        Ok(SqliteStore {
+            pool,
+            table_name,
+            columns: self.columns,
+            timestamps: self.timestamps,
+            auto_create: self.auto_create,
+        })
     }
 }
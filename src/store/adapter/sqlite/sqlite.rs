@@ -1,32 +1,240 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
 use serde_json::Value;
 use sqlx::SqlitePool;
 
-use crate::{Store, StoreError};
+use crate::{
+    json_path::{JsonPath, PathSegment},
+    utils::{
+        check_table, clamp_i64, expand_sql, expires_at, glob_prefix_pattern, is_transient_sqlx,
+        quote_identifier, remaining_ttl, Columns, ITER_BATCH_SIZE,
+    },
+    BatchOp, EntryMeta, EntryStream, ExportEntry, ExportStream, Store, StoreError,
+};
+
+/// The current unix time in whole seconds, as a SQL expression.
+const EPOCH_NOW: &str = "CAST(strftime('%s', 'now') AS INTEGER)";
+
+/// The condition matching rows whose TTL has not passed, substituted for `{live}`.
+const LIVE: &str = "({ttl} IS NULL OR {ttl} > CAST(strftime('%s', 'now') AS INTEGER))";
+
+/// Writes a row, replacing the value and TTL of an existing key.
+const UPSERT: &str = "INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {ttl} = EXCLUDED.{ttl}";
 
 pub struct SqliteStore {
     pub(crate) pool: Arc<SqlitePool>,
     pub(crate) table_name: String,
+    pub(crate) columns: Columns,
+    pub(crate) timestamps: bool,
+    pub(crate) auto_create: bool,
 }
 
 impl SqliteStore {
     fn get_table_name(&self) -> String {
         self.table_name.clone()
     }
+
+    /// Expands a SQL template with the table name, the double-quoted column names and,
+    /// for `{live}`, the condition matching rows that have not expired.
+    fn sql(&self, template: &str) -> String {
+        expand_sql(
+            &template.replace("{live}", LIVE),
+            &self.get_table_name(),
+            &self.columns,
+            '"',
+        )
+    }
+
+    /// Checks that the pre-provisioned table exists with the configured columns.
+    async fn verify_table(&self) -> Result<(), StoreError> {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(self.get_table_name())
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to read the table layout"))?;
+
+        check_table(
+            &self.get_table_name(),
+            &columns,
+            &self.columns,
+            self.timestamps,
+        )
+    }
+
+    /// Adds the ttl column to a table created before entries could expire, and the index
+    /// used to find expired rows.
+    async fn add_ttl(&self) -> Result<(), StoreError> {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(self.get_table_name())
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to read the table layout"))?;
+        if !columns.contains(&self.columns.ttl) {
+            sqlx::query(&self.sql("ALTER TABLE {table} ADD COLUMN {ttl} INTEGER"))
+                .execute(&*self.pool)
+                .await
+                .map_err(|e| query_error(e, "Failed to add the ttl column"))?;
+            log::info!("Added the ttl column to table {}", self.get_table_name());
+        }
+
+        let index = quote_identifier(
+            &format!("{}_{}_idx", self.table_name, self.columns.ttl),
+            '"',
+        );
+        sqlx::query(&self.sql(&format!(
+            "CREATE INDEX IF NOT EXISTS {} ON {{table}} ({{ttl}})",
+            index
+        )))
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| query_error(e, "Failed to create the ttl index"))?;
+
+        Ok(())
+    }
+
+    /// Deletes every row whose TTL has passed.
+    ///
+    /// Expired rows are already hidden from reads, so this only reclaims the space they
+    /// use. `optimize` calls it before compacting the database.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows deleted.
+    pub async fn cleanup_expired(&self) -> Result<u64, StoreError> {
+        let query = self.sql(&format!(
+            "DELETE FROM {{table}} WHERE {{ttl}} IS NOT NULL AND {{ttl}} <= {}",
+            EPOCH_NOW
+        ));
+        let result = sqlx::query(&query)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove expired rows"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Streams the live entries under `prefix` with their expiry, in key order.
+    fn scan(&self, prefix: &str) -> ExportStream<'_> {
+        // Keyset pagination: each batch resumes after the last key of the previous one.
+        let query = self.sql("SELECT {key}, {value}, {ttl} FROM {table} WHERE (?1 IS NULL OR {key} > ?1) AND {key} GLOB ?2 AND {live} ORDER BY {key} LIMIT ?3");
+        let pattern = glob_prefix_pattern(prefix);
+        let batches = stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| {
+            let query = query.clone();
+            let pattern = pattern.clone();
+            async move {
+                let Some(after) = cursor else {
+                    return Ok(None);
+                };
+                let rows = sqlx::query_as::<_, (String, Vec<u8>, Option<i64>)>(&query)
+                    .bind(after)
+                    .bind(pattern)
+                    .bind(ITER_BATCH_SIZE as i64)
+                    .fetch_all(&*self.pool)
+                    .await
+                    .map_err(|e| query_error(e, "Failed to fetch the entries"))?;
+
+                let next = match rows.last() {
+                    Some((key, _, _)) if rows.len() == ITER_BATCH_SIZE => Some(Some(key.clone())),
+                    _ => None,
+                };
+                let entries: Vec<Result<ExportEntry, StoreError>> = rows
+                    .into_iter()
+                    .map(|(key, value, ttl)| {
+                        let value = decode_value(&key, &value)?;
+                        Ok((key, value, ttl.map(|ttl| ttl as u64)))
+                    })
+                    .collect();
+                Ok::<_, StoreError>(Some((stream::iter(entries), next)))
+            }
+        });
+        Box::pin(batches.try_flatten())
+    }
+
+    /// Adds the `created_at` and `updated_at` columns, if missing, and the triggers that
+    /// fill them in.
+    ///
+    /// SQLite cannot add a column whose default is the current time, so an insert
+    /// trigger sets both timestamps and an update trigger refreshes `updated_at` whenever
+    /// the value is rewritten. Upserts that hit an existing key only fire the latter.
+    async fn add_timestamps(&self) -> Result<(), StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(self.get_table_name())
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to read the table layout"))?;
+        for column in ["created_at", "updated_at"] {
+            if columns.iter().any(|c| c == column) {
+                continue;
+            }
+            let sql = self.sql(&format!(
+                "ALTER TABLE {{table}} ADD COLUMN {} INTEGER",
+                column
+            ));
+            sqlx::query(&sql).execute(&mut *tx).await.map_err(|e| {
+                StoreError::QueryError(format!("Failed to add the {} column: {}", column, e))
+            })?;
+            let sql = self.sql(&format!("UPDATE {{table}} SET {} = {}", column, EPOCH_NOW));
+            sqlx::query(&sql).execute(&mut *tx).await.map_err(|e| {
+                StoreError::QueryError(format!("Failed to fill the {} column: {}", column, e))
+            })?;
+            log::info!(
+                "Added the {} column to table {}",
+                column,
+                self.get_table_name()
+            );
+        }
+
+        let on_insert = quote_identifier(&format!("{}_keyv_created_at", self.table_name), '"');
+        let on_update = quote_identifier(&format!("{}_keyv_updated_at", self.table_name), '"');
+        let triggers = [
+            format!("CREATE TRIGGER IF NOT EXISTS {} AFTER INSERT ON {{table}} BEGIN UPDATE {{table}} SET created_at = {EPOCH_NOW}, updated_at = {EPOCH_NOW} WHERE rowid = NEW.rowid; END", on_insert),
+            format!("CREATE TRIGGER IF NOT EXISTS {} AFTER UPDATE OF {{value}} ON {{table}} BEGIN UPDATE {{table}} SET updated_at = {EPOCH_NOW} WHERE rowid = NEW.rowid; END", on_update),
+        ];
+        for trigger in triggers {
+            sqlx::query(&self.sql(&trigger))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| query_error(e, "Failed to create the trigger"))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Store for SqliteStore {
     async fn initialize(&self) -> Result<(), StoreError> {
-        let sql = format!(
-            "CREATE TABLE IF NOT EXISTS {} (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
+        if !self.auto_create {
+            return self.verify_table().await;
+        }
+
+        let timestamp_columns = if self.timestamps {
+            ",
+                created_at INTEGER,
+                updated_at INTEGER"
+        } else {
+            ""
+        };
+        let sql = self.sql(&format!(
+            "CREATE TABLE IF NOT EXISTS {{table}} (
+                {{key}} TEXT PRIMARY KEY,
+                {{value}} TEXT NOT NULL,
+                {{ttl}} INTEGER{}
             )",
-            self.get_table_name()
-        );
+            timestamp_columns
+        ));
 
         sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
             StoreError::QueryError(format!(
@@ -35,78 +243,681 @@ impl Store for SqliteStore {
             ))
         })?;
 
+        self.add_ttl().await?;
+        if self.timestamps {
+            self.add_timestamps().await?;
+        }
+
         Ok(())
     }
 
+    async fn health_check(&self) -> Result<(), StoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::ConnectionError(e.to_string()))
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let query = format!("SELECT value FROM {} WHERE key = ?", self.get_table_name());
-        let result = sqlx::query_as::<_, (String,)>(query.as_str())
+        let query = self.sql("SELECT {value} FROM {table} WHERE {key} = ? AND {live}");
+        let result = sqlx::query_as::<_, (Vec<u8>,)>(query.as_str())
+            .bind(key)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        result.map(|(value,)| decode_value(key, &value)).transpose()
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        let query = self.sql("SELECT 1 FROM {table} WHERE {key} = ? AND {live} LIMIT 1");
+        let result = sqlx::query(&query)
             .bind(key)
             .fetch_optional(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to check the key"))?;
+
+        Ok(result.is_some())
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = self.sql(&format!(
+            "SELECT {{key}}, {{value}} FROM {{table}} WHERE {{key}} IN ({}) AND {{live}}",
+            keys.iter().map(|_| "?").collect::<Vec<&str>>().join(",")
+        ));
+
+        let mut query = sqlx::query_as::<_, (String, Vec<u8>)>(&query);
+        for key in keys {
+            query = query.bind(key);
+        }
+
+        let rows = query
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the values"))?;
+
+        let found = rows
+            .into_iter()
+            .map(|(key, value)| {
+                let value = decode_value(&key, &value)?;
+                Ok((key, value))
+            })
+            .collect::<Result<HashMap<String, Value>, StoreError>>()?;
+
+        Ok(keys.iter().map(|key| found.get(*key).cloned()).collect())
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        if !self.timestamps {
+            return Ok(self
+                .get(key)
+                .await?
+                .map(|value| (value, EntryMeta::default())));
+        }
+
+        let query = self
+            .sql("SELECT {value}, created_at, updated_at FROM {table} WHERE {key} = ? AND {live}");
+        let result = sqlx::query_as::<_, (Vec<u8>, Option<i64>, Option<i64>)>(&query)
+            .bind(key)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        result
+            .map(|(value, created_at, updated_at)| {
+                let value = decode_value(key, &value)?;
+                let meta = EntryMeta {
+                    created_at: created_at.map(|t| t as u64),
+                    updated_at: updated_at.map(|t| t as u64),
+                };
+                Ok((value, meta))
+            })
+            .transpose()
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        let query = self.sql("SELECT {ttl} FROM {table} WHERE {key} = ? AND {live}");
+        let result = sqlx::query_as::<_, (Option<i64>,)>(&query)
+            .bind(key)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the TTL"))?;
 
         Ok(result
-            .map(|(value,)| serde_json::from_str(&value).ok())
-            .flatten())
+            .and_then(|(ttl,)| ttl)
+            .and_then(|ttl| remaining_ttl(ttl as u64)))
     }
 
-    async fn set(&self, key: &str, value: Value, _ttl: Option<u64>) -> Result<(), StoreError> {
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
 
-        let sql = format!(
-            "INSERT INTO {} (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value",
-            self.get_table_name()
-        );
-        sqlx::query(&sql)
+        sqlx::query(&self.sql(UPSERT))
             .bind(key)
             .bind(value_str)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the value"))?;
+
+        Ok(())
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        // SQLite columns are dynamically typed, so the TEXT value column can hold a BLOB.
+        sqlx::query(&self.sql(UPSERT))
+            .bind(key)
+            .bind(value)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to set the value"))?;
+
+        Ok(())
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        let query = self.sql("SELECT {value} FROM {table} WHERE {key} = ? AND {live}");
+        let result = sqlx::query_as::<_, (Vec<u8>,)>(query.as_str())
+            .bind(key)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the value"))?;
+
+        Ok(result.map(|(value,)| value))
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let sql = self.sql(&format!(
+            "INSERT INTO {{table}} ({{key}}, {{value}}, {{ttl}}) VALUES {} ON CONFLICT({{key}}) DO UPDATE SET {{value}} = EXCLUDED.{{value}}, {{ttl}} = EXCLUDED.{{ttl}}",
+            entries.iter().map(|_| "(?, ?, ?)").collect::<Vec<&str>>().join(",")
+        ));
+
+        let mut query = sqlx::query(&sql);
+        for (key, value, ttl) in entries {
+            let value_str =
+                serde_json::to_string(value).map_err(|e| StoreError::SerializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })?;
+            query = query
+                .bind(key)
+                .bind(value_str)
+                .bind(expires_at(*ttl).map(clamp_i64));
+        }
+
+        query
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to set the values"))?;
 
         Ok(())
     }
 
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let new_str = serde_json::to_string(&new).map_err(|e| StoreError::SerializationError {
+            key: Some(key.to_string()),
+            source: e,
+        })?;
+        let expires_at = expires_at(ttl).map(clamp_i64);
+
+        let result = match expected {
+            Some(expected) => {
+                let expected_str = serde_json::to_string(&expected)
+                    .map_err(|e| StoreError::SerializationError { key: Some(key.to_string()), source: e })?;
+                let sql = self.sql(
+                    "UPDATE {table} SET {value} = ?, {ttl} = ? WHERE {key} = ? AND {value} = ? AND {live}",
+                );
+                sqlx::query(&sql)
+                    .bind(new_str)
+                    .bind(expires_at)
+                    .bind(key)
+                    .bind(expected_str)
+                    .execute(&*self.pool)
+                    .await
+            }
+            None => {
+                // An expired row counts as absent, so it is overwritten like a missing one.
+                let sql = self.sql("INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?, ?, ?) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {ttl} = EXCLUDED.{ttl} WHERE NOT {live}");
+                sqlx::query(&sql)
+                    .bind(key)
+                    .bind(new_str)
+                    .bind(expires_at)
+                    .execute(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let select = self.sql("SELECT {value} FROM {table} WHERE {key} = ? AND {live}");
+        let previous = sqlx::query_as::<_, (Vec<u8>,)>(&select)
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+        sqlx::query(&self.sql(UPSERT))
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to swap the value"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        previous
+            .map(|(previous,)| decode_value(key, &previous))
+            .transpose()
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        // A single statement, so racing increments never have to upgrade a read lock. An
+        // expired row is restarted from `delta` rather than incremented. The `WHERE`
+        // leaves a value that is not an integer, or that would overflow, untouched, in
+        // which case no row is returned.
+        let query = self.sql(
+            "INSERT INTO {table} ({key}, {value}, {ttl}) VALUES (?1, ?2, NULL)
+            ON CONFLICT({key}) DO UPDATE SET
+                {value} = CASE WHEN {live} THEN CAST({value} AS INTEGER) + ?2 ELSE ?2 END,
+                {ttl} = CASE WHEN {live} THEN {ttl} ELSE NULL END
+            WHERE NOT {live} OR (
+                CAST(CAST({value} AS INTEGER) AS TEXT) = {value}
+                AND CASE WHEN ?2 >= 0
+                    THEN CAST({value} AS INTEGER) <= 9223372036854775807 - ?2
+                    ELSE CAST({value} AS INTEGER) >= -9223372036854775807 - 1 - ?2
+                END
+            )
+            RETURNING CAST({value} AS INTEGER)",
+        );
+        // Fetches every row: SQLite only commits once the statement has run to completion,
+        // which `fetch_optional` does not wait for after the first `RETURNING` row.
+        let new: Vec<i64> = sqlx::query_scalar(&query)
+            .bind(key)
+            .bind(delta)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to increment the value"))?;
+        if let Some(new) = new.first() {
+            return Ok(*new);
+        }
+
+        let current = self.get(key).await?;
+        if current.as_ref().and_then(Value::as_i64).is_none() {
+            return Err(StoreError::NotAnInteger(key.to_string()));
+        }
+        Err(StoreError::QueryError(
+            "Increment would overflow".to_string(),
+        ))
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let query = self.sql("UPDATE {table} SET {ttl} = ? WHERE {key} = ? AND {live}");
+        let result = sqlx::query(&query)
+            .bind(expires_at(ttl).map(clamp_i64))
+            .bind(key)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to update the TTL"))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        // `MIN` keeps the sum within INTEGER; a NULL ttl stays NULL.
+        let query = self.sql(
+            "UPDATE {table} SET {ttl} = MIN({ttl}, 9223372036854775807 - ?1) + ?1 WHERE {key} = ?2 AND {live}",
+        );
+        let result = sqlx::query(&query)
+            .bind(clamp_i64(by_secs))
+            .bind(key)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to extend the TTL"))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        let path = JsonPath::parse(path)?;
+        let value_str =
+            serde_json::to_string(&value).map_err(|e| StoreError::SerializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // `json_set` appends when an index is one past the end of an array, and is a no-op
+        // further out, so an index must point at an existing element.
+        let update = "UPDATE {table} SET {value} = json_set({value}, ?1, json(?2)) WHERE {key} = ?3 AND {live}";
+        let updated = match path.last() {
+            PathSegment::Field(_) => {
+                let query = self.sql(&format!(
+                    "{} AND json_type({{value}}, ?4) = 'object' RETURNING {{value}}",
+                    update
+                ));
+                sqlx::query_as::<_, (String,)>(&query)
+                    .bind(path.to_sqlite())
+                    .bind(value_str)
+                    .bind(key)
+                    .bind(path.parent().to_sqlite())
+                    .fetch_all(&mut *tx)
+                    .await
+            }
+            PathSegment::Index(_) => {
+                let query = self.sql(&format!(
+                    "{} AND json_type({{value}}, ?1) IS NOT NULL RETURNING {{value}}",
+                    update
+                ));
+                sqlx::query_as::<_, (String,)>(&query)
+                    .bind(path.to_sqlite())
+                    .bind(value_str)
+                    .bind(key)
+                    .fetch_all(&mut *tx)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to update the JSON path"))?;
+        let Some((updated,)) = updated.into_iter().next() else {
+            return Ok(false);
+        };
+
+        // `json_set` appends new members after the existing ones, while `compare_and_swap`
+        // compares against the text `serde_json` writes, so the result is written back in
+        // that form within the same transaction.
+        let canonical = serde_json::from_str::<Value>(&updated)
+            .and_then(|value| serde_json::to_string(&value))
+            .map_err(|e| StoreError::DeserializationError {
+                key: Some(key.to_string()),
+                source: e,
+            })?;
+        let query = self.sql("UPDATE {table} SET {value} = ? WHERE {key} = ?");
+        sqlx::query(&query)
+            .bind(canonical)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to update the JSON path"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(true)
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = ?", self.get_table_name());
+        let query = self.sql("DELETE FROM {table} WHERE {key} = ?");
         sqlx::query(&query)
             .bind(key)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
 
         Ok(())
     }
 
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let query = format!(
-            "DELETE FROM {} WHERE key IN ({})",
-            self.get_table_name(),
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // An expired row is left for `cleanup_expired`, as if it were already gone.
+        let query = self.sql("DELETE FROM {table} WHERE {key} = ? AND {live} RETURNING {value}");
+        // Fetches every row, so the delete is done before the transaction commits; see
+        // `increment`.
+        let result = sqlx::query_as::<_, (Vec<u8>,)>(&query)
+            .bind(key)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the key"))?;
+        // A value that cannot be decoded is kept: returning early drops the transaction,
+        // which rolls the delete back.
+        let value = result
+            .into_iter()
+            .next()
+            .map(|(value,)| decode_value(key, &value))
+            .transpose()?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+        Ok(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
+        let query = self.sql(&format!(
+            "DELETE FROM {{table}} WHERE {{key}} IN ({}) AND {{live}}",
             keys.iter().map(|_| "?").collect::<Vec<&str>>().join(",")
-        );
+        ));
 
         let mut query = sqlx::query(&query);
         for key in keys {
             query = query.bind(key);
         }
 
-        query.execute(&*self.pool).await.map_err(|e| {
-            StoreError::QueryError(format!("Failed to remove the keys: {}", e.to_string()))
-        })?;
+        let result = query
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        let set = self.sql(UPSERT);
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = ?");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        // Returning early drops `tx`, which rolls back everything applied so far.
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = serde_json::to_string(value).map_err(|e| {
+                        StoreError::SerializationError {
+                            key: Some(key.to_string()),
+                            source: e,
+                        }
+                    })?;
+                    sqlx::query(&set)
+                        .bind(key)
+                        .bind(value_str)
+                        .bind(expires_at(*ttl).map(clamp_i64))
+                        .execute(&mut *tx)
+                        .await
+                }
+                BatchOp::Remove { key } => sqlx::query(&remove).bind(key).execute(&mut *tx).await,
+            }
+            .map_err(|e| query_error(e, "Failed to apply the batch"))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
 
         Ok(())
     }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let exists = self.sql("SELECT 1 FROM {table} WHERE {key} = ? AND {live}");
+        let remove = self.sql("DELETE FROM {table} WHERE {key} = ?");
+        let rename = self.sql("UPDATE {table} SET {key} = ? WHERE {key} = ?");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| query_error(e, "Failed to start a transaction"))?;
+
+        let found = sqlx::query(&exists)
+            .bind(from)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?
+            .is_some();
+        if !found || from == to {
+            return Ok(found);
+        }
+
+        // Clear the destination first, so the update cannot hit its unique key.
+        sqlx::query(&remove)
+            .bind(to)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+        sqlx::query(&rename)
+            .bind(to)
+            .bind(from)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(e, "Failed to rename the key"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| query_error(e, "Failed to commit the transaction"))?;
+
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        let rows = match prefix {
+            Some(prefix) => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {key} GLOB ? AND {live}");
+                sqlx::query_as::<_, (String,)>(&query)
+                    .bind(glob_prefix_pattern(prefix))
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+            None => {
+                let query = self.sql("SELECT {key} FROM {table} WHERE {live}");
+                sqlx::query_as::<_, (String,)>(&query)
+                    .fetch_all(&*self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| query_error(e, "Failed to list the keys"))?;
+
+        Ok(rows.into_iter().map(|(key,)| key).collect())
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.scan_prefix("")
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        let query =
+            self.sql("SELECT {key}, {value} FROM {table} WHERE {key} >= ? AND {key} < ? AND {live} ORDER BY {key}");
+        let rows = sqlx::query_as::<_, (String, Vec<u8>)>(&query)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch the range"))?;
+
+        rows.into_iter()
+            .map(|(key, value)| {
+                let value = decode_value(&key, &value)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        Box::pin(self.scan(prefix).map_ok(|(key, value, _)| (key, value)))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.scan("")
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        let query = self.sql("SELECT COUNT(*) FROM {table} WHERE {live}");
+        let (count,) = sqlx::query_as::<_, (i64,)>(&query)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to count the entries"))?;
+
+        Ok(count as usize)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {}", self.get_table_name());
+        let query = self.sql("DELETE FROM {table}");
 
         sqlx::query(&query)
             .execute(&*self.pool)
             .await
-            .map_err(|_| StoreError::QueryError("Failed to clear the table".to_string()))?;
+            .map_err(|e| query_error(e, "Failed to clear the table"))?;
+
+        Ok(())
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        // Expired rows are left for `cleanup_expired`, so only live keys are counted.
+        let query = self.sql("DELETE FROM {table} WHERE {key} GLOB ? AND {live}");
+        let result = sqlx::query(&query)
+            .bind(glob_prefix_pattern(prefix))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| query_error(e, "Failed to remove the keys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.cleanup_expired().await?;
+        sqlx::query("VACUUM")
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| query_error(e, "Failed to vacuum the database"))
+    }
 
+    async fn close(&self) -> Result<(), StoreError> {
+        self.pool.close().await;
         Ok(())
     }
 }
+
+/// Maps a failed query to `StoreError::Transient` when retrying it may succeed and to
+/// `StoreError::QueryError` otherwise.
+///
+/// `SQLITE_BUSY` and `SQLITE_LOCKED`, including their extended codes, are transient: another
+/// connection holds the lock and may release it by the next attempt.
+/// Parses the value column of `key`.
+///
+/// The column is read as bytes rather than text because `set_bytes` stores a BLOB in it,
+/// which sqlx refuses to decode as a string. Such a value then fails here on its own row,
+/// as a `StoreError::DeserializationError` naming the key, instead of failing the query.
+fn decode_value(key: &str, value: &[u8]) -> Result<Value, StoreError> {
+    serde_json::from_slice(value).map_err(|e| StoreError::DeserializationError {
+        key: Some(key.to_string()),
+        source: e,
+    })
+}
+
+fn query_error(e: sqlx::Error, message: &str) -> StoreError {
+    let transient = is_transient_sqlx(&e, |e| {
+        e.code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+    });
+    if transient {
+        StoreError::Transient(format!("{}: {}", message, e))
+    } else {
+        StoreError::QueryError(format!("{}: {}", message, e))
+    }
+}
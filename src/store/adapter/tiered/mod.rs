@@ -0,0 +1,2 @@
+mod tiered;
+pub use tiered::*;
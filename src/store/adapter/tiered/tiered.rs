@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError};
+
+/// A store that reads through a fast `near` layer to a slower `far` layer.
+///
+/// Reads check `near` first and fall back to `far`, copying hits into `near`. Writes go
+/// to `far` first and then to `near` (write-through), and removals are applied to both
+/// layers. `far` is the source of truth: listing and counting keys only consult `far`.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, tiered::TieredStore}};
+/// # use std::time::Duration;
+/// # async {
+/// let store = TieredStore::new(Box::new(InMemoryStore::new()), Box::new(InMemoryStore::new()))
+///     .with_near_ttl(Duration::from_secs(60));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct TieredStore {
+    near: Box<dyn Store>,
+    far: Box<dyn Store>,
+    near_ttl: Option<u64>,
+}
+
+impl TieredStore {
+    /// Creates a tiered store with `near` in front of `far`.
+    pub fn new(near: Box<dyn Store>, far: Box<dyn Store>) -> Self {
+        Self {
+            near,
+            far,
+            near_ttl: None,
+        }
+    }
+
+    /// Caps how long values stay in the `near` layer.
+    ///
+    /// Writes, and values copied into `near` on a read, use the shorter of `ttl` and their
+    /// own remaining TTL. Without it, `near` keeps values as long as they live in `far`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The maximum time-to-live in the `near` layer, truncated to whole seconds.
+    pub fn with_near_ttl(mut self, ttl: Duration) -> Self {
+        self.near_ttl = Some(ttl.as_secs());
+        self
+    }
+
+    /// Returns the fast front layer.
+    pub fn near(&self) -> &dyn Store {
+        self.near.as_ref()
+    }
+
+    /// Returns the backing layer.
+    pub fn far(&self) -> &dyn Store {
+        self.far.as_ref()
+    }
+
+    fn near_ttl_for(&self, ttl: Option<u64>) -> Option<u64> {
+        match (ttl, self.near_ttl) {
+            (Some(ttl), Some(near_ttl)) => Some(ttl.min(near_ttl)),
+            (ttl, near_ttl) => ttl.or(near_ttl),
+        }
+    }
+}
+
+#[async_trait]
+impl Store for TieredStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.near.initialize().await?;
+        self.far.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.near.health_check().await?;
+        self.far.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        if let Some(value) = self.near.get(key).await? {
+            return Ok(Some(value));
+        }
+
+        // The TTL is read first so that an entry expiring in between is never copied
+        // without one. The copy must not outlive the entry in `far`.
+        let ttl = self.far.get_ttl(key).await?;
+        let value = self.far.get(key).await?;
+        if let Some(ref value) = value {
+            self.near
+                .set(key, value.clone(), self.near_ttl_for(ttl))
+                .await?;
+        }
+        Ok(value)
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        if self.near.contains_key(key).await? {
+            return Ok(true);
+        }
+        self.far.contains_key(key).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        // `near` does not keep the timestamps of `far`.
+        self.far.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.far.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.far.set(key, value.clone(), ttl).await?;
+        self.near.set(key, value, self.near_ttl_for(ttl)).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        // Raw bytes are not cached in `near`, so only `far` is read and written.
+        self.far.set_bytes(key, value, ttl).await?;
+        self.near.remove(key).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.far.get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.far.set_many(entries).await?;
+
+        let near_entries: Vec<(&str, Value, Option<u64>)> = entries
+            .iter()
+            .map(|(key, value, ttl)| (*key, value.clone(), self.near_ttl_for(*ttl)))
+            .collect();
+        self.near.set_many(&near_entries).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        // The comparison must run against `far`, since `near` may be stale or evicted.
+        let swapped = self
+            .far
+            .compare_and_swap(key, expected, new.clone(), ttl)
+            .await?;
+        if swapped {
+            self.near.set(key, new, self.near_ttl_for(ttl)).await?;
+        } else {
+            self.near.remove(key).await?;
+        }
+        Ok(swapped)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let previous = self.far.swap(key, value.clone(), ttl).await?;
+        self.near.set(key, value, self.near_ttl_for(ttl)).await?;
+        Ok(previous)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let new = self.far.increment(key, delta).await?;
+        self.near.remove(key).await?;
+        Ok(new)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let updated = self.far.expire(key, ttl).await?;
+        if updated {
+            self.near.expire(key, self.near_ttl_for(ttl)).await?;
+        } else {
+            self.near.remove(key).await?;
+        }
+        Ok(updated)
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        let updated = self.far.extend_ttl(key, by_secs).await?;
+        if updated {
+            let ttl = self.far.get_ttl(key).await?;
+            self.near.expire(key, self.near_ttl_for(ttl)).await?;
+        } else {
+            self.near.remove(key).await?;
+        }
+        Ok(updated)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.far.remove(key).await?;
+        self.near.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let value = self.far.get_and_remove(key).await?;
+        self.near.remove(key).await?;
+        Ok(value)
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        let removed = self.far.remove_many(keys).await?;
+        self.near.remove_many(keys).await?;
+        Ok(removed)
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        // Only `far` gets the batch atomically; `near` just drops the keys it touched.
+        self.far.apply_batch(ops).await?;
+        let keys: Vec<&str> = ops.iter().map(BatchOp::key).collect();
+        self.near.remove_many(&keys).await?;
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let renamed = self.far.rename(from, to).await?;
+        self.near.remove_many(&[from, to]).await?;
+        Ok(renamed)
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.far.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.far.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.far.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.far.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.far.export()
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.far.len().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.far.clear().await?;
+        self.near.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let removed = self.far.clear_prefix(prefix).await?;
+        self.near.clear_prefix(prefix).await?;
+        Ok(removed)
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.far.optimize().await?;
+        self.near.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.far.close().await?;
+        self.near.close().await
+    }
+}
@@ -0,0 +1,2 @@
+mod timeout;
+pub use timeout::*;
@@ -0,0 +1,266 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use tokio::sync::Notify;
+
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// A store that fails any call to an inner store taking longer than a fixed timeout.
+///
+/// Each call is wrapped in `tokio::time::timeout` and fails with `StoreError::Timeout`
+/// once the limit elapses. The inner operation is dropped at that point, so a write
+/// that timed out may or may not have been applied. For `iter`, the limit applies to
+/// each batch fetched from the inner store rather than to the whole stream. Likewise,
+/// `import` only fails once the inner store goes longer than the limit without taking
+/// the next entry, so a large import is not cut short while it keeps making progress.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, timeout::TimeoutStore}};
+/// # use std::{sync::Arc, time::Duration};
+/// # async {
+/// let store = TimeoutStore::new(Arc::new(InMemoryStore::new()), Duration::from_secs(2));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct TimeoutStore {
+    inner: Arc<dyn Store>,
+    timeout: Duration,
+}
+
+impl TimeoutStore {
+    /// Creates a store that fails calls to `inner` taking longer than `timeout`.
+    pub fn new(inner: Arc<dyn Store>, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+
+    async fn run<T>(
+        &self,
+        operation: impl Future<Output = Result<T, StoreError>>,
+    ) -> Result<T, StoreError> {
+        tokio::time::timeout(self.timeout, operation)
+            .await
+            .map_err(|_| StoreError::Timeout(self.timeout))?
+    }
+
+    /// Fails `entries` with a timeout when fetching the next item takes too long.
+    ///
+    /// The stream ends after a timeout, since the inner stream is left mid-batch.
+    fn stream<'a, T: 'a>(
+        &self,
+        entries: impl Stream<Item = Result<T, StoreError>> + Unpin + 'a,
+    ) -> impl Stream<Item = Result<T, StoreError>> + 'a {
+        let timeout = self.timeout;
+        stream::unfold(Some(entries), move |entries| async move {
+            let mut entries = entries?;
+            match tokio::time::timeout(timeout, entries.next()).await {
+                Ok(Some(entry)) => Some((entry, Some(entries))),
+                Ok(None) => None,
+                Err(_) => Some((Err(StoreError::Timeout(timeout)), None)),
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Store for TimeoutStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.run(self.inner.initialize()).await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.run(self.inner.health_check()).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.run(self.inner.get(key)).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.run(self.inner.contains_key(key)).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.run(self.inner.get_many(keys)).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.run(self.inner.get_with_meta(key)).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.run(self.inner.get_ttl(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.run(self.inner.set(key, value, ttl)).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.run(self.inner.set_with_ttl(key, value, ttl)).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.run(self.inner.set_bytes(key, value, ttl)).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.run(self.inner.get_bytes(key)).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.run(self.inner.set_many(entries)).await
+    }
+
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.run(self.inner.set_group(entries, ttl)).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.run(self.inner.compare_and_swap(key, expected, new, ttl))
+            .await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.run(self.inner.set_nx(key, value, ttl)).await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.run(self.inner.swap(key, value, ttl)).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.run(self.inner.increment(key, delta)).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.run(self.inner.expire(key, ttl)).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.run(self.inner.extend_ttl(key, by_secs)).await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.run(self.inner.get_field(key, field)).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.run(self.inner.set_field(key, field, value, ttl)).await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.run(self.inner.set_json_path(key, path, value)).await
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.run(self.inner.remove_field(key, field)).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.run(self.inner.remove(key)).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.run(self.inner.get_and_remove(key)).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.run(self.inner.remove_many(keys)).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.run(self.inner.apply_batch(ops)).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.run(self.inner.rename(from, to)).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.run(self.inner.keys(prefix)).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.run(self.inner.range(start, end)).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        Box::pin(self.stream(self.inner.iter()))
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        Box::pin(self.stream(self.inner.scan_prefix(prefix)))
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        Box::pin(self.stream(self.inner.export()))
+    }
+
+    async fn import(&self, entries: ExportStream<'_>) -> Result<u64, StoreError> {
+        // The deadline restarts whenever the inner store takes an entry, which covers
+        // each batch it writes in between.
+        let progress = Notify::new();
+        let entries = Box::pin(entries.inspect(|_| progress.notify_one()));
+        let import = self.inner.import(entries);
+        tokio::pin!(import);
+        loop {
+            tokio::select! {
+                result = &mut import => return result,
+                _ = progress.notified() => {}
+                _ = tokio::time::sleep(self.timeout) => {
+                    return Err(StoreError::Timeout(self.timeout));
+                }
+            }
+        }
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.run(self.inner.len()).await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.run(self.inner.is_empty()).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.run(self.inner.clear()).await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.run(self.inner.clear_prefix(prefix)).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.run(self.inner.optimize()).await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.run(self.inner.close()).await
+    }
+}
@@ -0,0 +1,2 @@
+mod validated;
+pub use validated::*;
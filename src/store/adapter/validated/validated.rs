@@ -0,0 +1,379 @@
+use std::{io, sync::Arc};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError, Ttl};
+
+/// A store that rejects keys and values breaking a set of rules before they reach an
+/// inner store.
+///
+/// Without it, an oversized key fails deep inside the driver or, depending on the
+/// backend, is silently truncated. Every call taking a key checks it first and fails
+/// with `StoreError::InvalidKey` without touching the inner store. Prefixes passed to
+/// `keys` and `clear_prefix` are not checked. Likewise, a value larger than
+/// `max_value_bytes` fails with `StoreError::ValueTooLarge`. No rule applies until one
+/// is configured.
+///
+/// Key lengths are counted in bytes of UTF-8. Recommended limits per backend:
+///
+/// - MySQL: 255, the length of the `VARCHAR(255)` key column. Longer keys are truncated
+///   or rejected depending on the SQL mode, and multi-byte characters count once there.
+/// - Postgres: 2048. The key column is unbounded, but index entries larger than about
+///   2.7 KB cannot be written.
+/// - Redis: 1024. Keys may be up to 512 MB, but long keys slow down every lookup.
+/// - MongoDB: 1024, the index key limit of servers older than 4.2.
+/// - SQLite, the file system and the in-memory store have no practical limit.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, validated::ValidatedStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let store = ValidatedStore::new(Arc::new(InMemoryStore::new()))
+///     .max_key_length(255)
+///     .forbidden_chars(['\n', '\0'])
+///     .max_value_bytes(1024 * 1024);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// assert!(keyv.set("line\nbreak", 1).await.is_err());
+/// # };
+/// ```
+pub struct ValidatedStore {
+    inner: Arc<dyn Store>,
+    max_key_length: Option<usize>,
+    forbidden_chars: Vec<char>,
+    max_value_bytes: Option<usize>,
+}
+
+impl ValidatedStore {
+    /// Creates a store that checks the keys passed to `inner`. No rule is set yet.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self {
+            inner,
+            max_key_length: None,
+            forbidden_chars: Vec::new(),
+            max_value_bytes: None,
+        }
+    }
+
+    /// Rejects keys longer than `bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The maximum length of a key, in bytes of UTF-8.
+    pub fn max_key_length(mut self, bytes: usize) -> Self {
+        self.max_key_length = Some(bytes);
+        self
+    }
+
+    /// Rejects keys containing any of `chars`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters keys may not contain, added to any set before.
+    pub fn forbidden_chars<I: IntoIterator<Item = char>>(mut self, chars: I) -> Self {
+        self.forbidden_chars.extend(chars);
+        self
+    }
+
+    /// Rejects values whose encoding is longer than `bytes`.
+    ///
+    /// Values are measured as the JSON this store passes on, so wrappers applied below
+    /// it count: with a `CompressedStore` or an `EncryptedStore` as the inner store, the
+    /// limit applies to the compressed or encrypted payload. Byte values written with
+    /// `set_bytes` are measured as they are. `set_field` and `set_json_path` measure the
+    /// value they write into the document, not the document it ends up in. Serializers and
+    /// column types of the backend may store a value in somewhat more or less space.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The maximum size of a value, in bytes.
+    pub fn max_value_bytes(mut self, bytes: usize) -> Self {
+        self.max_value_bytes = Some(bytes);
+        self
+    }
+
+    fn check(&self, key: &str) -> Result<(), StoreError> {
+        if let Some(max) = self.max_key_length.filter(|&max| key.len() > max) {
+            return Err(StoreError::InvalidKey(format!(
+                "key is {} bytes long, the limit is {}",
+                key.len(),
+                max
+            )));
+        }
+        if let Some(c) = key.chars().find(|c| self.forbidden_chars.contains(c)) {
+            return Err(StoreError::InvalidKey(format!(
+                "key contains the forbidden character {:?}",
+                c
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_all<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> Result<(), StoreError> {
+        keys.into_iter().try_for_each(|key| self.check(key))
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), StoreError> {
+        match self.max_value_bytes {
+            Some(limit) if size > limit => Err(StoreError::ValueTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_value(&self, key: &str, value: &Value) -> Result<(), StoreError> {
+        if self.max_value_bytes.is_none() {
+            return Ok(());
+        }
+        // Count the encoded bytes without buffering a copy of a possibly huge value.
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, value).map_err(|e| StoreError::SerializationError {
+            key: Some(key.to_string()),
+            source: e,
+        })?;
+        self.check_size(counter.0)
+    }
+}
+
+/// An `io::Write` that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for ValidatedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.check(key)?;
+        self.inner.get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.check_all(keys.iter().copied())?;
+        self.inner.get_many(keys).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.check(key)?;
+        self.inner.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.check(key)?;
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.set_with_ttl(key, value, ttl).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.check(key)?;
+        self.check_size(value.len())?;
+        self.inner.set_bytes(key, value, ttl).await
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.check(key)?;
+        self.inner.get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        for (key, value, _) in entries {
+            self.check(key)?;
+            self.check_value(key, value)?;
+        }
+        self.inner.set_many(entries).await
+    }
+
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        for (key, value) in entries {
+            self.check(key)?;
+            self.check_value(key, value)?;
+        }
+        self.inner.set_group(entries, ttl).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.check_value(key, &new)?;
+        self.inner.compare_and_swap(key, expected, new, ttl).await
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.set_nx(key, value, ttl).await
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.swap(key, value, ttl).await
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.check(key)?;
+        self.inner.get_field(key, field).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.set_field(key, field, value, ttl).await
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.check_value(key, &value)?;
+        self.inner.set_json_path(key, path, value).await
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.inner.remove_field(key, field).await
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        self.check(key)?;
+        self.inner.increment(key, delta).await
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.check(key)?;
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.check(key)?;
+        self.inner.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.check(key)?;
+        self.inner.get_and_remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.check_all(keys.iter().copied())?;
+        self.inner.remove_many(keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, .. } => {
+                    self.check(key)?;
+                    self.check_value(key, value)?;
+                }
+                BatchOp::Remove { key } => self.check(key)?,
+            }
+        }
+        self.inner.apply_batch(ops).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.check_all([from, to])?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.inner.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.inner.is_empty().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.inner.clear_prefix(prefix).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
@@ -0,0 +1,2 @@
+mod verified;
+pub use verified::*;
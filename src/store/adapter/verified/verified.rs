@@ -0,0 +1,301 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    store::json_path::JsonPath, BatchOp, EntryMeta, EntryStream, ExportStream, Store, StoreError,
+    Ttl,
+};
+
+/// A store that reads every value back right after writing it to an inner store and
+/// checks that it comes back unchanged.
+///
+/// This catches values that a serializer, a codec or the backend itself mangles, such as
+/// large integers losing precision, at the moment they are written rather than when they
+/// are next read. A mismatch fails with `StoreError::IntegrityError`; the value has been
+/// written by then. Each write costs an extra read, so this is meant for development and
+/// debugging.
+///
+/// A concurrent write to the same key between the write and the read back is reported as
+/// a mismatch as well. Writes that store nothing, such as a `compare_and_swap` that does
+/// not swap, are not checked. `set_field`, `remove_field` and `set_json_path` check only
+/// the part of the document they changed, since other parts may be updated concurrently.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::{inmemory::InMemoryStore, verified::VerifiedStore}};
+/// # use std::sync::Arc;
+/// # async {
+/// let store = VerifiedStore::new(Arc::new(InMemoryStore::new()));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("id", u64::MAX).await.unwrap();
+/// # };
+/// ```
+pub struct VerifiedStore {
+    inner: Arc<dyn Store>,
+}
+
+impl VerifiedStore {
+    /// Creates a store that checks the values written to `inner`.
+    pub fn new(inner: Arc<dyn Store>) -> Self {
+        Self { inner }
+    }
+
+    async fn verify(&self, key: &str, expected: &Value) -> Result<(), StoreError> {
+        match self.inner.get(key).await? {
+            Some(value) if value == *expected => Ok(()),
+            _ => Err(StoreError::IntegrityError(key.to_string())),
+        }
+    }
+
+    /// Checks that each key holds its expected value, or is absent where `None` is expected.
+    async fn verify_many(&self, expected: HashMap<&str, Option<&Value>>) -> Result<(), StoreError> {
+        let (keys, expected): (Vec<&str>, Vec<Option<&Value>>) = expected.into_iter().unzip();
+        let values = self.inner.get_many(&keys).await?;
+        for ((key, expected), value) in keys.iter().zip(expected).zip(values) {
+            if value.as_ref() != expected {
+                return Err(StoreError::IntegrityError(key.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for VerifiedStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.inner.initialize().await
+    }
+
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.inner.health_check().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.contains_key(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.inner.get_many(keys).await
+    }
+
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        self.inner.get_with_meta(key).await
+    }
+
+    async fn get_ttl(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.inner.get_ttl(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, value.clone(), ttl).await?;
+        self.verify(key, &value).await
+    }
+
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.inner.set_with_ttl(key, value.clone(), ttl).await?;
+        self.verify(key, &value).await
+    }
+
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set_bytes(key, value, ttl).await?;
+        match self.inner.get_bytes(key).await? {
+            Some(bytes) if bytes == value => Ok(()),
+            _ => Err(StoreError::IntegrityError(key.to_string())),
+        }
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.inner.get_bytes(key).await
+    }
+
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        self.inner.set_many(entries).await?;
+        // A key listed twice ends up with its last value.
+        let expected: HashMap<&str, Option<&Value>> = entries
+            .iter()
+            .map(|(key, value, _)| (*key, Some(value)))
+            .collect();
+        self.verify_many(expected).await
+    }
+
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.inner.set_group(entries, ttl).await?;
+        let expected: HashMap<&str, Option<&Value>> = entries
+            .iter()
+            .map(|(key, value)| (*key, Some(value)))
+            .collect();
+        self.verify_many(expected).await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        let swapped = self
+            .inner
+            .compare_and_swap(key, expected, new.clone(), ttl)
+            .await?;
+        if swapped {
+            self.verify(key, &new).await?;
+        }
+        Ok(swapped)
+    }
+
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        let written = self.inner.set_nx(key, value.clone(), ttl).await?;
+        if written {
+            self.verify(key, &value).await?;
+        }
+        Ok(written)
+    }
+
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let previous = self.inner.swap(key, value.clone(), ttl).await?;
+        self.verify(key, &value).await?;
+        Ok(previous)
+    }
+
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get_field(key, field).await
+    }
+
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        self.inner.set_field(key, field, value.clone(), ttl).await?;
+        match self.inner.get_field(key, field).await? {
+            Some(stored) if stored == value => Ok(()),
+            _ => Err(StoreError::IntegrityError(key.to_string())),
+        }
+    }
+
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        let parsed = JsonPath::parse(path)?;
+        let updated = self.inner.set_json_path(key, path, value.clone()).await?;
+        if updated {
+            match self.inner.get(key).await? {
+                Some(document) if parsed.get(&document) == Some(&value) => {}
+                _ => return Err(StoreError::IntegrityError(key.to_string())),
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        let removed = self.inner.remove_field(key, field).await?;
+        if removed && self.inner.get_field(key, field).await?.is_some() {
+            return Err(StoreError::IntegrityError(key.to_string()));
+        }
+        Ok(removed)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let value = self.inner.increment(key, delta).await?;
+        self.verify(key, &Value::from(value)).await?;
+        Ok(value)
+    }
+
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.inner.expire(key, ttl).await
+    }
+
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        self.inner.extend_ttl(key, by_secs).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get_and_remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        self.inner.apply_batch(ops).await?;
+        // Only the last operation on each key determines what it holds afterwards.
+        let expected: HashMap<&str, Option<&Value>> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, .. } => (key.as_str(), Some(value)),
+                BatchOp::Remove { key } => (key.as_str(), None),
+            })
+            .collect();
+        self.verify_many(expected).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        self.inner.range(start, end).await
+    }
+
+    fn iter(&self) -> EntryStream<'_> {
+        self.inner.iter()
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        self.inner.scan_prefix(prefix)
+    }
+
+    fn export(&self) -> ExportStream<'_> {
+        self.inner.export()
+    }
+
+    async fn len(&self) -> Result<usize, StoreError> {
+        self.inner.len().await
+    }
+
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        self.inner.is_empty().await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        self.inner.clear_prefix(prefix).await
+    }
+
+    async fn optimize(&self) -> Result<(), StoreError> {
+        self.inner.optimize().await
+    }
+
+    async fn close(&self) -> Result<(), StoreError> {
+        self.inner.close().await
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,12 +7,32 @@ pub enum StoreError {
     #[error("Failed to connect to the database backend: {0}")]
     ConnectionError(String),
 
-    #[error("Error while serializing or deserializing data")]
+    /// A value could not be encoded before being written.
+    #[error("Failed to serialize the value{}", for_key(.key))]
     SerializationError {
-        #[from]
+        key: Option<String>,
+        #[source]
         source: serde_json::Error,
     },
 
+    /// A stored value could not be decoded after being read, which usually means it is
+    /// corrupt or was written in another format.
+    #[error("Failed to deserialize the value{}", for_key(.key))]
+    DeserializationError {
+        key: Option<String>,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Error while encoding or decoding data")]
+    CodecError {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Failed to decrypt the value stored at key '{0}'")]
+    DecryptionError(String),
+
     #[error("Database operation failed")]
     DatabaseError {
         #[source]
@@ -20,9 +42,65 @@ pub enum StoreError {
     #[error("Database query error: {0}")]
     QueryError(String),
 
+    #[error("The value stored at key '{0}' is not an integer")]
+    NotAnInteger(String),
+
+    #[error("The value stored at key '{0}' is not a JSON object")]
+    NotAnObject(String),
+
     #[error("The requested key was not found")]
     NotFound,
 
+    #[error("Key '{0}' does not exist")]
+    KeyNotFound(String),
+
+    /// A key was rejected by a `ValidatedStore` before reaching the backend.
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    /// A path passed to `Store::set_json_path` is not valid.
+    #[error("Invalid JSON path: {0}")]
+    InvalidJsonPath(String),
+
+    /// A value was rejected by a `ValidatedStore` before reaching the backend.
+    #[error("The value is {size} bytes long, more than the limit of {limit}")]
+    ValueTooLarge { size: usize, limit: usize },
+
+    /// A value read back by a `VerifiedStore` right after being written differs from the
+    /// value written.
+    #[error("The value read back for key '{0}' does not match the value written")]
+    IntegrityError(String),
+
+    /// A write was rejected by a `ReadOnlyStore` before reaching the backend.
+    #[error("The store is read-only")]
+    ReadOnly,
+
+    /// An operation failed for a reason that may go away on its own, such as a deadlock,
+    /// a lock timeout or a dropped connection. `RetryingStore` retries these; every other
+    /// error is treated as permanent.
+    #[error("Transient store error: {0}")]
+    Transient(String),
+
+    /// The store does not implement the named operation.
+    #[error("The store does not support {0}")]
+    Unsupported(String),
+
+    #[error("Store operation timed out after {0:?}")]
+    Timeout(Duration),
+
     #[error("An unknown error has occurred")]
     Unknown,
 }
+
+impl StoreError {
+    /// Returns `true` if retrying the failed operation may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, StoreError::Transient(_))
+    }
+}
+
+fn for_key(key: &Option<String>) -> String {
+    key.as_ref()
+        .map(|key| format!(" stored at key '{}'", key))
+        .unwrap_or_default()
+}
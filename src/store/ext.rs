@@ -0,0 +1,178 @@
+use std::{collections::HashMap, future::Future};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{Store, StoreError};
+
+/// Convenience methods available on every `Store`.
+///
+/// The helpers live outside `Store` so that the core trait stays object-safe. A blanket
+/// implementation covers every store, including `dyn Store` behind a `Box` or an `Arc`.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Store, StoreExt, adapter::inmemory::InMemoryStore};
+/// # use std::sync::Arc;
+/// # async {
+/// let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+/// store.set("count", 3.into(), None).await.unwrap();
+///
+/// let count: Option<u32> = store.get_as("count").await.unwrap();
+/// assert_eq!(count, Some(3));
+/// assert!(store.has("count").await.unwrap());
+/// # };
+/// ```
+#[async_trait]
+pub trait StoreExt: Store {
+    /// Retrieves the value for `key` and deserializes it into `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Returns
+    /// - `Ok(Some(T))` if the key exists.
+    /// - `Ok(None)` if it does not.
+    /// - `Err(StoreError::DeserializationError)` if the stored value is not a valid `T`.
+    async fn get_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StoreError>;
+
+    /// Retrieves the value for a key that is expected to exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Returns
+    /// - `Ok(Value)` if the key exists.
+    /// - `Err(StoreError::KeyNotFound)` if it does not.
+    /// - `Err(StoreError)` if the read fails.
+    async fn get_required(&self, key: &str) -> Result<Value, StoreError>;
+
+    /// Returns the value for `key`, computing and storing it with `f` if it is missing.
+    ///
+    /// `f` only runs on a miss. The new value is written with `Store::set_nx`, so when
+    /// several callers miss at once they may all run `f`, but only the first write is
+    /// kept and the others return the stored value instead of their own. This holds only
+    /// if the store's `set_nx` is atomic; with the default `set_nx` a concurrent caller
+    /// can overwrite the value. `RedisStore::get_or_insert_with` does the write and the
+    /// read back in a single command.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    /// * `ttl` - The TTL, in seconds, of a newly stored value.
+    /// * `f` - Produces the value to store when the key is missing.
+    ///
+    /// # Returns
+    /// - `Ok(Value)` with the existing or newly stored value.
+    /// - `Err(StoreError)` if the read or the write fails.
+    async fn get_or_insert_with<F, Fut>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        f: F,
+    ) -> Result<Value, StoreError>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Value> + Send;
+
+    /// Checks whether `key` exists. Shorthand for `Store::contains_key`.
+    async fn has(&self, key: &str) -> Result<bool, StoreError>;
+
+    /// Retrieves the values for `keys` with a single `Store::get_many` and tells which of
+    /// them were missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to look up.
+    ///
+    /// # Returns
+    /// - `Ok((found, missing))` with a map from each existing key to its value, and the
+    ///   missing keys in the order they were requested, each listed once.
+    /// - `Err(StoreError)` if the read fails.
+    async fn get_many_partial<K: AsRef<str> + Sync>(
+        &self,
+        keys: &[K],
+    ) -> Result<(HashMap<String, Value>, Vec<String>), StoreError>;
+}
+
+#[async_trait]
+impl<S: Store + ?Sized> StoreExt for S {
+    async fn get_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StoreError> {
+        self.get(key)
+            .await?
+            .map(|value| {
+                serde_json::from_value(value).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            })
+            .transpose()
+    }
+
+    async fn get_required(&self, key: &str) -> Result<Value, StoreError> {
+        self.get(key)
+            .await?
+            .ok_or_else(|| StoreError::KeyNotFound(key.to_string()))
+    }
+
+    async fn get_or_insert_with<F, Fut>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        f: F,
+    ) -> Result<Value, StoreError>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Value> + Send,
+    {
+        if let Some(value) = self.get(key).await? {
+            return Ok(value);
+        }
+
+        let value = f().await;
+        if self.set_nx(key, value.clone(), ttl).await? {
+            return Ok(value);
+        }
+
+        // Another caller stored a value first. Should it already be gone again, ours is
+        // still the value this call computed.
+        Ok(self.get(key).await?.unwrap_or(value))
+    }
+
+    async fn has(&self, key: &str) -> Result<bool, StoreError> {
+        self.contains_key(key).await
+    }
+
+    async fn get_many_partial<K: AsRef<str> + Sync>(
+        &self,
+        keys: &[K],
+    ) -> Result<(HashMap<String, Value>, Vec<String>), StoreError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        let values = self.get_many(&keys).await?;
+        Ok(split_found(&keys, values))
+    }
+}
+
+/// Splits the result of a `get_many` into the values found, keyed by key, and the keys
+/// that were missing, in request order and without duplicates.
+pub(crate) fn split_found(
+    keys: &[&str],
+    values: Vec<Option<Value>>,
+) -> (HashMap<String, Value>, Vec<String>) {
+    let mut found = HashMap::with_capacity(keys.len());
+    let mut missing: Vec<String> = Vec::new();
+    for (key, value) in keys.iter().zip(values) {
+        match value {
+            Some(value) => {
+                found.insert(key.to_string(), value);
+            }
+            None if !missing.iter().any(|m| m == key) => missing.push(key.to_string()),
+            None => {}
+        }
+    }
+    (found, missing)
+}
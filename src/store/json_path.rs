@@ -0,0 +1,160 @@
+use serde_json::Value;
+
+use super::StoreError;
+
+/// One step of a `JsonPath`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PathSegment {
+    /// `.name`, a field of an object.
+    Field(String),
+    /// `[n]`, an element of an array.
+    Index(usize),
+}
+
+/// A parsed path into a JSON document, such as `$.profile.emails[0]`.
+///
+/// A path starts with `$`, the document itself, followed by at least one `.name` or `[n]`
+/// segment. Field names may contain any character except whitespace, `.`, `[`, `]`, `"`,
+/// `'`, `\` and `$`; indexes are non-negative integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    pub(crate) fn parse(path: &str) -> Result<Self, StoreError> {
+        let invalid = |reason: &str| StoreError::InvalidJsonPath(format!("'{}' {}", path, reason));
+
+        let mut rest = path
+            .strip_prefix('$')
+            .ok_or_else(|| invalid("must start with '$'"))?;
+        let mut segments = Vec::new();
+        while let Some(c) = rest.chars().next() {
+            match c {
+                '.' => {
+                    let end = rest[1..].find(['.', '[']).map_or(rest.len(), |i| i + 1);
+                    let name = &rest[1..end];
+                    if name.is_empty() {
+                        return Err(invalid("has an empty field name"));
+                    }
+                    if name
+                        .chars()
+                        .any(|c| c.is_whitespace() || "]\"'\\$".contains(c))
+                    {
+                        return Err(invalid("has an invalid character in a field name"));
+                    }
+                    segments.push(PathSegment::Field(name.to_string()));
+                    rest = &rest[end..];
+                }
+                '[' => {
+                    let end = rest
+                        .find(']')
+                        .ok_or_else(|| invalid("has an unclosed '['"))?;
+                    let index = &rest[1..end];
+                    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(invalid("has an array index that is not a number"));
+                    }
+                    let index = index
+                        .parse()
+                        .map_err(|_| invalid("has an array index that is too large"))?;
+                    segments.push(PathSegment::Index(index));
+                    rest = &rest[end + 1..];
+                }
+                _ => return Err(invalid("must separate segments with '.' or '['")),
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(invalid("must name at least one field or index"));
+        }
+        Ok(Self { segments })
+    }
+
+    /// The last segment, the one that is written.
+    pub(crate) fn last(&self) -> &PathSegment {
+        self.segments.last().expect("a parsed path is never empty")
+    }
+
+    /// The path without its last segment.
+    pub(crate) fn parent(&self) -> JsonPath {
+        JsonPath {
+            segments: self.segments[..self.segments.len() - 1].to_vec(),
+        }
+    }
+
+    /// The path as a Postgres `text[]`, for `#>` and `jsonb_set`.
+    #[cfg(feature = "postgres")]
+    pub(crate) fn to_pg_array(&self) -> Vec<String> {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Field(name) => name.clone(),
+                PathSegment::Index(index) => index.to_string(),
+            })
+            .collect()
+    }
+
+    /// The path in SQLite's syntax, with every field name quoted.
+    #[cfg(feature = "sqlite")]
+    pub(crate) fn to_sqlite(&self) -> String {
+        let mut path = "$".to_string();
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Field(name) => path.push_str(&format!(".\"{}\"", name)),
+                PathSegment::Index(index) => path.push_str(&format!("[{}]", index)),
+            }
+        }
+        path
+    }
+
+    /// Returns the value at this path inside `document`, if there is one.
+    pub(crate) fn get<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(document, |target, segment| match segment {
+                PathSegment::Field(name) => target.get(name),
+                PathSegment::Index(index) => target.get(*index),
+            })
+    }
+
+    /// Writes `value` at this path inside `document`.
+    ///
+    /// The parent must already exist: the last field is added to its object if missing,
+    /// while the last index must point at an existing array element.
+    ///
+    /// # Returns
+    /// `true` if `document` was changed, `false` if the parent is missing or of the wrong
+    /// type.
+    pub(crate) fn apply(&self, document: &mut Value, value: Value) -> bool {
+        let mut target = document;
+        for segment in &self.segments[..self.segments.len() - 1] {
+            let next = match segment {
+                PathSegment::Field(name) => target.as_object_mut().and_then(|o| o.get_mut(name)),
+                PathSegment::Index(index) => target.as_array_mut().and_then(|a| a.get_mut(*index)),
+            };
+            match next {
+                Some(next) => target = next,
+                None => return false,
+            }
+        }
+
+        match self.last() {
+            PathSegment::Field(name) => match target.as_object_mut() {
+                Some(object) => {
+                    object.insert(name.clone(), value);
+                    true
+                }
+                None => false,
+            },
+            PathSegment::Index(index) => {
+                match target.as_array_mut().and_then(|a| a.get_mut(*index)) {
+                    Some(element) => {
+                        *element = value;
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+}
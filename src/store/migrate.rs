@@ -0,0 +1,33 @@
+use super::{Store, StoreError};
+
+/// Copies every live entry of `src` into `dst`, keeping keys, values and expiry.
+///
+/// This is `dst.import(src.export())`, so the two stores may use different backends.
+/// Entries are streamed in batches and never all held in memory. `dst` keeps its other
+/// keys and has existing ones overwritten; clear it first for an exact copy.
+///
+/// # Arguments
+///
+/// * `src` - The store to read from.
+/// * `dst` - The store to write to.
+///
+/// # Returns
+/// - `Ok(u64)` with the number of entries copied.
+/// - `Err(StoreError)` if reading from `src` or writing to `dst` fails.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{migrate, Store, adapter::inmemory::InMemoryStore};
+/// # async {
+/// let old = InMemoryStore::new();
+/// old.set("user", "alice".into(), Some(3600)).await.unwrap();
+///
+/// let new = InMemoryStore::new();
+/// assert_eq!(migrate(&old, &new).await.unwrap(), 1);
+/// assert_eq!(new.get("user").await.unwrap().unwrap(), "alice");
+/// # };
+/// ```
+pub async fn migrate(src: &dyn Store, dst: &dyn Store) -> Result<u64, StoreError> {
+    dst.import(src.export()).await
+}
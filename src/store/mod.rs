@@ -1,7 +1,23 @@
 mod store;
 pub use store::*;
 
+mod ext;
+pub use ext::*;
+
+mod migrate;
+pub use migrate::*;
+
 mod errors;
 pub use errors::*;
 
+mod serializer;
+pub use serializer::*;
+
+mod ttl;
+pub use ttl::*;
+
+pub(crate) mod json_path;
+
+pub(crate) mod utils;
+
 pub mod adapter;
@@ -0,0 +1,90 @@
+use serde_json::Value;
+
+use super::StoreError;
+
+/// Converts values to and from the bytes kept by a store.
+///
+/// Adapters that store raw bytes accept a serializer on their builder. `JsonSerializer`
+/// is always the default, so data written by earlier versions stays readable.
+pub trait Serializer: Send + Sync {
+    /// Encodes `value` into bytes.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)` with the encoded value.
+    /// - `Err(StoreError)` if the value cannot be encoded.
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError>;
+
+    /// Decodes bytes previously produced by `serialize`.
+    ///
+    /// # Returns
+    /// - `Ok(Value)` with the decoded value.
+    /// - `Err(StoreError)` if the bytes are not valid in this format.
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, StoreError>;
+
+    /// Returns `true` if integers are encoded as their plain decimal text.
+    ///
+    /// Backends with a native counter, such as redis `INCR`, can only use it when this
+    /// holds. Defaults to `false`.
+    fn is_plain_text(&self) -> bool {
+        false
+    }
+}
+
+/// Encodes values as JSON text. This is the default format of every adapter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        serde_json::to_vec(value).map_err(|e| StoreError::SerializationError {
+            key: None,
+            source: e,
+        })
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, StoreError> {
+        serde_json::from_slice(bytes).map_err(|e| StoreError::DeserializationError {
+            key: None,
+            source: e,
+        })
+    }
+
+    fn is_plain_text(&self) -> bool {
+        true
+    }
+}
+
+/// Encodes values as MessagePack, with map keys kept as strings.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MessagePackSerializer;
+
+#[cfg(feature = "msgpack")]
+impl Serializer for MessagePackSerializer {
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        rmp_serde::to_vec_named(value).map_err(|e| StoreError::CodecError { source: e.into() })
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, StoreError> {
+        rmp_serde::from_slice(bytes).map_err(|e| StoreError::CodecError { source: e.into() })
+    }
+}
+
+/// Encodes values as CBOR.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CborSerializer;
+
+#[cfg(feature = "cbor")]
+impl Serializer for CborSerializer {
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes)
+            .map_err(|e| StoreError::CodecError { source: e.into() })?;
+        Ok(bytes)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, StoreError> {
+        ciborium::from_reader(bytes).map_err(|e| StoreError::CodecError { source: e.into() })
+    }
+}
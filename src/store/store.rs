@@ -1,7 +1,109 @@
+use std::{pin::Pin, sync::Once};
+
 use async_trait::async_trait;
-use serde_json::Value;
+use futures::{stream, Stream, TryStreamExt};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::{
+    json_path::JsonPath,
+    utils::{expires_at, remaining_ttl, to_value, ITER_BATCH_SIZE},
+    StoreError, Ttl,
+};
+
+/// Makes the default `Store::set_json_path` warn only once per process.
+static JSON_PATH_FALLBACK: Once = Once::new();
+
+/// How often the default read-modify-write methods retry a `compare_and_swap` that lost
+/// to another write before failing with `StoreError::Transient`.
+const CAS_ATTEMPTS: u32 = 32;
+
+/// A stream of the `(key, value)` pairs held by a store, as returned by `Store::iter`.
+pub type EntryStream<'a> =
+    Pin<Box<dyn Stream<Item = Result<(String, Value), StoreError>> + Send + 'a>>;
+
+/// A `(key, value, expires_at)` entry of an export.
+///
+/// `expires_at` is an absolute unix timestamp in seconds, or `None` for an entry that
+/// never expires.
+pub type ExportEntry = (String, Value, Option<u64>);
+
+/// A stream of entries, as returned by `Store::export` and consumed by `Store::import`.
+pub type ExportStream<'a> =
+    Pin<Box<dyn Stream<Item = Result<ExportEntry, StoreError>> + Send + 'a>>;
+
+/// A single write applied as part of `Store::apply_batch`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchOp {
+    /// Sets `key` to `value`, expiring after `ttl` seconds when one is given.
+    Set {
+        key: String,
+        value: Value,
+        ttl: Option<u64>,
+    },
+    /// Removes `key`, if it exists.
+    Remove { key: String },
+}
+
+impl BatchOp {
+    /// Creates an operation setting `key` to `value` without an explicit TTL.
+    ///
+    /// # Returns
+    /// - `Ok(BatchOp)` with the serialized value.
+    /// - `Err(StoreError::SerializationError)` if JSON cannot hold `value`, such as a map
+    ///   with non-string keys.
+    pub fn set<K: Into<String>, T: Serialize>(key: K, value: T) -> Result<Self, StoreError> {
+        let key = key.into();
+        let value = to_value(&key, value)?;
+        Ok(BatchOp::Set {
+            key,
+            value,
+            ttl: None,
+        })
+    }
+
+    /// Creates an operation setting `key` to `value`, expiring after `ttl` seconds.
+    ///
+    /// # Returns
+    /// - `Ok(BatchOp)` with the serialized value.
+    /// - `Err(StoreError::SerializationError)` if JSON cannot hold `value`.
+    pub fn set_with_ttl<K: Into<String>, T: Serialize>(
+        key: K,
+        value: T,
+        ttl: u64,
+    ) -> Result<Self, StoreError> {
+        let key = key.into();
+        let value = to_value(&key, value)?;
+        Ok(BatchOp::Set {
+            key,
+            value,
+            ttl: Some(ttl),
+        })
+    }
 
-use super::StoreError;
+    /// Creates an operation removing `key`.
+    pub fn remove<K: Into<String>>(key: K) -> Self {
+        BatchOp::Remove { key: key.into() }
+    }
+
+    /// Returns the key the operation writes to.
+    pub fn key(&self) -> &str {
+        match self {
+            BatchOp::Set { key, .. } | BatchOp::Remove { key } => key,
+        }
+    }
+}
+
+/// Bookkeeping timestamps of a stored entry, as returned by `Store::get_with_meta`.
+///
+/// Both are unix timestamps in seconds, and `None` when the store does not record them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntryMeta {
+    /// When the key was first written. Overwriting it keeps this unchanged.
+    pub created_at: Option<u64>,
+    /// When the value was last written.
+    pub updated_at: Option<u64>,
+}
 
 #[async_trait]
 pub trait Store: Send + Sync {
@@ -14,6 +116,18 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if initialisation fails.
     async fn initialize(&self) -> Result<(), StoreError>;
 
+    /// Checks that the storage backend is reachable, without writing any data.
+    ///
+    /// The default implementation reads a sentinel key that is never written. Adapters
+    /// should override it with the backend's own liveness check, such as `PING`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the backend answered.
+    /// - `Err(StoreError)` if the backend could not be reached.
+    async fn health_check(&self) -> Result<(), StoreError> {
+        self.get("__keyv_health_check__").await.map(|_| ())
+    }
+
     /// Retrieves a value associated with a given key from the store.
     ///
     /// # Arguments
@@ -25,6 +139,79 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if there is an error retrieving the value.
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError>;
 
+    /// Checks whether a given key exists in the store.
+    ///
+    /// The default implementation calls `get` and discards the value, so it honours the
+    /// same expiry rules as `get`. Adapters should override it with a cheaper existence
+    /// check where the backend provides one.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key to look up.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the key exists and has not expired.
+    /// - `Ok(false)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error checking the key.
+    async fn contains_key(&self, key: &str) -> Result<bool, StoreError> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    /// Retrieves the values associated with several keys in one operation.
+    ///
+    /// The default implementation calls `get` once per key. Adapters should override it
+    /// with a single batched query where the backend supports one.
+    ///
+    /// # Arguments
+    /// - `keys`: A slice of string slices representing the keys to retrieve.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Option<Value>>)` with one entry per requested key, in the same order as
+    ///   `keys`, where `None` marks a key that does not exist.
+    /// - `Err(StoreError)` if there is an error retrieving the values.
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Returns the time left before a key expires.
+    ///
+    /// `Ok(None)` is returned both for a missing key and for a key stored without a TTL;
+    /// use `contains_key` to tell the two apart. The default implementation reports
+    /// `Ok(None)` for every key and suits adapters that do not track expiry.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key to inspect.
+    ///
+    /// # Returns
+    /// - `Ok(Some(u64))` with the remaining seconds, rounded up, if the key has a TTL.
+    /// - `Ok(None)` if the key does not exist or never expires.
+    /// - `Err(StoreError)` if there is an error reading the TTL.
+    async fn get_ttl(&self, _key: &str) -> Result<Option<u64>, StoreError> {
+        Ok(None)
+    }
+
+    /// Retrieves the value for a key together with its `EntryMeta`.
+    ///
+    /// The default implementation calls `get` and returns an empty `EntryMeta`; SQL
+    /// adapters fill it in when their timestamp columns are enabled.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key to look up.
+    ///
+    /// # Returns
+    /// - `Ok(Some((Value, EntryMeta)))` if the key exists.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error retrieving the value.
+    async fn get_with_meta(&self, key: &str) -> Result<Option<(Value, EntryMeta)>, StoreError> {
+        Ok(self
+            .get(key)
+            .await?
+            .map(|value| (value, EntryMeta::default())))
+    }
+
     /// Sets a value for a given key in the store, with an optional time-to-live (TTL).
     ///
     /// # Arguments
@@ -37,6 +224,400 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if there is an error setting the value.
     async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError>;
 
+    /// Sets a value for a given key in the store, expiring after `ttl`.
+    ///
+    /// Unlike `set`, the TTL carries its unit. The default implementation calls `set`
+    /// with the TTL rounded up to whole seconds; adapters that can expire entries more
+    /// precisely, such as Redis with `PX`, override it.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the value is stored.
+    /// - `value`: The value to set.
+    /// - `ttl`: How long the entry lives.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the value is successfully set.
+    /// - `Err(StoreError)` if there is an error setting the value.
+    async fn set_with_ttl(&self, key: &str, value: Value, ttl: Ttl) -> Result<(), StoreError> {
+        self.set(key, value, Some(ttl.as_secs_ceil())).await
+    }
+
+    /// Sets several key-value pairs in one operation, each with an optional time-to-live (TTL).
+    ///
+    /// The default implementation calls `set` once per entry and is therefore not atomic.
+    /// Adapters should override it with a single batched write where the backend supports one.
+    ///
+    /// # Arguments
+    /// - `entries`: A slice of `(key, value, ttl)` tuples to store.
+    ///
+    /// # Returns
+    /// - `Ok(())` if all values are successfully set.
+    /// - `Err(StoreError)` if there is an error setting the values.
+    async fn set_many(&self, entries: &[(&str, Value, Option<u64>)]) -> Result<(), StoreError> {
+        for (key, value, ttl) in entries {
+            self.set(key, value.clone(), *ttl).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes several key-value pairs as one group that shares a single TTL.
+    ///
+    /// Unlike `set_many`, the group is written through `apply_batch`, so it is applied as
+    /// a whole or not at all wherever `apply_batch` is atomic, and all of its entries
+    /// expire together. SQL stores write the group in one transaction, which rolls back
+    /// on any failure. Redis queues the writes in `MULTI`/`EXEC`: other clients never see
+    /// part of the group and a dropped connection before `EXEC` applies none of it, but
+    /// redis has no rollback, so the guarantee rests on `SET` not failing once queued.
+    /// SQL stores compute each expiry as the writes run, so entries may expire up to a
+    /// second apart if the transaction crosses a second boundary.
+    ///
+    /// # Arguments
+    /// - `entries`: The `(key, value)` pairs to store. Later pairs for the same key win.
+    /// - `ttl`: An optional time-to-live in seconds, applied to every entry.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the whole group has been written.
+    /// - `Err(StoreError)` if the group could not be written.
+    async fn set_group(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        let ops: Vec<BatchOp> = entries
+            .iter()
+            .map(|(key, value)| BatchOp::Set {
+                key: key.to_string(),
+                value: value.clone(),
+                ttl,
+            })
+            .collect();
+        self.apply_batch(&ops).await
+    }
+
+    /// Stores raw bytes under `key`, bypassing the JSON value model.
+    ///
+    /// Backends that can hold bytes natively store them as-is: redis keeps the raw string
+    /// and SQLite a `BLOB`. The default implementation stores the bytes as a JSON array of
+    /// numbers through `set`, which works everywhere but takes about three times the space
+    /// of the bytes. Postgres and MySQL use it too, since their value column only holds
+    /// JSON. A key written with `set_bytes` should be read back with `get_bytes`: `get`
+    /// returns the array under the default implementation, while on SQLite it fails with
+    /// `StoreError::DeserializationError` unless the bytes happen to be JSON.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the bytes are stored.
+    /// - `value`: The bytes to store.
+    /// - `ttl`: An optional time-to-live in seconds.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the bytes are successfully stored.
+    /// - `Err(StoreError)` if there is an error writing them.
+    async fn set_bytes(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), StoreError> {
+        self.set(key, json!(value), ttl).await
+    }
+
+    /// Retrieves the bytes stored under `key` with `set_bytes`.
+    ///
+    /// # Arguments
+    /// - `key`: The key to look up.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Vec<u8>))` with the stored bytes.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError::DeserializationError)` if, with the default implementation, the
+    ///   stored value is not an array of bytes.
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        self.get(key)
+            .await?
+            .map(|value| {
+                serde_json::from_value(value).map_err(|e| StoreError::DeserializationError {
+                    key: Some(key.to_string()),
+                    source: e,
+                })
+            })
+            .transpose()
+    }
+
+    /// Sets `new` for `key` only if the current value equals `expected`.
+    ///
+    /// An `expected` of `None` means the key must be absent (or expired) for the write to
+    /// happen. The default implementation performs a `get` followed by a `set` and is
+    /// therefore not atomic; adapters override it with an atomic backend operation where
+    /// one is available.
+    ///
+    /// # Arguments
+    /// - `key`: The key to update.
+    /// - `expected`: The value the key must currently hold, or `None` if it must be absent.
+    /// - `new`: The value to write when the comparison succeeds.
+    /// - `ttl`: An optional time-to-live in seconds for the new value.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the comparison succeeded and `new` was written.
+    /// - `Ok(false)` if the current value did not match `expected`.
+    /// - `Err(StoreError)` if there is an error reading or writing the value.
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<Value>,
+        new: Value,
+        ttl: Option<u64>,
+    ) -> Result<bool, StoreError> {
+        if self.get(key).await? != expected {
+            return Ok(false);
+        }
+        self.set(key, new, ttl).await?;
+        Ok(true)
+    }
+
+    /// Sets a value only if the key does not exist yet.
+    ///
+    /// A key whose TTL has passed counts as absent. The default implementation calls
+    /// `compare_and_swap` with `None` as the expected value, so it is atomic wherever that
+    /// is; adapters may override it with a cheaper native operation.
+    ///
+    /// # Arguments
+    /// - `key`: The key to set.
+    /// - `value`: The value to write when the key is absent.
+    /// - `ttl`: An optional time-to-live in seconds for the new value.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the key was absent and `value` was written.
+    /// - `Ok(false)` if the key already exists.
+    /// - `Err(StoreError)` if there is an error writing the value.
+    async fn set_nx(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<bool, StoreError> {
+        self.compare_and_swap(key, None, value, ttl).await
+    }
+
+    /// Sets a value for `key` and returns the value it replaces.
+    ///
+    /// A key whose TTL has passed counts as absent. The default implementation performs a
+    /// `get` followed by a `set` and is therefore not atomic: a concurrent write between
+    /// the two is lost without being returned. Adapters override it with an atomic backend
+    /// operation where one is available.
+    ///
+    /// # Arguments
+    /// - `key`: The key to set.
+    /// - `value`: The value to write.
+    /// - `ttl`: An optional time-to-live in seconds for the new value.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Value))` with the previous value if the key existed.
+    /// - `Ok(None)` if it did not.
+    /// - `Err(StoreError)` if there is an error reading or writing the value.
+    async fn swap(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<Option<Value>, StoreError> {
+        let previous = self.get(key).await?;
+        self.set(key, value, ttl).await?;
+        Ok(previous)
+    }
+
+    /// Adds `delta` to the integer stored at `key` and returns the new value.
+    ///
+    /// A missing key is treated as `0`. The default implementation performs a `get`
+    /// followed by a `set` and is therefore not atomic; adapters override it with an
+    /// atomic backend operation where one is available.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the counter.
+    /// - `delta`: The amount to add, which may be negative.
+    ///
+    /// # Returns
+    /// - `Ok(i64)` with the value after the increment.
+    /// - `Err(StoreError::NotAnInteger)` if the stored value is not an integer.
+    /// - `Err(StoreError)` if there is an error updating the value.
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, StoreError> {
+        let current = match self.get(key).await? {
+            Some(value) => value
+                .as_i64()
+                .ok_or_else(|| StoreError::NotAnInteger(key.to_string()))?,
+            None => 0,
+        };
+        let new = current
+            .checked_add(delta)
+            .ok_or_else(|| StoreError::QueryError("Increment would overflow".to_string()))?;
+        self.set(key, Value::from(new), None).await?;
+        Ok(new)
+    }
+
+    /// Updates the time-to-live of an existing key without changing its value.
+    ///
+    /// The default implementation rewrites the value with a `get` followed by a `set` and
+    /// is therefore not atomic; adapters override it with a backend operation that only
+    /// touches the expiry where one is available.
+    ///
+    /// # Arguments
+    /// - `key`: The key to update.
+    /// - `ttl`: The new time-to-live in seconds, counted from now, or `None` to make the
+    ///   key permanent.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the key exists and its expiry was updated.
+    /// - `Ok(false)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error updating the expiry.
+    async fn expire(&self, key: &str, ttl: Option<u64>) -> Result<bool, StoreError> {
+        match self.get(key).await? {
+            Some(value) => {
+                self.set(key, value, ttl).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Pushes back the expiry of an existing key by `by_secs` seconds, for sliding expiry.
+    ///
+    /// Unlike `expire`, which sets the TTL counted from now, this adds to the time the key
+    /// has left, and never creates a key that is gone. A key without expiry keeps none.
+    /// The new expiry saturates at the largest one the backend can hold instead of
+    /// overflowing. SQL stores update the expiry in a single `UPDATE`, Redis in a script
+    /// and the in-memory store under its lock. The default implementation reads the TTL
+    /// with `get_ttl` and writes it back with `expire`, so a concurrent write in between
+    /// can be overridden.
+    ///
+    /// # Arguments
+    /// - `key`: The key to extend.
+    /// - `by_secs`: The seconds added to the key's expiry.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the key exists, whether or not it has an expiry.
+    /// - `Ok(false)` if the key does not exist or has expired.
+    /// - `Err(StoreError)` if the expiry cannot be updated.
+    async fn extend_ttl(&self, key: &str, by_secs: u64) -> Result<bool, StoreError> {
+        match self.get_ttl(key).await? {
+            Some(ttl) => self.expire(key, Some(ttl.saturating_add(by_secs))).await,
+            None => self.contains_key(key).await,
+        }
+    }
+
+    /// Reads one field of the JSON object stored at `key`.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the object.
+    /// - `field`: The name of the field to read.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Value))` with the field's value.
+    /// - `Ok(None)` if the key or the field does not exist.
+    /// - `Err(StoreError::NotAnObject)` if the stored value is not a JSON object.
+    async fn get_field(&self, key: &str, field: &str) -> Result<Option<Value>, StoreError> {
+        match self.get(key).await? {
+            Some(Value::Object(mut object)) => Ok(object.remove(field)),
+            Some(_) => Err(StoreError::NotAnObject(key.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets one field of the JSON object stored at `key`, creating the object if the key
+    /// does not exist.
+    ///
+    /// An existing key keeps its expiry. The default implementation reads the object and
+    /// writes it back with `compare_and_swap`, retrying up to 32 times when another write
+    /// got in between. It is therefore atomic wherever `compare_and_swap` is, as in the
+    /// SQL and in-memory stores; elsewhere a concurrent write to another field of the same
+    /// key may be lost.
+    /// Redis overrides it with a `WATCH`/`MULTI` transaction.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the object.
+    /// - `field`: The name of the field to set.
+    /// - `value`: The field's new value.
+    /// - `ttl`: An optional time-to-live in seconds, only used when the key is created.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the field was written.
+    /// - `Err(StoreError::NotAnObject)` if the stored value is not a JSON object.
+    /// - `Err(StoreError::Transient)` if other writes kept changing the object.
+    async fn set_field(
+        &self,
+        key: &str,
+        field: &str,
+        value: Value,
+        ttl: Option<u64>,
+    ) -> Result<(), StoreError> {
+        update_object(self, key, ttl, |object| {
+            object.insert(field.to_string(), value.clone());
+            true
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Writes `value` at `path` inside the JSON document stored at `key`.
+    ///
+    /// The path starts with `$` and walks the document with `.field` and `[index]`
+    /// segments, as in `$.profile.emails[0]`. Its parent must already exist: a missing last
+    /// field is added to its object, while the last index must point at an existing array
+    /// element. The key keeps its expiry.
+    ///
+    /// Postgres and SQLite apply the change server-side in a single `UPDATE`, with
+    /// `jsonb_set` and `json_set`, so the document is never fetched and concurrent updates
+    /// of other paths are not lost. The default implementation reads the document, updates
+    /// it and writes it back with `compare_and_swap`, retrying up to 32 times when another
+    /// write got in between, and logs a warning the first time it runs.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the document.
+    /// - `path`: Where to write inside the document.
+    /// - `value`: The value to write.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the document was updated.
+    /// - `Ok(false)` if the key does not exist, or the path's parent is missing or is not
+    ///   an object or array as the last segment requires.
+    /// - `Err(StoreError::InvalidJsonPath)` if `path` cannot be parsed.
+    /// - `Err(StoreError::Transient)` if other writes kept changing the document.
+    async fn set_json_path(&self, key: &str, path: &str, value: Value) -> Result<bool, StoreError> {
+        let path = JsonPath::parse(path)?;
+        JSON_PATH_FALLBACK.call_once(|| {
+            log::warn!(
+                "This store cannot update JSON paths server-side, falling back to reading and rewriting whole documents"
+            );
+        });
+
+        for _ in 0..CAS_ATTEMPTS {
+            let Some(current) = self.get(key).await? else {
+                return Ok(false);
+            };
+            let mut updated = current.clone();
+            if !path.apply(&mut updated, value.clone()) {
+                return Ok(false);
+            }
+            let ttl = self.get_ttl(key).await?;
+            if self
+                .compare_and_swap(key, Some(current), updated, ttl)
+                .await?
+            {
+                return Ok(true);
+            }
+        }
+        Err(StoreError::Transient(format!(
+            "Gave up updating '{}' after {} conflicting writes",
+            key, CAS_ATTEMPTS
+        )))
+    }
+
+    /// Removes one field of the JSON object stored at `key`.
+    ///
+    /// The key itself is kept, even when its last field is removed, along with its expiry.
+    /// Atomicity is the same as for `set_field`.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the object.
+    /// - `field`: The name of the field to remove.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the field existed and was removed.
+    /// - `Ok(false)` if the key or the field does not exist.
+    /// - `Err(StoreError::NotAnObject)` if the stored value is not a JSON object.
+    /// - `Err(StoreError::Transient)` if other writes kept changing the object.
+    async fn remove_field(&self, key: &str, field: &str) -> Result<bool, StoreError> {
+        update_object(self, key, None, |object| object.remove(field).is_some()).await
+    }
+
     /// Removes a value associated with a given key from the store.
     ///
     /// # Arguments
@@ -47,20 +628,412 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if there is an error removing the value.
     async fn remove(&self, key: &str) -> Result<(), StoreError>;
 
+    /// Removes a key from the store and returns the value it held.
+    ///
+    /// The default implementation performs a `get` followed by a `remove` and is
+    /// therefore not atomic: two concurrent callers may both receive the value. Adapters
+    /// override it with an atomic backend operation where one is available.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key to remove.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Value))` with the removed value if the key existed.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError::DeserializationError)` if the value cannot be decoded. The SQL
+    ///   stores and the default implementation keep the key then, while Redis and MongoDB
+    ///   have already removed it.
+    /// - `Err(StoreError)` if there is an error removing the value.
+    async fn get_and_remove(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let value = self.get(key).await?;
+        if value.is_some() {
+            self.remove(key).await?;
+        }
+        Ok(value)
+    }
+
     /// Removes multiple values associated with the given keys from the store.
     ///
     /// # Arguments
     /// - `keys`: A slice of string slices representing the keys for the values to be removed.
     ///
     /// # Returns
-    /// - `Ok(())` if the values are successfully removed.
+    /// - `Ok(u64)` with the number of keys that existed and were removed.
     /// - `Err(StoreError)` if there is an error removing the values.
-    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError>;
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError>;
+
+    /// Applies a batch of sets and removes, in order, as a single unit.
+    ///
+    /// SQL adapters run the batch in one transaction and redis wraps it in `MULTI`/`EXEC`,
+    /// so either every operation is applied or, on failure, none is. The in-memory store
+    /// applies the batch under a single lock, which cannot fail part-way.
+    ///
+    /// The default implementation applies the operations one at a time and is **not**
+    /// atomic: if one fails, the operations before it stay applied and the rest are
+    /// skipped. Other clients may also observe the batch half-applied.
+    ///
+    /// # Arguments
+    /// - `ops`: The operations to apply. Later operations on the same key win.
+    ///
+    /// # Returns
+    /// - `Ok(())` once every operation has been applied.
+    /// - `Err(StoreError)` if the batch could not be applied.
+    async fn apply_batch(&self, ops: &[BatchOp]) -> Result<(), StoreError> {
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => self.set(key, value.clone(), *ttl).await?,
+                BatchOp::Remove { key } => self.remove(key).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the value stored at `from` to `to`, keeping its remaining TTL.
+    ///
+    /// An existing value at `to` is overwritten, as with redis `RENAME`. Renaming a key to
+    /// itself leaves it untouched. SQL adapters move the row in one transaction, redis
+    /// uses `RENAME` and the in-memory store moves the entry under its lock.
+    ///
+    /// The default implementation reads the value and TTL, then writes `to` and removes
+    /// `from` with `apply_batch`. The write is as atomic as the store's `apply_batch`, but
+    /// a concurrent write to `from` between the read and the batch is lost.
+    ///
+    /// # Arguments
+    /// - `from`: The key to move.
+    /// - `to`: The key to move it to.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if `from` existed and was moved.
+    /// - `Ok(false)` if `from` does not exist; `to` is left untouched.
+    /// - `Err(StoreError)` if there is an error moving the value.
+    async fn rename(&self, from: &str, to: &str) -> Result<bool, StoreError> {
+        let Some(value) = self.get(from).await? else {
+            return Ok(false);
+        };
+        if from == to {
+            return Ok(true);
+        }
+
+        let ttl = self.get_ttl(from).await?;
+        self.apply_batch(&[
+            BatchOp::Set {
+                key: to.to_string(),
+                value,
+                ttl,
+            },
+            BatchOp::remove(from),
+        ])
+        .await?;
+        Ok(true)
+    }
+
+    /// Lists the keys currently held by the store.
+    ///
+    /// # Arguments
+    /// - `prefix`: When set, only keys starting with this prefix are returned.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<String>)` with the matching keys, excluding expired ones. The order is
+    ///   backend-specific.
+    /// - `Err(StoreError)` if there is an error listing the keys.
+    ///
+    /// The default implementation returns `StoreError::Unsupported`, so stores that
+    /// cannot list their keys still build; `iter`, `range` and the other defaults that
+    /// list keys then fail the same way.
+    async fn keys(&self, _prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        Err(StoreError::Unsupported("listing keys".to_string()))
+    }
+
+    /// Fetches the live entries whose keys fall in `[start, end)`, in key order.
+    ///
+    /// Keys are compared as strings, so numeric parts must be zero-padded to sort
+    /// correctly, as in `event:0000001700`. SQL stores run a single
+    /// `WHERE key >= start AND key < end ORDER BY key` query, with bounds and order
+    /// following the key column's collation. Every other store, Redis included, uses the
+    /// default implementation, which lists the keys sharing the bounds' common prefix with
+    /// `keys`, sorts them and fetches the matches with `get_many`.
+    ///
+    /// # Arguments
+    /// - `start`: The inclusive lower bound.
+    /// - `end`: The exclusive upper bound.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<(String, Value)>)` with the matching entries sorted by key, empty when
+    ///   `start >= end`.
+    /// - `Err(StoreError)` if there is an error reading the entries.
+    async fn range(&self, start: &str, end: &str) -> Result<Vec<(String, Value)>, StoreError> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let common = start
+            .char_indices()
+            .zip(end.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(start.len(), |((i, _), _)| i);
+        let mut keys: Vec<String> = self
+            .keys(Some(&start[..common]))
+            .await?
+            .into_iter()
+            .filter(|key| key.as_str() >= start && key.as_str() < end)
+            .collect();
+        keys.sort();
+
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let values = self.get_many(&key_refs).await?;
+        Ok(keys
+            .into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .collect())
+    }
+
+    /// Streams every live entry of the store.
+    ///
+    /// Entries are fetched in batches as the stream is polled, so the whole store is never
+    /// buffered in memory. The default implementation lists all keys with `keys` and then
+    /// fetches their values in batches with `get_many`; adapters override it to page
+    /// through the backend directly. Entries written or removed while the stream is
+    /// consumed may or may not be observed.
+    ///
+    /// # Returns
+    /// A stream yielding `Ok((key, value))` per entry, or `Err(StoreError)` if a batch
+    /// cannot be fetched. The order is backend-specific.
+    fn iter(&self) -> EntryStream<'_> {
+        stream_keys(self, None)
+    }
+
+    /// Streams every live entry whose key starts with `prefix`.
+    ///
+    /// Like `iter`, entries are fetched in batches as the stream is polled, so a large
+    /// prefix can be walked page by page without listing all of its keys up front. The
+    /// default implementation lists the matching keys with `keys` and fetches their
+    /// values in batches with `get_many`; SQL stores page through a `LIKE` query in key
+    /// order and Redis runs a `SCAN MATCH` loop. Expired entries are skipped.
+    ///
+    /// # Arguments
+    /// - `prefix`: Only keys starting with this prefix are returned.
+    ///
+    /// # Returns
+    /// A stream yielding `Ok((key, value))` per matching entry, or `Err(StoreError)` if a
+    /// batch cannot be fetched. The order is backend-specific.
+    fn scan_prefix(&self, prefix: &str) -> EntryStream<'_> {
+        stream_keys(self, Some(prefix.to_string()))
+    }
+
+    /// Streams every live entry of the store together with its expiry, for a backup or a
+    /// migration to another backend.
+    ///
+    /// Expiry is reported as an absolute unix timestamp rather than as the TTL left, so
+    /// an export that is stored and imported later does not extend the life of its
+    /// entries. The default implementation reads each entry from `iter` and its TTL with
+    /// `get_ttl`; SQL and in-memory stores read both in the same batch.
+    ///
+    /// # Returns
+    /// A stream yielding `Ok((key, value, expires_at))` per entry, or `Err(StoreError)` if
+    /// a batch cannot be fetched. The order is backend-specific.
+    fn export(&self) -> ExportStream<'_> {
+        Box::pin(self.iter().try_filter_map(move |(key, value)| async move {
+            let ttl = self.get_ttl(&key).await?;
+            // `None` also stands for a key that expired after `iter` returned it.
+            if ttl.is_none() && !self.contains_key(&key).await? {
+                return Ok(None);
+            }
+            Ok(Some((key, value, expires_at(ttl))))
+        }))
+    }
+
+    /// Writes the entries of an export into the store, in batches of `set_many`.
+    ///
+    /// Each absolute expiry is turned back into the TTL left at the time of the write.
+    /// Entries that have expired in the meantime are skipped. Existing keys are
+    /// overwritten; other keys of the store are kept.
+    ///
+    /// # Arguments
+    /// - `entries`: The entries to write, typically from `export` on another store.
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with the number of entries written.
+    /// - `Err(StoreError)` if the stream fails or a batch cannot be written. Batches
+    ///   written before the error are kept.
+    async fn import(&self, mut entries: ExportStream<'_>) -> Result<u64, StoreError> {
+        let mut batch: Vec<ExportEntry> = Vec::with_capacity(ITER_BATCH_SIZE);
+        let mut imported = 0;
+        loop {
+            let entry = entries.try_next().await?;
+            let done = entry.is_none();
+            if let Some((key, value, expires_at)) = entry {
+                let ttl = match expires_at {
+                    Some(expires_at) => match remaining_ttl(expires_at) {
+                        Some(ttl) => Some(ttl),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                batch.push((key, value, ttl));
+            }
+
+            if batch.len() == ITER_BATCH_SIZE || (done && !batch.is_empty()) {
+                let entries: Vec<(&str, Value, Option<u64>)> = batch
+                    .iter_mut()
+                    .map(|(key, value, ttl)| (key.as_str(), value.take(), *ttl))
+                    .collect();
+                self.set_many(&entries).await?;
+                imported += entries.len() as u64;
+                batch.clear();
+            }
+            if done {
+                return Ok(imported);
+            }
+        }
+    }
+
+    /// Returns the number of entries held by the store.
+    ///
+    /// Entries whose TTL has passed are not counted, even if the backend has not removed
+    /// them yet. The default implementation counts the result of `keys(None)`.
+    ///
+    /// # Returns
+    /// - `Ok(usize)` with the number of live entries.
+    /// - `Err(StoreError)` if there is an error counting the entries.
+    async fn len(&self) -> Result<usize, StoreError> {
+        Ok(self.keys(None).await?.len())
+    }
+
+    /// Returns `true` if the store holds no live entries.
+    ///
+    /// # Returns
+    /// - `Ok(bool)` indicating whether the store is empty.
+    /// - `Err(StoreError)` if there is an error counting the entries.
+    async fn is_empty(&self) -> Result<bool, StoreError> {
+        Ok(self.len().await? == 0)
+    }
 
     /// Clears all values from the store.
     ///
+    /// SQL stores delete every row of their table with `DELETE`, never `TRUNCATE`, which
+    /// would lock the whole table exclusively. A `NamespacedStore` only clears its own keys
+    /// through `clear_prefix` on the store it wraps.
+    ///
     /// # Returns
     /// - `Ok(())` if the store is successfully cleared.
     /// - `Err(StoreError)` if there is an error clearing the store.
     async fn clear(&self) -> Result<(), StoreError>;
+
+    /// Removes every key starting with `prefix`, leaving the rest of the store untouched.
+    ///
+    /// The default implementation lists the matching keys with `keys` and removes them
+    /// with `remove_many`; adapters override it with a single backend-side delete where
+    /// one is available, such as `DELETE ... WHERE key LIKE 'prefix%'` in the SQL stores,
+    /// with `%` and `_` in the prefix escaped.
+    ///
+    /// # Arguments
+    /// - `prefix`: The prefix of the keys to remove.
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with the number of live keys that were removed.
+    /// - `Err(StoreError)` if there is an error removing the keys.
+    async fn clear_prefix(&self, prefix: &str) -> Result<u64, StoreError> {
+        let keys = self.keys(Some(prefix)).await?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.remove_many(&keys).await
+    }
+
+    /// Reclaims space left behind by deleted entries.
+    ///
+    /// SQLite runs `VACUUM`, which rebuilds the whole database file. It needs up to twice
+    /// the size of the database in free disk space, and other connections cannot write
+    /// until it finishes, which can take a while for large databases. Run it during a
+    /// maintenance window, e.g. after a bulk delete. Backends without an equivalent
+    /// operation use the default implementation, which does nothing.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the store has been compacted, or immediately for a no-op.
+    /// - `Err(StoreError)` if the compaction failed.
+    async fn optimize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    /// Flushes pending writes and releases the resources held by the store.
+    ///
+    /// Background sweep and flush tasks are stopped and SQL connection pools are closed,
+    /// so other clones of a pool stop working as well. Calling `close` again is a no-op,
+    /// but any other operation on a closed store may fail. Stores without resources to
+    /// release use the default implementation, which does nothing.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the store has been closed.
+    /// - `Err(StoreError)` if pending writes could not be flushed.
+    async fn close(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+}
+
+/// Applies `update` to the JSON object stored at `key` and writes it back with
+/// `compare_and_swap`, starting over whenever the value changed in between.
+///
+/// A missing key starts from an empty object and is written with `ttl`; an existing one
+/// keeps its expiry. Nothing is written when `update` returns `false`. Gives up with
+/// `StoreError::Transient` after `CAS_ATTEMPTS` lost swaps, so a swap that can never
+/// succeed does not spin forever.
+async fn update_object<S, F>(
+    store: &S,
+    key: &str,
+    ttl: Option<u64>,
+    mut update: F,
+) -> Result<bool, StoreError>
+where
+    S: Store + ?Sized,
+    F: FnMut(&mut Map<String, Value>) -> bool + Send,
+{
+    for _ in 0..CAS_ATTEMPTS {
+        let current = store.get(key).await?;
+        let (mut object, ttl) = match &current {
+            Some(Value::Object(object)) => (object.clone(), store.get_ttl(key).await?),
+            Some(_) => return Err(StoreError::NotAnObject(key.to_string())),
+            None => (Map::new(), ttl),
+        };
+        if !update(&mut object) {
+            return Ok(false);
+        }
+        if store
+            .compare_and_swap(key, current, Value::Object(object), ttl)
+            .await?
+        {
+            return Ok(true);
+        }
+    }
+    Err(StoreError::Transient(format!(
+        "Gave up updating '{}' after {} conflicting writes",
+        key, CAS_ATTEMPTS
+    )))
+}
+
+/// Streams the live entries whose keys `store.keys(prefix)` lists, fetching their values
+/// in batches with `get_many`. Backs the default `iter` and `scan_prefix`.
+fn stream_keys<S: Store + ?Sized>(store: &S, prefix: Option<String>) -> EntryStream<'_> {
+    let batches = stream::try_unfold(None, move |keys: Option<std::vec::IntoIter<String>>| {
+        let prefix = prefix.clone();
+        async move {
+            let mut keys = match keys {
+                Some(keys) => keys,
+                None => store.keys(prefix.as_deref()).await?.into_iter(),
+            };
+            let batch: Vec<String> = keys.by_ref().take(ITER_BATCH_SIZE).collect();
+            if batch.is_empty() {
+                return Ok(None);
+            }
+
+            let batch_refs: Vec<&str> = batch.iter().map(String::as_str).collect();
+            let values = store.get_many(&batch_refs).await?;
+            // Keys removed since they were listed come back as `None` and are skipped.
+            let entries: Vec<Result<(String, Value), StoreError>> = batch
+                .into_iter()
+                .zip(values)
+                .filter_map(|(key, value)| value.map(|value| Ok((key, value))))
+                .collect();
+            Ok::<_, StoreError>(Some((stream::iter(entries), Some(keys))))
+        }
+    });
+    Box::pin(batches.try_flatten())
 }
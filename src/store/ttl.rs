@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+/// How long an entry lives before it expires.
+///
+/// Stores take TTLs as whole seconds unless stated otherwise; `Ttl` spells the unit out
+/// at the call site. A bare `u64` converts into seconds, so existing callers keep their
+/// meaning. Most backends record expiry with second precision and round a millisecond
+/// TTL up to the next whole second, so an entry never expires early. Redis and the
+/// in-memory store keep millisecond precision.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, Ttl};
+/// # async {
+/// let keyv = Keyv::default();
+/// keyv.set_with_ttl("session", "data", Ttl::seconds(3600)).await.unwrap();
+/// keyv.set_with_ttl("lock", "owner", Ttl::millis(250)).await.unwrap();
+/// keyv.set_with_ttl("legacy", "data", 60).await.unwrap(); // Seconds
+/// # };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ttl(Duration);
+
+impl Ttl {
+    /// A TTL of `secs` seconds.
+    pub const fn seconds(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+
+    /// A TTL of `millis` milliseconds.
+    pub const fn millis(millis: u64) -> Self {
+        Self(Duration::from_millis(millis))
+    }
+
+    /// Returns the TTL as a `Duration`.
+    pub const fn as_duration(&self) -> Duration {
+        self.0
+    }
+
+    /// Returns the TTL in whole seconds, rounding any fraction up.
+    pub fn as_secs_ceil(&self) -> u64 {
+        self.0
+            .as_secs()
+            .saturating_add(u64::from(self.0.subsec_nanos() > 0))
+    }
+
+    /// Returns the TTL in whole milliseconds, rounding any fraction up.
+    pub fn as_millis_ceil(&self) -> u64 {
+        let millis =
+            self.0.as_millis() + u128::from(!self.0.subsec_nanos().is_multiple_of(1_000_000));
+        u64::try_from(millis).unwrap_or(u64::MAX)
+    }
+}
+
+impl From<u64> for Ttl {
+    /// Interprets `secs` as seconds, the unit of the plain `u64` TTLs used elsewhere.
+    fn from(secs: u64) -> Self {
+        Self::seconds(secs)
+    }
+}
+
+impl From<Duration> for Ttl {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
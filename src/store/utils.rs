@@ -0,0 +1,253 @@
+use std::{fmt::Display, future::Future, path::Path, time::Duration};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::StoreError;
+
+/// Number of entries fetched per round-trip when a store is streamed with `Store::iter`.
+pub(crate) const ITER_BATCH_SIZE: usize = 500;
+
+/// Delay before the first connection retry when a builder's `retry_backoff` is not set.
+pub(crate) const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Column names of the table backing a SQL store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Columns {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) ttl: String,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            ttl: "ttl".to_string(),
+        }
+    }
+}
+
+/// Wraps `name` in `quote` characters, doubling any `quote` inside it, so that it can
+/// only ever be read as a single identifier.
+pub(crate) fn quote_identifier(name: &str, quote: char) -> String {
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push(quote);
+    for c in name.chars() {
+        if c == quote {
+            quoted.push(quote);
+        }
+        quoted.push(c);
+    }
+    quoted.push(quote);
+    quoted
+}
+
+/// Expands the `{table}`, `{key}`, `{value}` and `{ttl}` placeholders of a SQL template.
+///
+/// Column names are quoted with `quote`; the table name is inserted as-is. Substitution
+/// happens in a single pass, so placeholders inside the inserted names are not expanded.
+pub(crate) fn expand_sql(template: &str, table: &str, columns: &Columns, quote: char) -> String {
+    let mut sql = String::with_capacity(template.len() + table.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        sql.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find('}').map_or(rest.len(), |end| end + 1);
+        match &rest[..end] {
+            "{table}" => sql.push_str(table),
+            "{key}" => sql.push_str(&quote_identifier(&columns.key, quote)),
+            "{value}" => sql.push_str(&quote_identifier(&columns.value, quote)),
+            "{ttl}" => sql.push_str(&quote_identifier(&columns.ttl, quote)),
+            other => sql.push_str(other),
+        }
+        rest = &rest[end..];
+    }
+    sql.push_str(rest);
+    sql
+}
+
+/// Builds a `LIKE` pattern matching every key that starts with `prefix`.
+///
+/// `%`, `_` and the escape character itself are escaped with a backslash, so the
+/// generated SQL must declare `ESCAPE '\'` (written `ESCAPE '\\'` in MySQL).
+pub(crate) fn like_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Builds a SQLite `GLOB` pattern matching every key that starts with `prefix`.
+///
+/// Unlike `LIKE`, which folds ASCII case in SQLite, `GLOB` compares bytes exactly, so
+/// `"acme:"` does not match `"ACME:"`. `*`, `?` and `[` are wrapped in brackets to match
+/// literally.
+pub(crate) fn glob_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[') {
+            pattern.push('[');
+            pattern.push(c);
+            pattern.push(']');
+        } else {
+            pattern.push(c);
+        }
+    }
+    pattern.push('*');
+    pattern
+}
+
+/// Converts `value` into JSON, reporting values JSON cannot hold, such as integers beyond
+/// the 64-bit range, as `StoreError::SerializationError` instead of panicking.
+pub(crate) fn to_value<T: Serialize>(key: &str, value: T) -> Result<Value, StoreError> {
+    serde_json::to_value(value).map_err(|e| StoreError::SerializationError {
+        key: Some(key.to_string()),
+        source: e,
+    })
+}
+
+/// Maps a URI that cannot be parsed, or a connection that cannot be opened, to
+/// `StoreError::ConnectionError`, keeping the cause, such as an invalid port or an unknown
+/// host, after `message`.
+pub(crate) fn connection_error<E: Display>(e: E, message: &str) -> StoreError {
+    StoreError::ConnectionError(format!("{}: {}", message, e))
+}
+
+/// Returns the current unix time in whole seconds.
+pub(crate) fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Converts a relative TTL in seconds into an absolute unix expiry timestamp.
+pub(crate) fn expires_at(ttl: Option<u64>) -> Option<u64> {
+    ttl.map(|ttl| now_secs().saturating_add(ttl))
+}
+
+/// Converts a timestamp or a number of seconds for a signed `BIGINT` column, clamping
+/// values past `i64::MAX` instead of letting them wrap negative.
+pub(crate) fn clamp_i64(secs: u64) -> i64 {
+    i64::try_from(secs).unwrap_or(i64::MAX)
+}
+
+/// Converts an absolute unix expiry timestamp back into the seconds left before it.
+///
+/// Returns `None` once the timestamp has been reached.
+pub(crate) fn remaining_ttl(expires_at: u64) -> Option<u64> {
+    expires_at.checked_sub(now_secs()).filter(|&ttl| ttl > 0)
+}
+
+/// Tells whether a sqlx error may go away when the operation is retried.
+///
+/// I/O errors, such as a reset connection, and pool timeouts are always transient. A
+/// database error is transient when `is_transient_code` accepts it, since the codes for
+/// deadlocks and lock timeouts differ between backends.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn is_transient_sqlx(
+    e: &sqlx::Error,
+    is_transient_code: impl Fn(&dyn sqlx::error::DatabaseError) -> bool,
+) -> bool {
+    match e {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(e) => is_transient_code(e.as_ref()),
+        _ => false,
+    }
+}
+
+/// Checks that a table a store did not create itself has the columns the store uses.
+///
+/// `found` lists the columns of the table, and is empty when the table does not exist.
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn check_table(
+    table: &str,
+    found: &[String],
+    columns: &Columns,
+    timestamps: bool,
+) -> Result<(), StoreError> {
+    if found.is_empty() {
+        return Err(StoreError::QueryError(format!(
+            "Table {} does not exist and auto_create is disabled, create it before initializing the store",
+            table
+        )));
+    }
+
+    let mut required = vec![
+        columns.key.as_str(),
+        columns.value.as_str(),
+        columns.ttl.as_str(),
+    ];
+    if timestamps {
+        required.extend(["created_at", "updated_at"]);
+    }
+    let missing: Vec<&str> = required
+        .into_iter()
+        .filter(|column| !found.iter().any(|c| c == column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(StoreError::QueryError(format!(
+            "Table {} is missing the columns {} and auto_create is disabled",
+            table,
+            missing.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that the CA certificate configured for a TLS connection exists.
+///
+/// Without this, a mistyped path only surfaces as a generic TLS failure once the pool
+/// tries to connect.
+pub(crate) fn check_root_cert(path: &Path) -> Result<(), StoreError> {
+    if path.is_file() {
+        Ok(())
+    } else {
+        Err(StoreError::ConnectionError(format!(
+            "SSL root certificate '{}' does not exist",
+            path.display()
+        )))
+    }
+}
+
+/// Runs `connect` until it succeeds, retrying up to `retries` times.
+///
+/// The first retry waits `backoff` and each further retry doubles the delay. The error of
+/// the last attempt is returned once the retries are exhausted.
+pub(crate) async fn connect_with_retry<T, E, F, Fut>(
+    retries: u32,
+    backoff: Duration,
+    mut connect: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let mut delay = backoff;
+    for attempt in 1..=retries {
+        match connect().await {
+            Ok(connection) => return Ok(connection),
+            Err(e) => {
+                log::warn!(
+                    "Connection attempt {} of {} failed: {}; retrying in {:?}",
+                    attempt,
+                    retries + 1,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+            }
+        }
+    }
+    connect().await
+}
@@ -0,0 +1,75 @@
+use std::{sync::Arc, time::Duration};
+
+use keyv::{
+    adapter::{buffered::BufferedStore, inmemory::InMemoryStore},
+    Store,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_buffered_flushes_when_full() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = BufferedStore::new(inner.clone()).max_entries(3);
+
+    store.set("a", json!(1), None).await.unwrap();
+    store.set("a", json!(2), None).await.unwrap();
+    store.set("b", json!(3), None).await.unwrap();
+    assert_eq!(inner.get("a").await.unwrap(), None);
+    assert_eq!(store.get("a").await.unwrap(), Some(json!(2)));
+    assert_eq!(
+        store.get_many(&["a", "missing"]).await.unwrap(),
+        vec![Some(json!(2)), None]
+    );
+
+    store.set("c", json!(4), None).await.unwrap();
+    assert_eq!(inner.get("a").await.unwrap(), Some(json!(2)));
+    assert_eq!(inner.len().await.unwrap(), 3);
+
+    store.set("d", json!(5), None).await.unwrap();
+    store.remove("d").await.unwrap();
+    store.flush().await.unwrap();
+    assert_eq!(inner.get("d").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_buffered_flush_before_other_operations() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = BufferedStore::new(inner.clone());
+
+    store.set("counter", json!(1), None).await.unwrap();
+    assert_eq!(store.increment("counter", 2).await.unwrap(), 3);
+    assert_eq!(inner.get("counter").await.unwrap(), Some(json!(3)));
+
+    store.set("other", json!(1), None).await.unwrap();
+    let mut keys = store.keys(None).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["counter", "other"]);
+}
+
+#[tokio::test]
+async fn test_buffered_flush_interval_and_drop() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = BufferedStore::new(inner.clone()).flush_interval(Duration::from_millis(50));
+
+    store.set("ticked", json!(1), None).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(inner.get("ticked").await.unwrap(), Some(json!(1)));
+
+    store.set("dropped", json!(2), None).await.unwrap();
+    drop(store);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(inner.get("dropped").await.unwrap(), Some(json!(2)));
+}
+
+#[tokio::test]
+async fn test_buffered_close_flushes_and_is_idempotent() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = BufferedStore::new(inner.clone()).flush_interval(Duration::from_secs(60));
+
+    store.set("a", json!(1), None).await.unwrap();
+    assert_eq!(inner.get("a").await.unwrap(), None);
+
+    store.close().await.unwrap();
+    assert_eq!(inner.get("a").await.unwrap(), Some(json!(1)));
+    store.close().await.unwrap();
+}
@@ -0,0 +1,92 @@
+#[cfg(feature = "compression")]
+use std::sync::Arc;
+
+#[cfg(feature = "compression")]
+use keyv::{
+    adapter::{
+        compressed::{CompressedStore, Compression},
+        inmemory::InMemoryStore,
+    },
+    Keyv, Store,
+};
+#[cfg(feature = "compression")]
+use serde_json::json;
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compressed_round_trip() {
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let inner = Arc::new(InMemoryStore::new());
+        let store = CompressedStore::new(inner.clone(), compression).threshold(64);
+
+        let large = json!({ "text": "keyv ".repeat(100) });
+        store.set("large", large.clone(), None).await.unwrap();
+        store.set("small", json!("tiny"), None).await.unwrap();
+
+        let stored = inner.get("large").await.unwrap().unwrap();
+        assert!(stored.as_str().unwrap().len() < large.to_string().len());
+        assert_eq!(inner.get("small").await.unwrap(), Some(json!("tiny")));
+
+        assert_eq!(store.get("large").await.unwrap(), Some(large));
+        assert_eq!(store.get("small").await.unwrap(), Some(json!("tiny")));
+    }
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compressed_reads_existing_data() {
+    let inner = Arc::new(InMemoryStore::new());
+    let plain = json!(["existing", "value"]);
+    inner.set("old", plain.clone(), None).await.unwrap();
+
+    // A stored string that looks like the header must survive a round trip.
+    let tricky = json!("\u{1b}keyv:zstd:not-base64");
+
+    let keyv = Keyv::try_new(InMemoryStore::new())
+        .await
+        .unwrap()
+        .with_compression(Compression::Gzip);
+    keyv.set("tricky", tricky.clone()).await.unwrap();
+    assert_eq!(keyv.get("tricky").await.unwrap(), Some(tricky));
+
+    let store = CompressedStore::new(inner, Compression::Zstd);
+    assert_eq!(store.get("old").await.unwrap(), Some(plain));
+
+    store.set("counter", json!(1), None).await.unwrap();
+    assert_eq!(store.increment("counter", 2).await.unwrap(), 3);
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_max_value_bytes_counts_compressed_size() {
+    let keyv = Keyv::default()
+        .with_max_value_bytes(256)
+        .with_compression(Compression::Gzip);
+    // Highly repetitive, so it compresses far below the limit.
+    keyv.set("document", "a".repeat(10_000)).await.unwrap();
+    assert_eq!(
+        keyv.get("document").await.unwrap().unwrap(),
+        "a".repeat(10_000)
+    );
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compressed_max_decoded_size() {
+    for compression in [Compression::Gzip, Compression::Zstd] {
+        let inner = Arc::new(InMemoryStore::new());
+        let writer = CompressedStore::new(inner.clone(), compression);
+        // Compresses to a few dozen bytes but decompresses to 10 002 bytes of JSON.
+        let bomb = json!("a".repeat(10_000));
+        writer.set("bomb", bomb.clone(), None).await.unwrap();
+
+        let reader = CompressedStore::new(inner.clone(), compression).max_decoded_size(1024);
+        assert!(matches!(
+            reader.get("bomb").await,
+            Err(keyv::StoreError::CodecError { .. })
+        ));
+
+        let reader = CompressedStore::new(inner, compression).max_decoded_size(10_002);
+        assert_eq!(reader.get("bomb").await.unwrap(), Some(bomb));
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(feature = "encryption")]
+use std::sync::Arc;
+
+#[cfg(feature = "encryption")]
+use keyv::{
+    adapter::{encrypted::EncryptedStore, inmemory::InMemoryStore},
+    Keyv, KeyvError, Store, StoreError,
+};
+#[cfg(feature = "encryption")]
+use serde_json::json;
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn test_encrypted_round_trip() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = EncryptedStore::new(inner.clone(), [1u8; 32]);
+
+    store.set("token", json!("secret"), None).await.unwrap();
+    store.set("other", json!("secret"), None).await.unwrap();
+
+    let stored = inner.get("token").await.unwrap().unwrap();
+    assert!(!stored.as_str().unwrap().contains("secret"));
+    // Fresh nonces make equal values encrypt differently.
+    assert_ne!(Some(stored), inner.get("other").await.unwrap());
+
+    assert_eq!(store.get("token").await.unwrap(), Some(json!("secret")));
+    assert_eq!(store.increment("counter", 3).await.unwrap(), 3);
+    assert_eq!(store.get("counter").await.unwrap(), Some(json!(3)));
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn test_encrypted_wrong_key() {
+    let inner = Arc::new(InMemoryStore::new());
+    EncryptedStore::new(inner.clone(), [1u8; 32])
+        .set("token", json!("secret"), None)
+        .await
+        .unwrap();
+    inner.set("plain", json!("value"), None).await.unwrap();
+
+    let keyv = Keyv::try_new(EncryptedStore::new(inner, [2u8; 32]))
+        .await
+        .unwrap();
+    match keyv.get("token").await {
+        Err(KeyvError::StoreError(StoreError::DecryptionError(key))) => assert_eq!(key, "token"),
+        other => panic!("Expected a decryption error, got {:?}", other),
+    }
+    assert!(matches!(
+        keyv.get("plain").await,
+        Err(KeyvError::StoreError(StoreError::DecryptionError(_)))
+    ));
+
+    let keyv = Keyv::default().with_encryption([3u8; 32]);
+    keyv.set("key", vec![1, 2, 3]).await.unwrap();
+    assert_eq!(
+        keyv.get_as::<Vec<i32>>("key").await.unwrap(),
+        Some(vec![1, 2, 3])
+    );
+}
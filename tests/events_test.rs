@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use futures::stream;
+use keyv::{
+    adapter::{
+        events::{EventStore, StoreEvent},
+        inmemory::InMemoryStore,
+    },
+    BatchOp, Keyv, Store,
+};
+use serde_json::json;
+use tokio::sync::broadcast::error::TryRecvError;
+
+fn set(key: &str) -> StoreEvent {
+    StoreEvent::Set {
+        key: key.to_string(),
+    }
+}
+
+fn removed(key: &str) -> StoreEvent {
+    StoreEvent::Removed {
+        key: key.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn store_reports_successful_changes() {
+    let store = EventStore::new(Arc::new(InMemoryStore::new()));
+    store.set("before", 0.into(), None).await.unwrap();
+
+    let mut events = store.subscribe();
+    store.set("a", 1.into(), None).await.unwrap();
+    store
+        .set_many(&[("b", 2.into(), None), ("c", 3.into(), None)])
+        .await
+        .unwrap();
+    assert!(!store.set_nx("a", 4.into(), None).await.unwrap());
+    store.increment("a", 1).await.unwrap();
+    store
+        .apply_batch(&[BatchOp::set("d", 4).unwrap(), BatchOp::remove("b")])
+        .await
+        .unwrap();
+    assert!(store.rename("c", "e").await.unwrap());
+    assert_eq!(store.get_and_remove("missing").await.unwrap(), None);
+    store.remove("a").await.unwrap();
+    store.clear_prefix("d").await.unwrap();
+    store.clear().await.unwrap();
+
+    let expected = [
+        set("a"),
+        set("b"),
+        set("c"),
+        set("a"),
+        set("d"),
+        removed("b"),
+        removed("c"),
+        set("e"),
+        removed("a"),
+        StoreEvent::ClearedPrefix {
+            prefix: "d".to_string(),
+        },
+        StoreEvent::Cleared,
+    ];
+    for event in expected {
+        assert_eq!(events.try_recv().unwrap(), event);
+    }
+    assert_eq!(events.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[tokio::test]
+async fn store_reports_field_group_and_import_changes() {
+    let store = EventStore::new(Arc::new(InMemoryStore::new()));
+    let mut events = store.subscribe();
+
+    store
+        .set_field("user", "name", json!("alice"), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get_field("user", "name").await.unwrap(),
+        Some(json!("alice"))
+    );
+    assert!(store.remove_field("user", "name").await.unwrap());
+    assert!(!store.remove_field("user", "name").await.unwrap());
+    store
+        .set_group(&[("g1", json!(1)), ("g2", json!(2))], None)
+        .await
+        .unwrap();
+
+    let entries = stream::iter(vec![
+        Ok(("old".to_string(), json!(1), Some(1))),
+        Ok(("new".to_string(), json!(2), None)),
+    ]);
+    assert_eq!(store.import(Box::pin(entries)).await.unwrap(), 1);
+
+    for event in [set("user"), set("user"), set("g1"), set("g2"), set("new")] {
+        assert_eq!(events.try_recv().unwrap(), event);
+    }
+    assert_eq!(events.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[tokio::test]
+async fn keyv_subscribers_see_namespaced_keys() {
+    let keyv = Keyv::default();
+    let sessions = keyv.namespace("session");
+    let mut events = keyv.subscribe();
+
+    sessions.set("42", "alice").await.unwrap();
+    sessions.clear().await.unwrap();
+
+    assert_eq!(events.recv().await.unwrap(), set("session:42"));
+    assert_eq!(
+        events.recv().await.unwrap(),
+        StoreEvent::ClearedPrefix {
+            prefix: "session:".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn published_events_reach_every_view() {
+    let keyv = Keyv::default();
+    let sessions = keyv.namespace("session");
+    let mut events = sessions.subscribe();
+
+    keyv.publish(removed("session:1"));
+    sessions.set("2", "bob").await.unwrap();
+    assert_eq!(events.recv().await.unwrap(), removed("session:1"));
+    assert_eq!(events.recv().await.unwrap(), set("session:2"));
+    assert!(matches!(events.try_recv(), Err(TryRecvError::Empty)));
+}
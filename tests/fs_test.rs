@@ -0,0 +1,117 @@
+#[cfg(feature = "fs")]
+use keyv::{adapter::fs::FsStoreBuilder, Keyv};
+
+#[cfg(feature = "fs")]
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("keyv-fs-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn test_keyv_fs() {
+    let dir = test_dir("basic");
+    let store = FsStoreBuilder::new().path(&dir).build().await.unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("number", 42).await.unwrap();
+    keyv.set("number", 10).await.unwrap();
+    keyv.set_many(&[("user:1", "alice"), ("user:2", "bob")])
+        .await
+        .unwrap();
+    assert_eq!(keyv.get_as::<i64>("number").await.unwrap(), Some(10));
+    assert_eq!(keyv.increment("number", 5).await.unwrap(), 15);
+    assert!(!keyv.set_if_absent("user:1", "carol", None).await.unwrap());
+
+    let mut keys = keyv.keys(Some("user:")).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["user:1".to_string(), "user:2".to_string()]);
+    assert_eq!(keyv.len().await.unwrap(), 3);
+
+    assert_eq!(keyv.remove_many(&["user:1", "user:3"]).await.unwrap(), 1);
+    assert_eq!(keyv.take("user:2").await.unwrap(), Some("bob".into()));
+
+    // Entries survive the store being reopened.
+    drop(keyv);
+    let store = FsStoreBuilder::new().path(&dir).build().await.unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert_eq!(keyv.get_as::<i64>("number").await.unwrap(), Some(15));
+
+    keyv.clear().await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn test_keyv_fs_ttl() {
+    let dir = test_dir("ttl");
+    let store = FsStoreBuilder::new().path(&dir).build().await.unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_with_ttl("short", "lived", 1).await.unwrap();
+    keyv.set_with_ttl("unread", "lived", 1).await.unwrap();
+    keyv.set("forever", "value").await.unwrap();
+    assert!(keyv.ttl("short").await.unwrap().is_some_and(|ttl| ttl <= 1));
+    assert_eq!(keyv.ttl("forever").await.unwrap(), None);
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+
+    assert!(keyv.get("short").await.unwrap().is_none());
+    assert_eq!(keyv.keys(None).await.unwrap(), vec!["forever".to_string()]);
+    // `get` deleted its expired file; the unread one is left for the sweep.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    let store = FsStoreBuilder::new().path(&dir).build().await.unwrap();
+    assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn test_keyv_fs_unsafe_keys() {
+    let dir = test_dir("keys");
+    let store = FsStoreBuilder::new()
+        .path(dir.join("store"))
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    let keys = ["../escape", "/etc/passwd", "a/b\\c", "", "naïve:ключ", "."];
+    for key in keys {
+        keyv.set(key, key).await.unwrap();
+    }
+    for key in keys {
+        assert_eq!(
+            keyv.get_as::<String>(key).await.unwrap().as_deref(),
+            Some(key)
+        );
+    }
+
+    // Every entry lives directly in the store directory, and nothing escaped it.
+    assert_eq!(
+        std::fs::read_dir(dir.join("store")).unwrap().count(),
+        keys.len()
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn test_keyv_fs_large_integers() {
+    let dir = test_dir("large_integers");
+    let store = FsStoreBuilder::new().path(&dir).build().await.unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("max", u64::MAX).await.unwrap();
+    keyv.set("min", i64::MIN).await.unwrap();
+    assert_eq!(keyv.get_as::<u64>("max").await.unwrap(), Some(u64::MAX));
+    assert_eq!(keyv.get_as::<i64>("min").await.unwrap(), Some(i64::MIN));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
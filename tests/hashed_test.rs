@@ -0,0 +1,122 @@
+#[cfg(feature = "key-hashing")]
+use std::sync::Arc;
+
+#[cfg(feature = "key-hashing")]
+use keyv::{
+    adapter::{
+        events::StoreEvent,
+        hashed::{HashAlgo, HashedStore},
+        inmemory::InMemoryStore,
+    },
+    Keyv, Store, StoreError,
+};
+#[cfg(feature = "key-hashing")]
+use serde_json::json;
+
+#[cfg(feature = "key-hashing")]
+#[tokio::test]
+async fn test_hashed_keys_round_trip() {
+    let inner = Arc::new(InMemoryStore::new());
+    let keyv = Keyv::try_new(HashedStore::new(inner.clone(), HashAlgo::Sha256))
+        .await
+        .unwrap();
+
+    let long_key = format!("https://example.com/{}", "segment/".repeat(200));
+    keyv.set(&long_key, "page").await.unwrap();
+    keyv.set("short", 1).await.unwrap();
+
+    // The inner store only sees fixed-length hashes.
+    let stored = inner.keys(None).await.unwrap();
+    assert_eq!(stored.len(), 2);
+    assert!(stored.iter().all(|key| key.len() == 64));
+
+    assert_eq!(keyv.get(&long_key).await.unwrap(), Some(json!("page")));
+    assert!(keyv.has("short").await.unwrap());
+    assert_eq!(keyv.increment("short", 2).await.unwrap(), 3);
+
+    let mut keys = keyv.keys(None).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec![long_key.clone(), "short".to_string()]);
+    assert_eq!(
+        keyv.keys(Some("https://")).await.unwrap(),
+        vec![long_key.clone()]
+    );
+
+    assert!(keyv.rename("short", "renamed").await.unwrap());
+    assert_eq!(keyv.get("renamed").await.unwrap(), Some(json!(3)));
+    assert!(!keyv.has("short").await.unwrap());
+
+    keyv.remove(&long_key).await.unwrap();
+    assert_eq!(keyv.get(&long_key).await.unwrap(), None);
+}
+
+#[cfg(feature = "key-hashing")]
+#[tokio::test]
+async fn test_hashed_keys_compare_original_key() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = HashedStore::new(inner.clone(), HashAlgo::Sha256);
+    store.set("a", json!(1), None).await.unwrap();
+
+    // Simulate a collision: "b" hashes to the slot holding "a".
+    let slot = inner.keys(None).await.unwrap().remove(0);
+    let b_slot = {
+        let probe = Arc::new(InMemoryStore::new());
+        HashedStore::new(probe.clone(), HashAlgo::Sha256)
+            .set("b", json!(null), None)
+            .await
+            .unwrap();
+        probe.keys(None).await.unwrap().remove(0)
+    };
+    let entry = inner.get(&slot).await.unwrap();
+    inner.set(&b_slot, entry.unwrap(), None).await.unwrap();
+
+    assert_eq!(store.get("b").await.unwrap(), None);
+    assert!(!store.contains_key("b").await.unwrap());
+    assert_eq!(store.get("a").await.unwrap(), Some(json!(1)));
+
+    // Values written without hashing are reported rather than misread.
+    inner.set(&slot, json!("plain"), None).await.unwrap();
+    assert!(matches!(
+        store.get("a").await,
+        Err(StoreError::CodecError { .. })
+    ));
+}
+
+#[cfg(feature = "key-hashing")]
+#[tokio::test]
+async fn test_keyv_with_key_hashing() {
+    let keyv = Keyv::default().with_key_hashing(HashAlgo::Sha256);
+    keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    assert_eq!(
+        keyv.get_as::<String>("session").await.unwrap(),
+        Some("data".to_string())
+    );
+    assert!(keyv.ttl("session").await.unwrap().is_some());
+}
+
+#[cfg(feature = "key-hashing")]
+#[tokio::test]
+async fn test_keyv_with_key_hashing_reports_original_keys() {
+    let keyv = Keyv::default().with_key_hashing(HashAlgo::Sha256);
+    let sessions = keyv.namespace("session");
+    let mut events = keyv.subscribe();
+
+    keyv.set("user", "alice").await.unwrap();
+    sessions.set("42", "bob").await.unwrap();
+    keyv.remove("user").await.unwrap();
+
+    assert_eq!(
+        events.recv().await.unwrap(),
+        StoreEvent::Set { key: "user".into() }
+    );
+    assert_eq!(
+        events.recv().await.unwrap(),
+        StoreEvent::Set {
+            key: "session:42".into()
+        }
+    );
+    assert_eq!(
+        events.recv().await.unwrap(),
+        StoreEvent::Removed { key: "user".into() }
+    );
+}
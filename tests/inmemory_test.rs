@@ -1,4 +1,4 @@
-use keyv::Keyv;
+use keyv::{BatchOp, Keyv, KeyvError, StoreError, Ttl};
 
 #[tokio::test]
 async fn test_keyv() {
@@ -13,7 +13,7 @@ async fn test_keyv() {
             let number: i32 = serde_json::from_value(number).unwrap();
             assert_eq!(number, 10);
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("string").await.unwrap() {
@@ -21,7 +21,7 @@ async fn test_keyv() {
             let string: String = serde_json::from_value(string).unwrap();
             assert_eq!(string, "life long");
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("array").await.unwrap() {
@@ -29,11 +29,1262 @@ async fn test_keyv() {
             let array: Vec<String> = serde_json::from_value(array).unwrap();
             assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.remove_many(&["number", "string"]).await {
         Ok(_) => {}
-        Err(_) => assert!(false),
+        Err(_) => panic!("Failed to remove keys"),
     }
 }
+
+#[tokio::test]
+async fn test_keyv_has() {
+    let keyv = Keyv::default();
+    keyv.set("present", "value").await.unwrap();
+
+    assert!(keyv.has("present").await.unwrap());
+    assert!(!keyv.has("absent").await.unwrap());
+
+    keyv.remove("present").await.unwrap();
+    assert!(!keyv.has("present").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_get_many() {
+    let keyv = Keyv::default();
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("third", 3).await.unwrap();
+
+    let values = keyv
+        .get_many(&["first", "second", "third", "fourth"])
+        .await
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Some(serde_json::json!(1)),
+            None,
+            Some(serde_json::json!(3)),
+            None
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_keyv_set_many() {
+    let keyv = Keyv::default();
+    keyv.set_many(&[("first", 1), ("second", 2), ("first", 10)])
+        .await
+        .unwrap();
+
+    let values = keyv.get_many(&["first", "second"]).await.unwrap();
+    assert_eq!(
+        values,
+        vec![Some(serde_json::json!(10)), Some(serde_json::json!(2))]
+    );
+}
+
+#[tokio::test]
+async fn test_keyv_keys() {
+    let keyv = Keyv::default();
+    keyv.set("session:1", "alice").await.unwrap();
+    keyv.set("session:2", "bob").await.unwrap();
+    keyv.set("cache:1", "page").await.unwrap();
+
+    let mut sessions = keyv.keys(Some("session:")).await.unwrap();
+    sessions.sort();
+    assert_eq!(sessions, vec!["session:1", "session:2"]);
+
+    assert_eq!(keyv.keys(None).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_keyv_increment() {
+    let keyv = Keyv::default();
+    assert_eq!(keyv.increment("counter", 5).await.unwrap(), 5);
+    assert_eq!(keyv.increment("counter", -2).await.unwrap(), 3);
+
+    keyv.set("name", "not a number").await.unwrap();
+    assert!(matches!(
+        keyv.increment("name", 1).await,
+        Err(KeyvError::StoreError(StoreError::NotAnInteger(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_get_as() {
+    let keyv = Keyv::default();
+    keyv.set("array", vec!["hola", "test"]).await.unwrap();
+
+    let array: Option<Vec<String>> = keyv.get_as("array").await.unwrap();
+    assert_eq!(array, Some(vec!["hola".to_string(), "test".to_string()]));
+
+    let missing: Option<Vec<String>> = keyv.get_as("missing").await.unwrap();
+    assert_eq!(missing, None);
+
+    assert!(matches!(
+        keyv.get_as::<i32>("array").await,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { key: Some(key), .. }
+        )) if key == "array"
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_get_many_as() {
+    let keyv = Keyv::default();
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    keyv.set("text", "not a number").await.unwrap();
+
+    let values = keyv
+        .get_many_as::<i64, _>(&["a", "b", "missing"])
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values["a"], 1);
+    assert_eq!(values["b"], 2);
+
+    assert!(matches!(
+        keyv.get_many_as::<i64, _>(&["a", "text"]).await,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { key: Some(key), .. }
+        )) if key == "text"
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_get_required() {
+    let keyv = Keyv::default();
+    keyv.set("port", 8080).await.unwrap();
+    assert_eq!(keyv.get_required("port").await.unwrap(), 8080);
+
+    match keyv.get_required("host").await {
+        Err(KeyvError::StoreError(StoreError::KeyNotFound(key))) => assert_eq!(key, "host"),
+        other => panic!("Expected KeyNotFound, got {:?}", other),
+    }
+    assert_eq!(keyv.get("host").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_take() {
+    let keyv = Keyv::default();
+    keyv.set("job", "payload").await.unwrap();
+
+    assert_eq!(
+        keyv.take("job").await.unwrap(),
+        Some(serde_json::json!("payload"))
+    );
+    assert_eq!(keyv.take("job").await.unwrap(), None);
+    assert!(!keyv.has("job").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_swap() {
+    let keyv = Keyv::default();
+
+    assert_eq!(keyv.swap("config", "v1").await.unwrap(), None);
+    assert_eq!(keyv.swap("config", "v2").await.unwrap().unwrap(), "v1");
+    assert_eq!(keyv.get("config").await.unwrap().unwrap(), "v2");
+
+    keyv.set_with_ttl("expiring", "old", 1).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    assert_eq!(keyv.swap("expiring", "new").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_compare_and_swap() {
+    let keyv = Keyv::default();
+
+    // Absent key: only succeeds when `None` is expected.
+    assert!(!keyv.compare_and_swap("lock", Some("a"), "b").await.unwrap());
+    assert!(keyv.compare_and_swap("lock", None, "a").await.unwrap());
+    assert!(!keyv.compare_and_swap("lock", None, "b").await.unwrap());
+
+    // Mismatch leaves the value untouched.
+    assert!(!keyv.compare_and_swap("lock", Some("x"), "b").await.unwrap());
+    assert_eq!(
+        keyv.get("lock").await.unwrap(),
+        Some(serde_json::json!("a"))
+    );
+
+    // Match swaps the value.
+    assert!(keyv.compare_and_swap("lock", Some("a"), "b").await.unwrap());
+    assert_eq!(
+        keyv.get("lock").await.unwrap(),
+        Some(serde_json::json!("b"))
+    );
+}
+
+#[tokio::test]
+async fn test_keyv_len() {
+    let keyv = Keyv::default();
+    assert!(keyv.is_empty().await.unwrap());
+
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("second", 2).await.unwrap();
+    assert_eq!(keyv.len().await.unwrap(), 2);
+
+    keyv.clear().await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_remove_many_count() {
+    let keyv = Keyv::default();
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("second", 2).await.unwrap();
+
+    let removed = keyv
+        .remove_many(&["first", "second", "third"])
+        .await
+        .unwrap();
+    assert_eq!(removed, 2);
+    assert_eq!(keyv.remove_many(&["first"]).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_keyv_apply_batch() {
+    let keyv = Keyv::default().with_default_ttl(std::time::Duration::from_secs(60));
+    keyv.set("stale", 1).await.unwrap();
+
+    keyv.apply_batch(&[
+        BatchOp::set("status", "pending").unwrap(),
+        BatchOp::set("status", "shipped").unwrap(),
+        BatchOp::set_with_ttl("tracking", "1Z999", 3600).unwrap(),
+        BatchOp::remove("stale"),
+    ])
+    .await
+    .unwrap();
+
+    assert_eq!(keyv.get("stale").await.unwrap(), None);
+    assert_eq!(
+        keyv.get_as::<String>("status").await.unwrap().as_deref(),
+        Some("shipped")
+    );
+    assert!(keyv
+        .ttl("status")
+        .await
+        .unwrap()
+        .is_some_and(|ttl| ttl <= 60));
+    assert!(keyv
+        .ttl("tracking")
+        .await
+        .unwrap()
+        .is_some_and(|ttl| ttl > 60));
+}
+
+#[tokio::test]
+async fn test_keyv_set_group() {
+    let keyv = Keyv::default();
+    keyv.set_group(
+        &[("session:user", "alice"), ("session:role", "admin")],
+        Some(1),
+    )
+    .await
+    .unwrap();
+    keyv.set_group(&[("profile", "bob")], None).await.unwrap();
+    assert_eq!(keyv.ttl("session:user").await.unwrap(), Some(1));
+    assert_eq!(keyv.ttl("session:role").await.unwrap(), Some(1));
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert_eq!(keyv.get("session:user").await.unwrap(), None);
+    assert_eq!(keyv.get("session:role").await.unwrap(), None);
+    assert!(keyv.get("profile").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_keyv_clear_prefix() {
+    let keyv = Keyv::default();
+    keyv.set("session:1", "alice").await.unwrap();
+    keyv.set("session:2", "bob").await.unwrap();
+    keyv.set_with_ttl("session:3", "carol", 1).await.unwrap();
+    keyv.set("cache:1", "page").await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert_eq!(keyv.clear_prefix("session:").await.unwrap(), 2);
+    assert_eq!(keyv.keys(None).await.unwrap(), vec!["cache:1".to_string()]);
+    assert_eq!(keyv.clear_prefix("session:").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_keyv_ttl_expiry() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("short", "lived", 1).await.unwrap();
+    keyv.set("forever", "value").await.unwrap();
+    assert!(keyv.has("short").await.unwrap());
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert!(keyv.get("short").await.unwrap().is_none());
+    assert!(!keyv.has("short").await.unwrap());
+    assert_eq!(keyv.keys(None).await.unwrap(), vec!["forever".to_string()]);
+    assert_eq!(keyv.len().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_keyv_millisecond_ttl() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("short", "lived", Ttl::millis(200))
+        .await
+        .unwrap();
+    keyv.set_with_ttl("long", "lived", Ttl::seconds(60))
+        .await
+        .unwrap();
+    assert!(keyv.has("short").await.unwrap());
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    assert!(keyv.get("short").await.unwrap().is_none());
+    assert!(keyv.get("long").await.unwrap().is_some());
+}
+
+#[test]
+fn test_ttl_units() {
+    assert_eq!(Ttl::from(60), Ttl::seconds(60));
+    assert_eq!(Ttl::millis(2000), Ttl::seconds(2));
+    assert_eq!(Ttl::millis(1500).as_secs_ceil(), 2);
+    assert_eq!(Ttl::millis(1000).as_secs_ceil(), 1);
+    assert_eq!(Ttl::seconds(3).as_millis_ceil(), 3000);
+}
+
+#[tokio::test]
+async fn test_keyv_default_ttl() {
+    let keyv = Keyv::default().with_default_ttl(std::time::Duration::from_secs(1));
+    keyv.set("session", "data").await.unwrap();
+    keyv.set_with_ttl("explicit", "data", 60).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert!(keyv.get("session").await.unwrap().is_none());
+    assert!(keyv.get("explicit").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_keyv_default_ttl_sub_second() {
+    // Rounded up to a whole second instead of truncated to an immediate expiry.
+    let keyv = Keyv::default().with_default_ttl(std::time::Duration::from_millis(500));
+    keyv.set("session", "data").await.unwrap();
+    assert!(keyv.has("session").await.unwrap());
+    assert!(keyv.ttl("session").await.unwrap().is_some());
+
+    let keyv = Keyv::default().with_default_ttl(std::time::Duration::ZERO);
+    keyv.set("session", "data").await.unwrap();
+    assert!(keyv.has("session").await.unwrap());
+    assert_eq!(keyv.ttl("session").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_inmemory_cleanup_expired() {
+    use keyv::{adapter::inmemory::InMemoryStore, Store};
+
+    let store = InMemoryStore::new();
+    store
+        .set("short", serde_json::json!(1), Some(1))
+        .await
+        .unwrap();
+    store.set("long", serde_json::json!(2), None).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert_eq!(store.cleanup_expired().await, 1);
+    assert_eq!(store.len().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_inmemory_lru_eviction() {
+    use keyv::adapter::inmemory::InMemoryStoreBuilder;
+
+    let store = InMemoryStoreBuilder::new().max_entries(3).build();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    keyv.set("c", 3).await.unwrap();
+    keyv.set("d", 4).await.unwrap();
+
+    assert!(keyv.get("a").await.unwrap().is_none());
+    assert_eq!(keyv.len().await.unwrap(), 3);
+
+    // Reading "b" makes "c" the least recently used entry.
+    assert!(keyv.get("b").await.unwrap().is_some());
+    keyv.set("e", 5).await.unwrap();
+
+    assert!(keyv.get("b").await.unwrap().is_some());
+    assert!(keyv.get("c").await.unwrap().is_none());
+    assert!(keyv.get("d").await.unwrap().is_some());
+    assert!(keyv.get("e").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_inmemory_sharded_lru_eviction() {
+    use keyv::adapter::inmemory::InMemoryStoreBuilder;
+
+    let store = InMemoryStoreBuilder::new()
+        .max_entries(100)
+        .shards(4)
+        .build();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    for i in 0..1000 {
+        keyv.set(&format!("key:{}", i), i).await.unwrap();
+    }
+
+    // Each shard keeps at most 25 entries.
+    assert!(keyv.len().await.unwrap() <= 100);
+    assert!(keyv.get("key:999").await.unwrap().is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_inmemory_concurrent_stress() {
+    use std::collections::HashSet;
+
+    const TASKS: usize = 32;
+    const OPS: usize = 500;
+
+    let keyv = Keyv::default();
+    let handles: Vec<_> = (0..TASKS)
+        .map(|task| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                for op in 0..OPS {
+                    let key = format!("task:{}:{}", task, op);
+                    keyv.set(&key, op).await.unwrap();
+                    assert_eq!(keyv.get_as::<usize>(&key).await.unwrap(), Some(op));
+                    keyv.increment("shared", 1).await.unwrap();
+                    // Batches spanning several shards must not deadlock.
+                    keyv.apply_batch(&[
+                        BatchOp::set("batch:a", task).unwrap(),
+                        BatchOp::set("batch:b", task).unwrap(),
+                        BatchOp::remove(format!("task:{}:{}", task, op / 2)),
+                    ])
+                    .await
+                    .unwrap();
+                    if op % 50 == 0 {
+                        keyv.rename(&key, &format!("renamed:{}:{}", task, op))
+                            .await
+                            .unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(
+        keyv.get_as::<usize>("shared").await.unwrap(),
+        Some(TASKS * OPS)
+    );
+    // Both keys of a batch are always written together.
+    assert_eq!(
+        keyv.get("batch:a").await.unwrap(),
+        keyv.get("batch:b").await.unwrap()
+    );
+
+    // Every task removed the first half of its keys and renamed every 50th key; the
+    // first key was removed before it could be renamed.
+    let keys: HashSet<String> = keyv
+        .keys(Some("task:"))
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    for task in 0..TASKS {
+        for op in 0..OPS {
+            let expected = op >= OPS / 2 && op % 50 != 0;
+            assert_eq!(keys.contains(&format!("task:{}:{}", task, op)), expected);
+        }
+    }
+    assert_eq!(
+        keyv.keys(Some("renamed:")).await.unwrap().len(),
+        TASKS * (OPS / 50 - 1)
+    );
+}
+
+#[tokio::test]
+async fn test_keyv_ttl() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("session", "data", 120).await.unwrap();
+    keyv.set("forever", "data").await.unwrap();
+
+    let remaining = keyv.ttl("session").await.unwrap().unwrap();
+    assert!(remaining > 118 && remaining <= 120);
+    assert_eq!(keyv.ttl("forever").await.unwrap(), None);
+    assert_eq!(keyv.ttl("missing").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_huge_ttl_does_not_overflow() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("forever", "data", u64::MAX)
+        .await
+        .unwrap();
+    assert!(keyv.has("forever").await.unwrap());
+
+    keyv.set("later", "data").await.unwrap();
+    assert!(keyv.expire("later", Some(u64::MAX)).await.unwrap());
+    assert!(keyv.has("later").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_expire() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("session", "data", 1).await.unwrap();
+    keyv.set_with_ttl("temporary", "data", 60).await.unwrap();
+
+    assert!(keyv.expire("session", Some(120)).await.unwrap());
+    assert!(keyv.ttl("session").await.unwrap().unwrap() > 60);
+
+    assert!(keyv.expire("temporary", None).await.unwrap());
+    assert_eq!(keyv.ttl("temporary").await.unwrap(), None);
+    assert!(keyv.has("temporary").await.unwrap());
+
+    assert!(!keyv.expire("missing", Some(60)).await.unwrap());
+    assert!(!keyv.has("missing").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_extend_ttl() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    keyv.set("permanent", "data").await.unwrap();
+
+    assert!(keyv.extend_ttl("session", 60).await.unwrap());
+    let ttl = keyv.ttl("session").await.unwrap().unwrap();
+    assert!(ttl > 60 && ttl <= 120);
+
+    // Capped instead of overflowing.
+    assert!(keyv.extend_ttl("session", u64::MAX).await.unwrap());
+    assert!(keyv.has("session").await.unwrap());
+
+    assert!(keyv.extend_ttl("permanent", 60).await.unwrap());
+    assert_eq!(keyv.ttl("permanent").await.unwrap(), None);
+
+    assert!(!keyv.extend_ttl("missing", 60).await.unwrap());
+    assert!(!keyv.has("missing").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_set_if_absent() {
+    let keyv = Keyv::default();
+    assert!(keyv.set_if_absent("lock", "first", Some(1)).await.unwrap());
+    assert!(!keyv.set_if_absent("lock", "second", None).await.unwrap());
+    assert_eq!(
+        keyv.get_as::<String>("lock").await.unwrap(),
+        Some("first".to_string())
+    );
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert!(keyv.set_if_absent("lock", "third", None).await.unwrap());
+    assert_eq!(
+        keyv.get_as::<String>("lock").await.unwrap(),
+        Some("third".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_keyv_iter() {
+    use futures::TryStreamExt;
+
+    let keyv = Keyv::default();
+    // Enough entries to span several batches.
+    let entries: Vec<(String, i64)> = (0..1200).map(|i| (format!("key:{}", i), i)).collect();
+    keyv.set_many(&entries).await.unwrap();
+    keyv.set_with_ttl("short", "lived", 1).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let mut streamed: Vec<(String, serde_json::Value)> = keyv.iter().try_collect().await.unwrap();
+    streamed.sort_by_key(|(_, value)| value.as_i64());
+    assert_eq!(streamed.len(), 1200);
+    assert!(streamed
+        .iter()
+        .zip(&entries)
+        .all(
+            |((key, value), (expected_key, expected))| key == expected_key
+                && value.as_i64() == Some(*expected)
+        ));
+}
+
+#[tokio::test]
+async fn test_keyv_scan_prefix() {
+    use futures::TryStreamExt;
+
+    let keyv = Keyv::default();
+    let users: Vec<(String, i64)> = (0..700).map(|i| (format!("user:{}", i), i)).collect();
+    keyv.set_many(&users).await.unwrap();
+    keyv.set("order:1", 1).await.unwrap();
+    keyv.set("user_1", 1).await.unwrap();
+    keyv.set_with_ttl("user:expired", 0, 1).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let mut streamed: Vec<(String, serde_json::Value)> =
+        keyv.scan_prefix("user:").try_collect().await.unwrap();
+    streamed.sort_by_key(|(_, value)| value.as_i64());
+    assert_eq!(streamed.len(), 700);
+    assert!(streamed
+        .iter()
+        .zip(&users)
+        .all(
+            |((key, value), (expected_key, expected))| key == expected_key
+                && value.as_i64() == Some(*expected)
+        ));
+
+    let none: Vec<(String, serde_json::Value)> =
+        keyv.scan_prefix("missing:").try_collect().await.unwrap();
+    assert!(none.is_empty());
+
+    let namespaced = keyv.namespace("user");
+    let scoped: Vec<(String, serde_json::Value)> =
+        namespaced.scan_prefix("1").try_collect().await.unwrap();
+    assert!(!scoped.is_empty());
+    assert!(scoped.iter().all(|(key, _)| key.starts_with('1')));
+}
+
+#[tokio::test]
+async fn test_keyv_fields() {
+    use serde_json::json;
+
+    let keyv = Keyv::default();
+    keyv.set_field("user:1", "name", "Alice").await.unwrap();
+    keyv.set_field("user:1", "age", 30).await.unwrap();
+    keyv.set_field("user:1", "age", 31).await.unwrap();
+    assert_eq!(
+        keyv.get("user:1").await.unwrap(),
+        Some(json!({"name": "Alice", "age": 31}))
+    );
+    assert_eq!(
+        keyv.get_field("user:1", "name").await.unwrap(),
+        Some(json!("Alice"))
+    );
+    assert_eq!(keyv.get_field("user:1", "email").await.unwrap(), None);
+    assert_eq!(keyv.get_field("missing", "name").await.unwrap(), None);
+
+    assert!(keyv.remove_field("user:1", "age").await.unwrap());
+    assert!(!keyv.remove_field("user:1", "age").await.unwrap());
+    assert!(!keyv.remove_field("missing", "age").await.unwrap());
+    assert!(!keyv.has("missing").await.unwrap());
+    assert_eq!(
+        keyv.get("user:1").await.unwrap(),
+        Some(json!({"name": "Alice"}))
+    );
+
+    keyv.set("counter", 1).await.unwrap();
+    assert!(matches!(
+        keyv.set_field("counter", "n", 2).await,
+        Err(KeyvError::StoreError(StoreError::NotAnObject(key))) if key == "counter"
+    ));
+    assert!(matches!(
+        keyv.get_field("counter", "n").await,
+        Err(KeyvError::StoreError(StoreError::NotAnObject(_)))
+    ));
+
+    keyv.set_with_ttl("session", json!({}), 3600).await.unwrap();
+    keyv.set_field("session", "user", 1).await.unwrap();
+    assert!(keyv.ttl("session").await.unwrap().unwrap() > 3500);
+}
+
+#[tokio::test]
+async fn test_keyv_concurrent_set_field() {
+    use std::sync::Arc;
+
+    let keyv = Arc::new(Keyv::default());
+    let tasks: Vec<_> = (0..20)
+        .map(|i| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                keyv.set_field("record", &format!("field{}", i), i)
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let record = keyv.get("record").await.unwrap().unwrap();
+    assert_eq!(record.as_object().unwrap().len(), 20);
+}
+
+#[tokio::test]
+async fn test_set_field_gives_up_on_endless_conflicts() {
+    use async_trait::async_trait;
+    use keyv::{adapter::inmemory::InMemoryStore, Store};
+    use serde_json::{json, Value};
+
+    /// Loses every `compare_and_swap`, as if another writer always got in first.
+    struct ContendedStore(InMemoryStore);
+
+    #[async_trait]
+    impl Store for ContendedStore {
+        async fn initialize(&self) -> Result<(), StoreError> {
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+            self.0.get(key).await
+        }
+
+        async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+            self.0.set(key, value, ttl).await
+        }
+
+        async fn compare_and_swap(
+            &self,
+            _key: &str,
+            _expected: Option<Value>,
+            _new: Value,
+            _ttl: Option<u64>,
+        ) -> Result<bool, StoreError> {
+            Ok(false)
+        }
+
+        async fn remove(&self, key: &str) -> Result<(), StoreError> {
+            self.0.remove(key).await
+        }
+
+        async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+            self.0.remove_many(keys).await
+        }
+
+        async fn clear(&self) -> Result<(), StoreError> {
+            self.0.clear().await
+        }
+    }
+
+    let store = ContendedStore(InMemoryStore::new());
+    store
+        .set("user", json!({"name": "bob"}), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        store.set_field("user", "name", json!("alice"), None).await,
+        Err(StoreError::Transient(_))
+    ));
+    assert!(matches!(
+        store.remove_field("user", "name").await,
+        Err(StoreError::Transient(_))
+    ));
+    assert!(matches!(
+        store.set_json_path("user", "$.name", json!("alice")).await,
+        Err(StoreError::Transient(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_ping() {
+    let keyv = Keyv::default();
+    keyv.ping().await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_store_ext_through_dyn_store() {
+    use keyv::{adapter::inmemory::InMemoryStore, Store, StoreExt};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    let boxed: Box<dyn Store> = Box::new(InMemoryStore::new());
+    boxed
+        .set("array", json!(["hola", "test"]), None)
+        .await
+        .unwrap();
+    let array: Option<Vec<String>> = boxed.get_as("array").await.unwrap();
+    assert_eq!(array, Some(vec!["hola".to_string(), "test".to_string()]));
+    assert!(matches!(
+        boxed.get_as::<u32>("array").await,
+        Err(StoreError::DeserializationError { .. })
+    ));
+
+    let shared: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+    assert!(!shared.has("computed").await.unwrap());
+    let value = shared
+        .get_or_insert_with("computed", None, || async { json!(1) })
+        .await
+        .unwrap();
+    assert_eq!(value, json!(1));
+    let value = shared
+        .get_or_insert_with("computed", None, || async { panic!("value is cached") })
+        .await
+        .unwrap();
+    assert_eq!(value, json!(1));
+    assert!(shared.has("computed").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_get_or_insert_with() {
+    let keyv = Keyv::default();
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+
+    for _ in 0..2 {
+        let value = keyv
+            .get_or_insert_with("user", Some(60), || async {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                vec!["Alice"]
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::json!(["Alice"]));
+    }
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert!(keyv.ttl("user").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_keyv_rename() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl("draft:1", "hello", 60).await.unwrap();
+    keyv.set("published:1", "stale").await.unwrap();
+
+    assert!(keyv.rename("draft:1", "published:1").await.unwrap());
+    assert!(!keyv.has("draft:1").await.unwrap());
+    assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+    assert!(keyv.ttl("published:1").await.unwrap().is_some());
+
+    assert!(!keyv.rename("missing", "published:1").await.unwrap());
+    assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+
+    assert!(keyv.rename("published:1", "published:1").await.unwrap());
+    assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+}
+
+#[tokio::test]
+async fn test_keyv_bytes() {
+    let keyv = Keyv::default();
+    let bytes = vec![0u8, 159, 146, 150, 255];
+    keyv.set_bytes("blob", &bytes).await.unwrap();
+    assert_eq!(keyv.get_bytes("blob").await.unwrap(), Some(bytes));
+    assert_eq!(keyv.get_bytes("missing").await.unwrap(), None);
+
+    keyv.set("text", "not bytes").await.unwrap();
+    assert!(matches!(
+        keyv.get_bytes("text").await,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { .. }
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_export_import() {
+    use futures::{stream, TryStreamExt};
+    use keyv::{adapter::inmemory::InMemoryStore, migrate, Store};
+    use serde_json::json;
+
+    let src = InMemoryStore::new();
+    src.set("a", json!(1), None).await.unwrap();
+    src.set("b", json!("two"), Some(3600)).await.unwrap();
+    src.set("gone", json!(3), Some(1)).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let mut exported: Vec<_> = src.export().try_collect().await.unwrap();
+    exported.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(exported.len(), 2);
+    assert_eq!(exported[0], ("a".to_string(), json!(1), None));
+    let expires_at = exported[1].2.unwrap();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    assert!((now + 3598..=now + 3600).contains(&expires_at));
+
+    let dst = InMemoryStore::new();
+    dst.set("a", json!("stale"), None).await.unwrap();
+    assert_eq!(migrate(&src, &dst).await.unwrap(), 2);
+    assert_eq!(dst.get("a").await.unwrap(), Some(json!(1)));
+    assert_eq!(dst.get("b").await.unwrap(), Some(json!("two")));
+    assert!(dst.get_ttl("b").await.unwrap().unwrap() > 3500);
+    assert_eq!(dst.get_ttl("a").await.unwrap(), None);
+
+    // Entries whose expiry has already passed are not imported.
+    let expired = stream::iter(vec![
+        Ok(("old".to_string(), json!(1), Some(now - 10))),
+        Ok(("new".to_string(), json!(2), None)),
+    ]);
+    assert_eq!(dst.import(Box::pin(expired)).await.unwrap(), 1);
+    assert!(!dst.contains_key("old").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_key_validation() {
+    let keyv = Keyv::default()
+        .with_max_key_length(10)
+        .with_forbidden_key_chars(['\n']);
+
+    keyv.set("short", 1).await.unwrap();
+    assert_eq!(keyv.get("short").await.unwrap().unwrap(), 1);
+
+    for key in ["much-too-long-key", "line\nbreak"] {
+        assert!(matches!(
+            keyv.set(key, 1).await,
+            Err(KeyvError::StoreError(StoreError::InvalidKey(_)))
+        ));
+        assert!(matches!(
+            keyv.get(key).await,
+            Err(KeyvError::StoreError(StoreError::InvalidKey(_)))
+        ));
+    }
+    assert!(keyv
+        .set_many(&[("ok", 1), ("much-too-long-key", 2)])
+        .await
+        .is_err());
+    assert!(!keyv.has("ok").await.unwrap());
+    assert!(keyv.rename("short", "much-too-long-key").await.is_err());
+    assert!(keyv
+        .set_field("much-too-long-key", "name", "alice")
+        .await
+        .is_err());
+    assert!(keyv
+        .set_json_path("line\nbreak", "$.name", "alice")
+        .await
+        .is_err());
+    assert!(keyv
+        .set_group(&[("ok", 1), ("much-too-long-key", 2)], None)
+        .await
+        .is_err());
+    assert!(!keyv.has("ok").await.unwrap());
+
+    // The limit covers the namespace prefix as well.
+    let sessions = keyv.namespace("session");
+    sessions.set("1", 1).await.unwrap();
+    assert!(sessions.set("123", 1).await.is_err());
+}
+
+#[tokio::test]
+async fn test_keyv_read_only() {
+    let keyv = Keyv::default();
+    keyv.set("user", "alice").await.unwrap();
+    keyv.set("count", 1).await.unwrap();
+
+    let reader = keyv.read_only();
+    assert_eq!(reader.get("user").await.unwrap().unwrap(), "alice");
+    assert!(reader.has("count").await.unwrap());
+    assert_eq!(reader.keys(None).await.unwrap().len(), 2);
+    assert_eq!(reader.len().await.unwrap(), 2);
+
+    let is_read_only = |result: Result<(), KeyvError>| {
+        matches!(result, Err(KeyvError::StoreError(StoreError::ReadOnly)))
+    };
+    assert!(is_read_only(reader.set("user", "bob").await));
+    assert!(is_read_only(reader.set_with_ttl("user", "bob", 60).await));
+    assert!(is_read_only(reader.set_many(&[("user", "bob")]).await));
+    assert!(is_read_only(reader.set_bytes("user", b"bob").await));
+    assert!(is_read_only(
+        reader
+            .compare_and_swap("user", Some("alice"), "bob")
+            .await
+            .map(drop)
+    ));
+    assert!(is_read_only(reader.swap("user", "bob").await.map(drop)));
+    assert!(is_read_only(
+        reader.set_if_absent("new", "bob", None).await.map(drop)
+    ));
+    assert!(is_read_only(reader.increment("count", 1).await.map(drop)));
+    assert!(is_read_only(
+        reader.expire("user", Some(60)).await.map(drop)
+    ));
+    assert!(is_read_only(reader.remove("user").await));
+    assert!(is_read_only(reader.take("user").await.map(drop)));
+    assert!(is_read_only(reader.remove_many(&["user"]).await.map(drop)));
+    assert!(is_read_only(
+        reader
+            .apply_batch(&[BatchOp::Remove { key: "user".into() }])
+            .await
+    ));
+    assert!(is_read_only(reader.rename("user", "owner").await.map(drop)));
+    assert!(is_read_only(reader.set_field("user", "name", "bob").await));
+    assert!(is_read_only(
+        reader
+            .set_json_path("user", "$.name", "bob")
+            .await
+            .map(drop)
+    ));
+    assert!(is_read_only(
+        reader.remove_field("user", "name").await.map(drop)
+    ));
+    assert!(is_read_only(
+        reader.set_group(&[("user", "bob")], None).await
+    ));
+    assert!(is_read_only(reader.clear_prefix("u").await.map(drop)));
+    assert!(is_read_only(reader.clear().await));
+    assert!(is_read_only(reader.optimize().await));
+
+    assert_eq!(keyv.get("user").await.unwrap().unwrap(), "alice");
+    assert_eq!(keyv.get("count").await.unwrap().unwrap(), 1);
+    assert_eq!(keyv.ttl("user").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_max_value_bytes() {
+    let keyv = Keyv::default().with_max_value_bytes(10);
+
+    // `"12345678"` encodes to exactly ten bytes of JSON.
+    keyv.set("fits", "12345678").await.unwrap();
+    assert!(matches!(
+        keyv.set("too-big", "123456789").await,
+        Err(KeyvError::StoreError(StoreError::ValueTooLarge {
+            size: 11,
+            limit: 10
+        }))
+    ));
+    assert!(!keyv.has("too-big").await.unwrap());
+
+    assert!(keyv
+        .set_many(&[("a", "1"), ("b", "123456789")])
+        .await
+        .is_err());
+    assert!(!keyv.has("a").await.unwrap());
+    assert!(keyv.swap("fits", "123456789").await.is_err());
+    assert!(keyv.set_field("user", "name", "123456789").await.is_err());
+    keyv.set_field("user", "name", "12345678").await.unwrap();
+    assert!(keyv
+        .set_json_path("user", "$.name", "123456789")
+        .await
+        .is_err());
+    assert_eq!(
+        keyv.get_field("user", "name").await.unwrap().unwrap(),
+        "12345678"
+    );
+    assert!(keyv.set_bytes("blob", &[0u8; 11]).await.is_err());
+    keyv.set_bytes("blob", &[0u8; 10]).await.unwrap();
+    assert_eq!(keyv.get("fits").await.unwrap().unwrap(), "12345678");
+}
+
+#[tokio::test]
+async fn test_keyv_large_integers() {
+    let keyv = Keyv::default();
+    keyv.set("max", u64::MAX).await.unwrap();
+    keyv.set("min", i64::MIN).await.unwrap();
+    keyv.set_many(&[("many", u64::MAX - 1)]).await.unwrap();
+    assert_eq!(keyv.get_as::<u64>("max").await.unwrap(), Some(u64::MAX));
+    assert_eq!(keyv.get_as::<i64>("min").await.unwrap(), Some(i64::MIN));
+    assert_eq!(
+        keyv.get_as::<u64>("many").await.unwrap(),
+        Some(u64::MAX - 1)
+    );
+
+    // Beyond 64 bits JSON has no exact representation, so the write is rejected.
+    assert!(matches!(
+        keyv.set("huge", u128::MAX).await,
+        Err(KeyvError::StoreError(StoreError::SerializationError { key: Some(key), .. })) if key == "huge"
+    ));
+    assert!(!keyv.has("huge").await.unwrap());
+
+    assert!(matches!(
+        BatchOp::set("huge", u128::MAX),
+        Err(StoreError::SerializationError { key: Some(key), .. }) if key == "huge"
+    ));
+    assert!(matches!(
+        BatchOp::set_with_ttl("huge", u128::MAX, 60),
+        Err(StoreError::SerializationError { .. })
+    ));
+    assert!(matches!(
+        keyv.get_or_insert_with("huge", None, || async { u128::MAX })
+            .await,
+        Err(KeyvError::StoreError(StoreError::SerializationError { .. }))
+    ));
+    assert!(!keyv.has("huge").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_keyv_range() {
+    let keyv = Keyv::default();
+    keyv.set_many(&[
+        ("event:0300", 3),
+        ("event:0100", 1),
+        ("event:0200", 2),
+        ("event:1000", 10),
+        ("log:0150", 0),
+    ])
+    .await
+    .unwrap();
+
+    let entries = keyv.range("event:0100", "event:1000").await.unwrap();
+    let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["event:0100", "event:0200", "event:0300"]);
+    assert_eq!(entries[1].1, serde_json::json!(2));
+
+    assert!(keyv
+        .range("event:1000", "event:0100")
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(keyv.range("a", "z").await.unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_keyv_u64_keys() {
+    let keyv = Keyv::default();
+    for id in [9, 10, 100, u64::MAX] {
+        keyv.set_u64(id, format!("user {}", id)).await.unwrap();
+    }
+
+    assert_eq!(
+        keyv.get_u64(10).await.unwrap(),
+        Some(serde_json::json!("user 10"))
+    );
+    assert_eq!(keyv.get_u64(11).await.unwrap(), None);
+
+    let ids: Vec<u64> = keyv
+        .range_u64(0, u64::MAX)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![9, 10, 100]);
+
+    keyv.remove_u64(10).await.unwrap();
+    assert_eq!(keyv.get_u64(10).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_set_json_path() {
+    let keyv = Keyv::default();
+    keyv.set_with_ttl(
+        "user",
+        serde_json::json!({"name": "alice", "address": {"city": "Paris"}, "tags": ["a", "b"]}),
+        3600,
+    )
+    .await
+    .unwrap();
+
+    assert!(keyv
+        .set_json_path("user", "$.address.city", "Lyon")
+        .await
+        .unwrap());
+    assert!(keyv
+        .set_json_path("user", "$.address.zip", 69000)
+        .await
+        .unwrap());
+    assert!(keyv.set_json_path("user", "$.tags[1]", "c").await.unwrap());
+    assert!(!keyv.set_json_path("user", "$.tags[2]", "d").await.unwrap());
+    assert!(!keyv
+        .set_json_path("user", "$.phone.home", "555")
+        .await
+        .unwrap());
+    assert!(!keyv
+        .set_json_path("missing", "$.name", "bob")
+        .await
+        .unwrap());
+    assert_eq!(
+        keyv.get("user").await.unwrap().unwrap(),
+        serde_json::json!({
+            "name": "alice",
+            "address": {"city": "Lyon", "zip": 69000},
+            "tags": ["a", "c"]
+        })
+    );
+    assert!(keyv.ttl("user").await.unwrap().is_some());
+
+    for path in [
+        "name",
+        "$",
+        "$.",
+        "$..name",
+        "$.tags[x]",
+        "$.tags[1",
+        "$.a b",
+        "$[0]x",
+    ] {
+        assert!(
+            matches!(
+                keyv.set_json_path("user", path, 1).await,
+                Err(KeyvError::StoreError(StoreError::InvalidJsonPath(_)))
+            ),
+            "{}",
+            path
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_keyv_clone_shares_store() {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<Keyv>();
+
+    let keyv = Keyv::default();
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                keyv.set(&format!("task:{}", i), i).await.unwrap();
+                keyv.increment("count", 1).await.unwrap();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(keyv.keys(Some("task:")).await.unwrap().len(), 8);
+    assert_eq!(keyv.get_as::<i64>("count").await.unwrap(), Some(8));
+
+    // Settings applied to a clone stay with the clone.
+    let expiring = keyv
+        .clone()
+        .with_default_ttl(std::time::Duration::from_secs(60));
+    expiring.set("short", 1).await.unwrap();
+    keyv.set("long", 1).await.unwrap();
+    assert!(keyv.ttl("short").await.unwrap().is_some());
+    assert_eq!(keyv.ttl("long").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_keyv_get_many_partial() {
+    use keyv::{adapter::inmemory::InMemoryStore, Store, StoreExt};
+    use std::sync::Arc;
+
+    let keyv = Keyv::default();
+    keyv.set_many(&[("b", 2), ("d", 4)]).await.unwrap();
+
+    let (found, missing) = keyv
+        .get_many_partial(&["e", "b", "a", "d", "e", "c"])
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found["b"], serde_json::json!(2));
+    assert_eq!(found["d"], serde_json::json!(4));
+    assert_eq!(missing, vec!["e", "a", "c"]);
+
+    let (found, missing) = keyv.get_many_partial::<&str>(&[]).await.unwrap();
+    assert!(found.is_empty() && missing.is_empty());
+
+    let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+    store.set("x", 1.into(), None).await.unwrap();
+    let (found, missing) = store.get_many_partial(&["x", "y"]).await.unwrap();
+    assert_eq!(found.keys().collect::<Vec<_>>(), vec!["x"]);
+    assert_eq!(missing, vec!["y"]);
+}
+
+#[tokio::test]
+async fn test_keyv_get_or() {
+    let keyv = Keyv::default();
+    keyv.set("config:retries", 5).await.unwrap();
+    keyv.set("config:name", "keyv").await.unwrap();
+
+    assert_eq!(keyv.get_or("config:retries", 3u32).await.unwrap(), 5);
+    assert_eq!(keyv.get_or("config:missing", 3u32).await.unwrap(), 3);
+    assert_eq!(
+        keyv.get_or_default::<Vec<String>>("config:missing")
+            .await
+            .unwrap(),
+        Vec::<String>::new()
+    );
+
+    // A value of the wrong type is an error, not a fallback.
+    assert!(matches!(
+        keyv.get_or("config:name", 3u32).await,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { .. }
+        ))
+    ));
+    assert!(keyv.get_or_default::<u32>("config:name").await.is_err());
+}
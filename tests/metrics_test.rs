@@ -0,0 +1,86 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::stream;
+use keyv::{
+    adapter::{
+        inmemory::InMemoryStore,
+        metrics::{Metrics, MetricsStore},
+    },
+    Keyv, Store, StoreError,
+};
+use serde_json::json;
+
+#[derive(Default)]
+struct Recorder {
+    samples: Mutex<Vec<(String, bool)>>,
+}
+
+impl Metrics for Recorder {
+    fn on_operation(&self, op: &str, _duration: Duration, result: Result<(), &StoreError>) {
+        self.samples
+            .lock()
+            .unwrap()
+            .push((op.to_string(), result.is_ok()));
+    }
+}
+
+#[tokio::test]
+async fn test_keyv_with_metrics() {
+    let recorder = Arc::new(Recorder::default());
+    let keyv = Keyv::default().with_metrics(recorder.clone());
+
+    keyv.set("number", 42).await.unwrap();
+    keyv.get("number").await.unwrap();
+    keyv.set("text", "not a number").await.unwrap();
+    assert!(keyv.increment("text", 1).await.is_err());
+    keyv.remove("number").await.unwrap();
+
+    let samples = recorder.samples.lock().unwrap().clone();
+    assert_eq!(
+        samples,
+        vec![
+            ("set".to_string(), true),
+            ("get".to_string(), true),
+            ("set".to_string(), true),
+            ("increment".to_string(), false),
+            ("remove".to_string(), true),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_metrics_store_observes_partial_writes() {
+    let recorder = Arc::new(Recorder::default());
+    let store = MetricsStore::new(Arc::new(InMemoryStore::new()), recorder.clone());
+
+    store
+        .set_field("user", "name", json!("alice"), None)
+        .await
+        .unwrap();
+    store.get_field("user", "name").await.unwrap();
+    store.remove_field("user", "name").await.unwrap();
+    store.set_group(&[("a", json!(1))], None).await.unwrap();
+    let entries = stream::iter(vec![Ok(("b".to_string(), json!(2), None))]);
+    store.import(Box::pin(entries)).await.unwrap();
+
+    let ops: Vec<String> = recorder
+        .samples
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(op, _)| op.clone())
+        .collect();
+    assert_eq!(
+        ops,
+        [
+            "set_field",
+            "get_field",
+            "remove_field",
+            "set_group",
+            "import"
+        ]
+    );
+}
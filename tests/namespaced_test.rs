@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use keyv::{
+    adapter::{inmemory::InMemoryStore, namespaced::NamespacedStore},
+    Keyv, Store,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_namespaced_prefixes_keys() {
+    let inner = Arc::new(InMemoryStore::new());
+    let store = NamespacedStore::new(inner.clone(), "tenant");
+
+    store.set("key", json!("value"), None).await.unwrap();
+    assert_eq!(inner.get("tenant:key").await.unwrap(), Some(json!("value")));
+    assert_eq!(store.get("key").await.unwrap(), Some(json!("value")));
+    assert_eq!(store.keys(None).await.unwrap(), vec!["key".to_string()]);
+}
+
+#[tokio::test]
+async fn test_namespaced_isolation() {
+    let inner = Arc::new(InMemoryStore::new());
+    let first = Keyv::try_new(NamespacedStore::new(inner.clone(), "first"))
+        .await
+        .unwrap();
+    let second = Keyv::try_new(NamespacedStore::new(inner.clone(), "second"))
+        .await
+        .unwrap();
+
+    first.set("shared", 1).await.unwrap();
+    first.set("only_first", 1).await.unwrap();
+    second.set("shared", 2).await.unwrap();
+
+    assert_eq!(first.get_as::<i64>("shared").await.unwrap(), Some(1));
+    assert_eq!(second.get_as::<i64>("shared").await.unwrap(), Some(2));
+    assert!(!second.has("only_first").await.unwrap());
+    assert_eq!(first.len().await.unwrap(), 2);
+    assert_eq!(second.keys(None).await.unwrap(), vec!["shared".to_string()]);
+
+    assert_eq!(
+        second.remove_many(&["shared", "only_first"]).await.unwrap(),
+        1
+    );
+    second.set("shared", 2).await.unwrap();
+
+    first.clear().await.unwrap();
+    assert!(first.is_empty().await.unwrap());
+    assert_eq!(second.get_as::<i64>("shared").await.unwrap(), Some(2));
+    assert_eq!(inner.len().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_keyv_namespace_views() {
+    let keyv = Keyv::default();
+    keyv.set("outside", 0).await.unwrap();
+
+    let a = keyv.namespace("a");
+    let ab = a.namespace("b");
+    a.set("key", 1).await.unwrap();
+    ab.set("key", 2).await.unwrap();
+
+    assert_eq!(keyv.get("a:key").await.unwrap(), Some(json!(1)));
+    assert_eq!(keyv.get("a:b:key").await.unwrap(), Some(json!(2)));
+    assert_eq!(ab.keys(None).await.unwrap(), vec!["key".to_string()]);
+
+    ab.clear().await.unwrap();
+    assert_eq!(a.get("key").await.unwrap(), Some(json!(1)));
+    assert_eq!(ab.get("key").await.unwrap(), None);
+    assert_eq!(keyv.len().await.unwrap(), 2);
+}
@@ -45,3 +45,265 @@ async fn test_keyv_redis() {
         .unwrap();
     assert_eq!(array, vec!["hola".to_string(), "test".to_string()]);
 }
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_keyv_redis_ttl() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_with_ttl("redis_ttl_short", "value", 1)
+        .await
+        .unwrap();
+    assert!(keyv.get("redis_ttl_short").await.unwrap().is_some());
+
+    keyv.set_with_ttl("redis_ttl_persist", "value", 1)
+        .await
+        .unwrap();
+    keyv.set("redis_ttl_persist", "value").await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+
+    assert!(keyv.get("redis_ttl_short").await.unwrap().is_none());
+    assert!(keyv.get("redis_ttl_persist").await.unwrap().is_some());
+    keyv.remove("redis_ttl_persist").await.unwrap();
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_keyv_redis_get_many_order() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_many(&[("redis_batch_a", 1), ("redis_batch_c", 3)])
+        .await
+        .unwrap();
+
+    let values = keyv
+        .get_many(&["redis_batch_c", "redis_batch_missing", "redis_batch_a"])
+        .await
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![Some(serde_json::json!(3)), None, Some(serde_json::json!(1))]
+    );
+
+    keyv.remove_many(&["redis_batch_a", "redis_batch_c"])
+        .await
+        .unwrap();
+}
+
+#[cfg(all(feature = "redis", feature = "msgpack"))]
+#[tokio::test]
+async fn test_keyv_redis_msgpack() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .namespace("keyv_msgpack")
+        .serializer(std::sync::Arc::new(keyv::MessagePackSerializer))
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("array", vec!["hola", "test"]).await.unwrap();
+    let array: Option<Vec<String>> = keyv.get_as("array").await.unwrap();
+    assert_eq!(array, Some(vec!["hola".to_string(), "test".to_string()]));
+
+    keyv.remove("counter").await.unwrap();
+    assert_eq!(keyv.increment("counter", 5).await.unwrap(), 5);
+    assert_eq!(keyv.increment("counter", -2).await.unwrap(), 3);
+
+    keyv.remove_many(&["array", "counter"]).await.unwrap();
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_connect_retries_exhausted() {
+    let started = std::time::Instant::now();
+    // Nothing listens on port 1, so every attempt fails.
+    let result = RedisStoreBuilder::new()
+        .uri("redis://127.0.0.1:1/")
+        .connect_retries(2)
+        .retry_backoff(std::time::Duration::from_millis(50))
+        .build()
+        .await;
+
+    assert!(matches!(result, Err(keyv::StoreError::ConnectionError(_))));
+    // Two retries wait 50ms and then 100ms.
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_cluster_unreachable() {
+    let result = RedisStoreBuilder::new()
+        .cluster(vec![
+            "redis://127.0.0.1:1/".to_string(),
+            "redis://127.0.0.1:2/".to_string(),
+        ])
+        .connect_retries(1)
+        .retry_backoff(std::time::Duration::from_millis(10))
+        .build()
+        .await;
+    assert!(matches!(result, Err(keyv::StoreError::ConnectionError(_))));
+
+    let result = RedisStoreBuilder::new().cluster(Vec::new()).build().await;
+    assert!(matches!(result, Err(keyv::StoreError::ConnectionError(_))));
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_get_or_insert_with() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .build()
+        .await
+        .unwrap();
+    keyv::Store::remove(&store, "get_or_insert").await.unwrap();
+
+    let value = store
+        .get_or_insert_with("get_or_insert", Some(60), || async { serde_json::json!(1) })
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!(1));
+
+    let value = store
+        .get_or_insert_with("get_or_insert", Some(60), || async { serde_json::json!(2) })
+        .await
+        .unwrap();
+    assert_eq!(value, serde_json::json!(1));
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_swap() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.remove("swap").await.unwrap();
+
+    assert_eq!(keyv.swap("swap", "v1").await.unwrap(), None);
+    assert_eq!(keyv.swap("swap", "v2").await.unwrap().unwrap(), "v1");
+    assert_eq!(keyv.get("swap").await.unwrap().unwrap(), "v2");
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_redis_connection_pool() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .namespace("keyv_pool_test")
+        .max_connections(4)
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = std::sync::Arc::new(Keyv::try_new(store).await.unwrap());
+    let tasks: Vec<_> = (0..16)
+        .map(|i| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                let key = format!("key{}", i);
+                keyv.set(&key, i).await.unwrap();
+                keyv.get(&key).await.unwrap().unwrap()
+            })
+        })
+        .collect();
+    for (i, task) in tasks.into_iter().enumerate() {
+        assert_eq!(task.await.unwrap(), i);
+    }
+    keyv.clear().await.unwrap();
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_keyv_redis_large_integers() {
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("large_int:max", u64::MAX).await.unwrap();
+    keyv.set("large_int:min", i64::MIN).await.unwrap();
+    assert_eq!(
+        keyv.get_as::<u64>("large_int:max").await.unwrap(),
+        Some(u64::MAX)
+    );
+    assert_eq!(
+        keyv.get_as::<i64>("large_int:min").await.unwrap(),
+        Some(i64::MIN)
+    );
+}
+
+#[cfg(feature = "redis")]
+#[tokio::test]
+async fn test_redis_keyspace_listener() {
+    use keyv::adapter::events::StoreEvent;
+    use std::time::Duration;
+
+    let client = redis::Client::open("redis://localhost:6379").unwrap();
+    let mut conn = client.get_connection().unwrap();
+    let _: () = redis::cmd("CONFIG")
+        .arg("SET")
+        .arg("notify-keyspace-events")
+        .arg("E$g")
+        .query(&mut conn)
+        .unwrap();
+
+    let store = RedisStoreBuilder::new()
+        .uri("redis://localhost:6379")
+        .namespace("keyspace_test")
+        .build()
+        .await
+        .unwrap();
+    let listener = store.keyspace_listener().unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    let mut events = keyv.subscribe();
+    let sink = keyv.clone();
+    let handle = listener.spawn(move |event| sink.publish(event));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Written by another client, outside the namespace and inside it.
+    let _: () = redis::cmd("SET")
+        .arg("other:key")
+        .arg("1")
+        .query(&mut conn)
+        .unwrap();
+    let _: () = redis::cmd("SET")
+        .arg("keyspace_test:user")
+        .arg("\"alice\"")
+        .query(&mut conn)
+        .unwrap();
+    let _: () = redis::cmd("DEL")
+        .arg("keyspace_test:user")
+        .query(&mut conn)
+        .unwrap();
+
+    for expected in [
+        StoreEvent::Set { key: "user".into() },
+        StoreEvent::Removed { key: "user".into() },
+    ] {
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event, expected);
+    }
+    handle.stop();
+}
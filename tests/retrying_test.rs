@@ -0,0 +1,136 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use keyv::{
+    adapter::{inmemory::InMemoryStore, retrying::RetryingStore},
+    Keyv, KeyvError, Store, StoreError,
+};
+use serde_json::{json, Value};
+
+/// Fails the first `failures` writes with `error`, then behaves like an in-memory store.
+struct FlakyStore {
+    inner: InMemoryStore,
+    failures: AtomicU32,
+    attempts: AtomicU32,
+    error: fn() -> StoreError,
+}
+
+impl FlakyStore {
+    fn new(failures: u32, error: fn() -> StoreError) -> Self {
+        Self {
+            inner: InMemoryStore::new(),
+            failures: AtomicU32::new(failures),
+            attempts: AtomicU32::new(0),
+            error,
+        }
+    }
+}
+
+#[async_trait]
+impl Store for FlakyStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        let failing = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failing {
+            return Err((self.error)());
+        }
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+fn deadlock() -> StoreError {
+    StoreError::Transient("deadlock detected".to_string())
+}
+
+fn syntax_error() -> StoreError {
+    StoreError::QueryError("syntax error".to_string())
+}
+
+#[tokio::test]
+async fn test_retrying_store_retries_transient_errors() {
+    let flaky = Arc::new(FlakyStore::new(2, deadlock));
+    let store = RetryingStore::new(flaky.clone())
+        .with_max_attempts(3)
+        .with_backoff(Duration::from_millis(1));
+
+    store.set("key", json!("value"), None).await.unwrap();
+    assert_eq!(flaky.attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(store.get("key").await.unwrap(), Some(json!("value")));
+}
+
+#[tokio::test]
+async fn test_retrying_store_gives_up() {
+    let flaky = Arc::new(FlakyStore::new(5, deadlock));
+    let store = RetryingStore::new(flaky.clone())
+        .with_max_attempts(3)
+        .with_backoff(Duration::from_millis(1));
+
+    assert!(matches!(
+        store.set("key", json!("value"), None).await,
+        Err(StoreError::Transient(_))
+    ));
+    assert_eq!(flaky.attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retrying_store_passes_permanent_errors_through() {
+    let flaky = Arc::new(FlakyStore::new(1, syntax_error));
+    let store = RetryingStore::new(flaky.clone()).with_backoff(Duration::from_millis(1));
+
+    assert!(matches!(
+        store.set("key", json!("value"), None).await,
+        Err(StoreError::QueryError(_))
+    ));
+    assert_eq!(flaky.attempts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_keyv_with_retry() {
+    let keyv = Keyv::try_new(FlakyStore::new(1, deadlock))
+        .await
+        .unwrap()
+        .with_retry(2, Duration::from_millis(1));
+    keyv.set("key", "value").await.unwrap();
+
+    let keyv = Keyv::try_new(FlakyStore::new(2, deadlock))
+        .await
+        .unwrap()
+        .with_retry(2, Duration::from_millis(1));
+    assert!(matches!(
+        keyv.set("key", "value").await,
+        Err(KeyvError::StoreError(StoreError::Transient(_)))
+    ));
+}
@@ -0,0 +1,40 @@
+use keyv::{JsonSerializer, Serializer};
+use serde_json::json;
+
+fn sample() -> serde_json::Value {
+    json!({
+        "name": "keyv",
+        "tags": ["cache", "store"],
+        "count": 42,
+        "max": u64::MAX,
+        "min": i64::MIN,
+        "ratio": 0.5,
+        "nested": { "enabled": true, "missing": null }
+    })
+}
+
+#[test]
+fn test_json_serializer_round_trip() {
+    let serializer = JsonSerializer;
+    let bytes = serializer.serialize(&sample()).unwrap();
+    assert_eq!(bytes, serde_json::to_vec(&sample()).unwrap());
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), sample());
+    assert!(serializer.deserialize(b"{not json").is_err());
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack_serializer_round_trip() {
+    let serializer = keyv::MessagePackSerializer;
+    let bytes = serializer.serialize(&sample()).unwrap();
+    assert!(bytes.len() < serde_json::to_vec(&sample()).unwrap().len());
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), sample());
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor_serializer_round_trip() {
+    let serializer = keyv::CborSerializer;
+    let bytes = serializer.serialize(&sample()).unwrap();
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), sample());
+}
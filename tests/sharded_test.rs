@@ -0,0 +1,87 @@
+use futures::TryStreamExt;
+use keyv::{
+    adapter::{inmemory::InMemoryStore, sharded::ShardedStore},
+    BatchOp, Store,
+};
+use serde_json::json;
+
+fn sharded(n: usize) -> ShardedStore {
+    ShardedStore::new(
+        (0..n)
+            .map(|_| Box::new(InMemoryStore::new()) as Box<dyn Store>)
+            .collect(),
+    )
+}
+
+#[tokio::test]
+async fn test_sharded_routing() {
+    let store = sharded(3);
+    for i in 0..30 {
+        let key = format!("key:{i}");
+        store.set(&key, json!(i), None).await.unwrap();
+        let shard = &store.shards()[store.shard_index(&key)];
+        assert_eq!(shard.get(&key).await.unwrap(), Some(json!(i)));
+    }
+    for shard in store.shards() {
+        assert!(!shard.is_empty().await.unwrap());
+    }
+
+    assert_eq!(store.len().await.unwrap(), 30);
+    assert_eq!(store.keys(Some("key:")).await.unwrap().len(), 30);
+    assert_eq!(
+        store.iter().try_collect::<Vec<_>>().await.unwrap().len(),
+        30
+    );
+    assert_eq!(store.get("key:7").await.unwrap(), Some(json!(7)));
+
+    store.clear().await.unwrap();
+    assert!(store.is_empty().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_sharded_multi_key_ops() {
+    let store = sharded(4);
+    let keys: Vec<String> = (0..20).map(|i| format!("k{i}")).collect();
+    let entries: Vec<(&str, _, _)> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key.as_str(), json!(i), None))
+        .collect();
+    store.set_many(&entries).await.unwrap();
+
+    let values = store
+        .get_many(&["k3", "missing", "k15", "k0"])
+        .await
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![Some(json!(3)), None, Some(json!(15)), Some(json!(0))]
+    );
+
+    assert_eq!(
+        store.remove_many(&["k1", "k2", "missing"]).await.unwrap(),
+        2
+    );
+    store
+        .apply_batch(&[BatchOp::set("k1", 100).unwrap(), BatchOp::remove("k4")])
+        .await
+        .unwrap();
+    assert_eq!(store.get("k1").await.unwrap(), Some(json!(100)));
+    assert_eq!(store.get("k4").await.unwrap(), None);
+    assert_eq!(store.len().await.unwrap(), 18);
+}
+
+#[tokio::test]
+async fn test_sharded_rename_across_shards() {
+    let store = sharded(2).with_hasher(|key| u64::from(key.starts_with('b')));
+    store.set("a", json!("value"), Some(60)).await.unwrap();
+
+    assert!(store.rename("a", "b").await.unwrap());
+    assert_eq!(store.shards()[0].get("a").await.unwrap(), None);
+    assert_eq!(
+        store.shards()[1].get("b").await.unwrap(),
+        Some(json!("value"))
+    );
+    assert!(store.get_ttl("b").await.unwrap().is_some());
+    assert!(!store.rename("a", "b").await.unwrap());
+}
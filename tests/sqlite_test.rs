@@ -45,3 +45,1358 @@ async fn test_keyv_sqlite() {
         None => assert!(false, "Expected data not found"),
     }
 }
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_has() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("present", "value").await.unwrap();
+
+    assert!(keyv.has("present").await.unwrap());
+    assert!(!keyv.has("absent").await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_get_many() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("third", 3).await.unwrap();
+
+    let values = keyv.get_many(&["third", "second", "first"]).await.unwrap();
+    assert_eq!(
+        values,
+        vec![Some(serde_json::json!(3)), None, Some(serde_json::json!(1))]
+    );
+
+    // A key asked for twice gets its value in both positions.
+    let values = keyv.get_many(&["first", "third", "first"]).await.unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Some(serde_json::json!(1)),
+            Some(serde_json::json!(3)),
+            Some(serde_json::json!(1))
+        ]
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_many() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("first", 0).await.unwrap();
+    keyv.set_many(&[("first", 1), ("second", 2), ("first", 10)])
+        .await
+        .unwrap();
+
+    let values = keyv.get_many(&["first", "second"]).await.unwrap();
+    assert_eq!(
+        values,
+        vec![Some(serde_json::json!(10)), Some(serde_json::json!(2))]
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_keys() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("session:1", "alice").await.unwrap();
+    keyv.set("session_2", "bob").await.unwrap();
+    keyv.set("cache:1", "page").await.unwrap();
+
+    // `_` must be matched literally rather than as a LIKE wildcard.
+    let sessions = keyv.keys(Some("session_")).await.unwrap();
+    assert_eq!(sessions, vec!["session_2"]);
+
+    assert_eq!(keyv.keys(None).await.unwrap().len(), 3);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_increment() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert_eq!(keyv.increment("counter", 5).await.unwrap(), 5);
+    assert_eq!(keyv.increment("counter", -2).await.unwrap(), 3);
+
+    keyv.set("name", "not a number").await.unwrap();
+    assert!(matches!(
+        keyv.increment("name", 1).await,
+        Err(keyv::KeyvError::StoreError(keyv::StoreError::NotAnInteger(
+            _
+        )))
+    ));
+
+    keyv.set("max", i64::MAX).await.unwrap();
+    assert!(keyv.increment("max", 1).await.is_err());
+    assert_eq!(keyv.get_as::<i64>("max").await.unwrap(), Some(i64::MAX));
+    assert_eq!(keyv.increment("max", -1).await.unwrap(), i64::MAX - 1);
+
+    keyv.set_with_ttl("expired", 10, 1).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+    assert_eq!(keyv.increment("expired", 1).await.unwrap(), 1);
+    assert_eq!(keyv.ttl("expired").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_keyv_sqlite_concurrent_increment() {
+    let path = std::env::temp_dir().join(format!("keyv_increment_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = SqliteStoreBuilder::new()
+        .uri(format!("sqlite://{}?mode=rwc", path.display()))
+        .table_name("counters")
+        .busy_timeout(std::time::Duration::from_secs(5))
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                for _ in 0..25 {
+                    keyv.increment("hits", 1).await.unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(keyv.get_as::<i64>("hits").await.unwrap(), Some(200));
+    drop(keyv);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_keyv_sqlite_take_commits() {
+    let path = std::env::temp_dir().join(format!("keyv_take_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = SqliteStoreBuilder::new()
+        .uri(format!("sqlite://{}?mode=rwc", path.display()))
+        .table_name("jobs")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    for i in 0..20 {
+        let key = format!("job:{}", i);
+        keyv.set(&key, i).await.unwrap();
+        assert_eq!(keyv.take(&key).await.unwrap(), Some(serde_json::json!(i)));
+        // Read back on whichever pooled connection comes next.
+        assert!(!keyv.has(&key).await.unwrap());
+    }
+    drop(keyv);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_take() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("job", "payload").await.unwrap();
+
+    assert_eq!(
+        keyv.take("job").await.unwrap(),
+        Some(serde_json::json!("payload"))
+    );
+    assert_eq!(keyv.take("job").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_compare_and_swap() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    assert!(!keyv.compare_and_swap("lock", Some("a"), "b").await.unwrap());
+    assert!(keyv.compare_and_swap("lock", None, "a").await.unwrap());
+    assert!(!keyv.compare_and_swap("lock", None, "b").await.unwrap());
+    assert!(!keyv.compare_and_swap("lock", Some("x"), "b").await.unwrap());
+    assert!(keyv.compare_and_swap("lock", Some("a"), "b").await.unwrap());
+    assert_eq!(
+        keyv.get("lock").await.unwrap(),
+        Some(serde_json::json!("b"))
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_len() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("second", 2).await.unwrap();
+    assert_eq!(keyv.len().await.unwrap(), 2);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_remove_many_count() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("first", 1).await.unwrap();
+    keyv.set("second", 2).await.unwrap();
+
+    let removed = keyv
+        .remove_many(&["first", "second", "third"])
+        .await
+        .unwrap();
+    assert_eq!(removed, 2);
+    assert_eq!(keyv.remove_many::<&str>(&[]).await.unwrap(), 0);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_journal_mode() {
+    use keyv::adapter::sqlite::{SqliteJournalMode, SqlitePoolOptions};
+
+    let path = std::env::temp_dir().join(format!("keyv_journal_{}.db", std::process::id()));
+    let uri = format!("sqlite://{}?mode=rwc", path.display());
+
+    let store = SqliteStoreBuilder::new()
+        .uri(&uri)
+        .table_name("cache")
+        .busy_timeout(std::time::Duration::from_secs(1))
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("key", "value").await.unwrap();
+
+    // WAL is persisted in the database file, so a fresh connection reports it.
+    let pool = SqlitePoolOptions::new().connect(&uri).await.unwrap();
+    let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(mode, "wal");
+    pool.close().await;
+    drop(keyv);
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .journal_mode(SqliteJournalMode::Memory)
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("key", "value").await.unwrap();
+    assert!(keyv.has("key").await.unwrap());
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_expire() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("key", "value").await.unwrap();
+
+    assert!(keyv.expire("key", None).await.unwrap());
+    assert_eq!(
+        keyv.get_as::<String>("key").await.unwrap(),
+        Some("value".to_string())
+    );
+    assert!(!keyv.expire("missing", Some(60)).await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_huge_ttl_does_not_wrap() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_with_ttl("forever", "data", u64::MAX)
+        .await
+        .unwrap();
+    assert!(keyv.has("forever").await.unwrap());
+
+    keyv.set("later", "data").await.unwrap();
+    assert!(keyv.expire("later", Some(u64::MAX)).await.unwrap());
+    assert!(keyv.has("later").await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_connection_error_keeps_cause() {
+    let result = SqliteStoreBuilder::new()
+        .uri("sqlite:///keyv/missing/directory/db.sqlite")
+        .build()
+        .await;
+    match result {
+        Err(keyv::StoreError::ConnectionError(message)) => {
+            assert!(message.starts_with("Failed to connect to the database: "));
+            assert!(
+                message.contains("unable to open database file"),
+                "{}",
+                message
+            );
+        }
+        _ => panic!("expected a connection error"),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_extend_ttl() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_with_ttl("session", "data", 60).await.unwrap();
+    keyv.set("permanent", "data").await.unwrap();
+
+    assert!(keyv.extend_ttl("session", 60).await.unwrap());
+    let ttl = keyv.ttl("session").await.unwrap().unwrap();
+    assert!(ttl > 60 && ttl <= 120);
+
+    // Capped instead of overflowing.
+    assert!(keyv.extend_ttl("session", u64::MAX).await.unwrap());
+    assert!(keyv.has("session").await.unwrap());
+
+    assert!(keyv.extend_ttl("permanent", 60).await.unwrap());
+    assert_eq!(keyv.ttl("permanent").await.unwrap(), None);
+
+    assert!(!keyv.extend_ttl("missing", 60).await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_if_absent() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert!(keyv.set_if_absent("lock", "first", None).await.unwrap());
+    assert!(!keyv.set_if_absent("lock", "second", None).await.unwrap());
+    assert_eq!(
+        keyv.get_as::<String>("lock").await.unwrap(),
+        Some("first".to_string())
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_iter() {
+    use futures::TryStreamExt;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    // Enough entries to span several batches.
+    let entries: Vec<(String, i64)> = (0..1200).map(|i| (format!("key:{:04}", i), i)).collect();
+    keyv.set_many(&entries).await.unwrap();
+
+    let streamed: Vec<(String, serde_json::Value)> = keyv.iter().try_collect().await.unwrap();
+    assert_eq!(streamed.len(), 1200);
+    for ((key, value), (expected_key, expected)) in streamed.iter().zip(&entries) {
+        assert_eq!(key, expected_key);
+        assert_eq!(value.as_i64(), Some(*expected));
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_scan_prefix() {
+    use futures::TryStreamExt;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    let users: Vec<(String, i64)> = (0..700).map(|i| (format!("user:{:04}", i), i)).collect();
+    keyv.set_many(&users).await.unwrap();
+    keyv.set("order:1", 1).await.unwrap();
+    // `_` must match literally rather than as a LIKE wildcard.
+    keyv.set("user_1", 1).await.unwrap();
+
+    let streamed: Vec<(String, serde_json::Value)> =
+        keyv.scan_prefix("user:").try_collect().await.unwrap();
+    assert_eq!(streamed.len(), 700);
+    for ((key, value), (expected_key, expected)) in streamed.iter().zip(&users) {
+        assert_eq!(key, expected_key);
+        assert_eq!(value.as_i64(), Some(*expected));
+    }
+
+    let underscored: Vec<(String, serde_json::Value)> =
+        keyv.scan_prefix("user_").try_collect().await.unwrap();
+    assert_eq!(underscored.len(), 1);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_fields() {
+    use serde_json::json;
+    use std::sync::Arc;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Arc::new(Keyv::try_new(store).await.unwrap());
+    let tasks: Vec<_> = (0..10)
+        .map(|i| {
+            let keyv = keyv.clone();
+            tokio::spawn(async move {
+                keyv.set_field("record", &format!("field{}", i), i)
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let record = keyv.get("record").await.unwrap().unwrap();
+    assert_eq!(record.as_object().unwrap().len(), 10);
+    assert_eq!(
+        keyv.get_field("record", "field3").await.unwrap(),
+        Some(json!(3))
+    );
+    assert!(keyv.remove_field("record", "field3").await.unwrap());
+    assert_eq!(keyv.get_field("record", "field3").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_ping() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.ping().await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_custom_columns() {
+    use keyv::adapter::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    // A single connection, so the store and this test share the same in-memory database.
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .key_column("cache \"key\"")
+        .value_column("select")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("a", 1).await.unwrap();
+    keyv.set_many(&[("b", 2), ("c", 3)]).await.unwrap();
+    assert_eq!(keyv.get_as::<i64>("a").await.unwrap(), Some(1));
+    assert_eq!(keyv.increment("c", 4).await.unwrap(), 7);
+    assert_eq!(keyv.keys(Some("b")).await.unwrap(), vec!["b".to_string()]);
+
+    let (value,): (String,) =
+        sqlx::query_as(r#"SELECT "select" FROM cache WHERE "cache ""key""" = 'b'"#)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(value, "2");
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_apply_batch_rolls_back() {
+    use keyv::{adapter::sqlite::SqlitePoolOptions, BatchOp};
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("a", 1).await.unwrap();
+    keyv.apply_batch(&[BatchOp::set("b", 2).unwrap(), BatchOp::remove("a")])
+        .await
+        .unwrap();
+    assert_eq!(keyv.get_as::<i64>("a").await.unwrap(), None);
+    assert_eq!(keyv.get_as::<i64>("b").await.unwrap(), Some(2));
+
+    // Make the last write of the next batch fail, after the others already ran.
+    sqlx::query(
+        "CREATE TRIGGER reject_poison BEFORE INSERT ON cache WHEN NEW.key = 'poison' \
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+    )
+    .execute(&*pool)
+    .await
+    .unwrap();
+
+    let result = keyv
+        .apply_batch(&[
+            BatchOp::set("c", 3).unwrap(),
+            BatchOp::remove("b"),
+            BatchOp::set("poison", 0).unwrap(),
+        ])
+        .await;
+    assert!(result.is_err());
+    assert_eq!(keyv.get_as::<i64>("b").await.unwrap(), Some(2));
+    assert_eq!(keyv.get_as::<i64>("c").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_group_rolls_back() {
+    use keyv::adapter::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_group(&[("a", 1), ("b", 2)], Some(60))
+        .await
+        .unwrap();
+    assert_eq!(keyv.get_as::<i64>("a").await.unwrap(), Some(1));
+    assert_eq!(keyv.get_as::<i64>("b").await.unwrap(), Some(2));
+
+    sqlx::query(
+        "CREATE TRIGGER reject_poison BEFORE INSERT ON cache WHEN NEW.key = 'poison' \
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+    )
+    .execute(&*pool)
+    .await
+    .unwrap();
+
+    let result = keyv
+        .set_group(&[("a", 10), ("c", 3), ("poison", 0)], Some(60))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(keyv.get_as::<i64>("a").await.unwrap(), Some(1));
+    assert_eq!(keyv.get_as::<i64>("c").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_clear_prefix() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_many(&[("user_1", 1), ("user_2", 2), ("userX", 3), ("cache", 4)])
+        .await
+        .unwrap();
+
+    // `_` is matched literally, not as a LIKE wildcard.
+    assert_eq!(keyv.clear_prefix("user_").await.unwrap(), 2);
+    let mut keys = keyv.keys(None).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["cache".to_string(), "userX".to_string()]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_clear_prefix_is_case_sensitive() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_many(&[("acme:user", 1), ("ACME:user", 2), ("a*me:user", 3)])
+        .await
+        .unwrap();
+
+    assert_eq!(keyv.clear_prefix("acme:").await.unwrap(), 1);
+    assert_eq!(keyv.get_as::<i64>("ACME:user").await.unwrap(), Some(2));
+    // GLOB wildcards in the prefix are matched literally.
+    assert_eq!(keyv.clear_prefix("a*").await.unwrap(), 1);
+    assert_eq!(
+        keyv.keys(None).await.unwrap(),
+        vec!["ACME:user".to_string()]
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_keys_prefix_is_case_sensitive() {
+    use futures::TryStreamExt;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_many(&[("user:1", 1), ("USER:2", 2), ("User:3", 3)])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        keyv.keys(Some("User:")).await.unwrap(),
+        vec!["User:3".to_string()]
+    );
+    let scanned: Vec<(String, serde_json::Value)> =
+        keyv.scan_prefix("user:").try_collect().await.unwrap();
+    assert_eq!(scanned, vec![("user:1".to_string(), serde_json::json!(1))]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_namespace_clear() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    let acme = keyv.namespace("acme");
+    let globex = keyv.namespace("globex");
+    let upper = keyv.namespace("ACME");
+    acme.set("user", "alice").await.unwrap();
+    globex.set("user", "bob").await.unwrap();
+    upper.set("user", "carol").await.unwrap();
+    keyv.set("acme", "not in the namespace").await.unwrap();
+
+    acme.clear().await.unwrap();
+    assert!(!acme.has("user").await.unwrap());
+    assert_eq!(
+        globex.get_as::<String>("user").await.unwrap(),
+        Some("bob".to_string())
+    );
+    // Namespaces differing only in case are kept apart.
+    assert_eq!(
+        upper.get_as::<String>("user").await.unwrap(),
+        Some("carol".to_string())
+    );
+    assert!(keyv.has("acme").await.unwrap());
+
+    keyv.clear().await.unwrap();
+    assert!(keyv.is_empty().await.unwrap());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_range() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_many(&[("event:0300", 3), ("event:0100", 1), ("event:0200", 2)])
+        .await
+        .unwrap();
+    keyv.set_with_ttl("event:0150", 0, 1).await.unwrap();
+    keyv.set("event:0400", 4).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+
+    let entries = keyv.range("event:0100", "event:0400").await.unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            ("event:0100".to_string(), serde_json::json!(1)),
+            ("event:0200".to_string(), serde_json::json!(2)),
+            ("event:0300".to_string(), serde_json::json!(3)),
+        ]
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_u64_keys() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    for id in [100, 10, 9] {
+        keyv.set_u64(id, id).await.unwrap();
+    }
+    keyv.set("name", "not an id").await.unwrap();
+
+    let ids: Vec<u64> = keyv
+        .range_u64(9, 101)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, vec![9, 10, 100]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_rename() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("draft:1", "hello").await.unwrap();
+    keyv.set("published:1", "stale").await.unwrap();
+
+    assert!(keyv.rename("draft:1", "published:1").await.unwrap());
+    assert_eq!(keyv.get("draft:1").await.unwrap(), None);
+    assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+    assert!(!keyv.rename("missing", "published:1").await.unwrap());
+    assert_eq!(keyv.get("published:1").await.unwrap().unwrap(), "hello");
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_swap() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert_eq!(keyv.swap("config", "v1").await.unwrap(), None);
+    assert_eq!(keyv.swap("config", "v2").await.unwrap().unwrap(), "v1");
+    assert_eq!(keyv.get("config").await.unwrap().unwrap(), "v2");
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_timestamps() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .timestamps(true)
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("config", "v1").await.unwrap();
+    let (value, first) = keyv.get_with_meta("config").await.unwrap().unwrap();
+    assert_eq!(value, "v1");
+    assert!(first.created_at.is_some());
+    assert_eq!(first.created_at, first.updated_at);
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    keyv.set("config", "v2").await.unwrap();
+    let (value, second) = keyv.get_with_meta("config").await.unwrap().unwrap();
+    assert_eq!(value, "v2");
+    assert_eq!(second.created_at, first.created_at);
+    assert!(second.updated_at > first.updated_at);
+
+    assert_eq!(keyv.get_with_meta("missing").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_bytes() {
+    use futures::TryStreamExt;
+    use keyv::{adapter::sqlite::SqlitePoolOptions, DecodePolicy, KeyvError, StoreError};
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    let bytes = vec![0u8, 159, 146, 150, 255];
+    keyv.set_bytes("blob", &bytes).await.unwrap();
+    assert_eq!(keyv.get_bytes("blob").await.unwrap(), Some(bytes));
+    assert_eq!(keyv.get_bytes("missing").await.unwrap(), None);
+
+    let (stored_type,): (String,) =
+        sqlx::query_as("SELECT typeof(value) FROM cache WHERE key = 'blob'")
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(stored_type, "blob");
+
+    // The BLOB row fails to decode on its own instead of failing every read of the table.
+    keyv.set("name", "alice").await.unwrap();
+    assert!(matches!(
+        keyv.get("blob").await,
+        Err(KeyvError::StoreError(StoreError::DeserializationError { key: Some(key), .. }))
+            if key == "blob"
+    ));
+    assert_eq!(
+        keyv.get_as::<String>("name").await.unwrap(),
+        Some("alice".to_string())
+    );
+    let keyv = keyv.with_decode_policy(DecodePolicy::Skip);
+    let entries: Vec<_> = keyv.iter().try_collect().await.unwrap();
+    assert_eq!(
+        entries,
+        vec![("name".to_string(), serde_json::json!("alice"))]
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_optimize() {
+    use keyv::adapter::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    let entries: Vec<(String, String)> = (0..500)
+        .map(|i| (format!("key:{}", i), "x".repeat(200)))
+        .collect();
+    keyv.set_many(&entries).await.unwrap();
+    keyv.clear().await.unwrap();
+
+    let free_pages = || async {
+        sqlx::query_scalar::<_, i64>("PRAGMA freelist_count")
+            .fetch_one(&*pool)
+            .await
+            .unwrap()
+    };
+    assert!(free_pages().await > 0);
+    keyv.optimize().await.unwrap();
+    assert_eq!(free_pages().await, 0);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_close() {
+    use keyv::Store;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    store.set("key", "value".into(), None).await.unwrap();
+
+    store.close().await.unwrap();
+    store.close().await.unwrap();
+    assert!(store.get("key").await.is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_large_integers() {
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("max", u64::MAX).await.unwrap();
+    keyv.set("min", i64::MIN).await.unwrap();
+    assert_eq!(keyv.get_as::<u64>("max").await.unwrap(), Some(u64::MAX));
+    assert_eq!(keyv.get_as::<i64>("min").await.unwrap(), Some(i64::MIN));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_decode_policy() {
+    use futures::TryStreamExt;
+    use keyv::{adapter::sqlite::SqlitePoolOptions, DecodePolicy, KeyvError, StoreError};
+    use std::sync::{Arc, Mutex};
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_many(&[("a", 1), ("b", 2)]).await.unwrap();
+    keyv.set("name", "alice").await.unwrap();
+    sqlx::query("INSERT INTO cache (key, value) VALUES ('legacy', 'not json')")
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        keyv.iter().try_collect::<Vec<_>>().await,
+        Err(KeyvError::StoreError(StoreError::DeserializationError { key: Some(key), .. }))
+            if key == "legacy"
+    ));
+    assert!(matches!(
+        keyv.get_many_as::<i64, _>(&["a", "name"]).await,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { .. }
+        ))
+    ));
+
+    let skipped = Arc::new(Mutex::new(Vec::new()));
+    let report = skipped.clone();
+    let keyv = keyv.with_decode_policy(DecodePolicy::SkipWith(Arc::new(move |e| {
+        if let StoreError::DeserializationError { key: Some(key), .. } = e {
+            report.lock().unwrap().push(key.clone());
+        }
+    })));
+
+    let entries: Vec<_> = keyv.iter().try_collect().await.unwrap();
+    assert_eq!(entries.len(), 3);
+    let numbers = keyv
+        .get_many_as::<i64, _>(&["a", "b", "name"])
+        .await
+        .unwrap();
+    assert_eq!(numbers.len(), 2);
+    assert_eq!(*skipped.lock().unwrap(), vec!["legacy", "name"]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_field_on_corrupt_value() {
+    use keyv::{adapter::sqlite::SqlitePoolOptions, DecodePolicy, KeyvError, StoreError};
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    sqlx::query("INSERT INTO cache (key, value) VALUES ('legacy', 'not json')")
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        keyv.get("legacy").await,
+        Err(KeyvError::StoreError(StoreError::DeserializationError { key: Some(key), .. }))
+            if key == "legacy"
+    ));
+    keyv.set("fresh", 1).await.unwrap();
+    assert!(matches!(
+        keyv.get_many(&["fresh", "legacy"]).await,
+        Err(KeyvError::StoreError(StoreError::DeserializationError { key: Some(key), .. }))
+            if key == "legacy"
+    ));
+    let skipping = keyv.clone().with_decode_policy(DecodePolicy::Skip);
+    assert_eq!(
+        skipping.get_many(&["fresh", "legacy"]).await.unwrap(),
+        vec![Some(1.into()), None]
+    );
+    // The row is kept when its value cannot be returned.
+    assert!(matches!(
+        keyv.take("legacy").await,
+        Err(KeyvError::StoreError(StoreError::DeserializationError { key: Some(key), .. }))
+            if key == "legacy"
+    ));
+    assert!(keyv.has("legacy").await.unwrap());
+    // Fails instead of retrying a swap against a row it cannot read.
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(3),
+        keyv.set_field("legacy", "name", "alice"),
+    )
+    .await
+    .expect("set_field never settled");
+    assert!(matches!(
+        result,
+        Err(KeyvError::StoreError(
+            StoreError::DeserializationError { .. }
+        ))
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_fresh_database_with_ttl() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("kept", "value").await.unwrap();
+    keyv.set_with_ttl("short", "value", 1).await.unwrap();
+    assert!(keyv.ttl("short").await.unwrap().is_some());
+    assert_eq!(keyv.ttl("kept").await.unwrap(), None);
+    assert_eq!(keyv.len().await.unwrap(), 2);
+
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+    assert_eq!(keyv.get("short").await.unwrap(), None);
+    assert_eq!(keyv.keys(None).await.unwrap(), vec!["kept".to_string()]);
+    assert!(keyv.set_if_absent("short", "again", None).await.unwrap());
+    assert_eq!(
+        keyv.get_as::<String>("short").await.unwrap(),
+        Some("again".to_string())
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_adds_ttl_column() {
+    use keyv::{adapter::sqlite::SqlitePoolOptions, Store};
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    sqlx::query("CREATE TABLE cache (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+        .execute(&*pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO cache (key, value) VALUES ('old', '1')")
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    store.initialize().await.unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    assert_eq!(keyv.get_as::<i64>("old").await.unwrap(), Some(1));
+    keyv.set_with_ttl("new", 2, 60).await.unwrap();
+    assert!(keyv.ttl("new").await.unwrap().is_some());
+
+    let indexes: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM pragma_index_list('cache') WHERE origin = 'c'")
+            .fetch_all(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(indexes, vec!["cache_ttl_idx".to_string()]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_without_auto_create() {
+    use keyv::{adapter::sqlite::SqlitePoolOptions, StoreError};
+    use std::sync::Arc;
+
+    let pool = Arc::new(
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    let builder = || {
+        SqliteStoreBuilder::new()
+            .pool(pool.clone())
+            .table_name("cache")
+            .auto_create(false)
+    };
+
+    let result = Keyv::try_new(builder().build().await.unwrap()).await;
+    assert!(matches!(
+        result,
+        Err(keyv::KeyvError::StoreError(StoreError::QueryError(message))) if message.contains("does not exist")
+    ));
+
+    sqlx::query("CREATE TABLE cache (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+        .execute(&*pool)
+        .await
+        .unwrap();
+    let result = Keyv::try_new(builder().build().await.unwrap()).await;
+    assert!(matches!(
+        result,
+        Err(keyv::KeyvError::StoreError(StoreError::QueryError(message))) if message.contains("ttl")
+    ));
+
+    sqlx::query("ALTER TABLE cache ADD COLUMN ttl INTEGER")
+        .execute(&*pool)
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(builder().build().await.unwrap())
+        .await
+        .unwrap();
+    keyv.set("key", "value").await.unwrap();
+    assert_eq!(
+        keyv.get_as::<String>("key").await.unwrap(),
+        Some("value".to_string())
+    );
+
+    // Nothing was created behind the user's back, not even the ttl index.
+    let indexes: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM pragma_index_list('cache') WHERE origin = 'c'")
+            .fetch_all(&*pool)
+            .await
+            .unwrap();
+    assert!(indexes.is_empty());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_field_after_set_json_path() {
+    use serde_json::json;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("doc", json!({"b": 1})).await.unwrap();
+    assert!(keyv.set_json_path("doc", "$.a", 2).await.unwrap());
+
+    // The value read back must still compare equal to what is stored.
+    let current = keyv.get("doc").await.unwrap().unwrap();
+    assert!(keyv
+        .compare_and_swap("doc", Some(current.clone()), current)
+        .await
+        .unwrap());
+    tokio::time::timeout(
+        std::time::Duration::from_secs(3),
+        keyv.set_field("doc", "c", 3),
+    )
+    .await
+    .expect("set_field never settled")
+    .unwrap();
+    assert_eq!(
+        keyv.get("doc").await.unwrap().unwrap(),
+        json!({"a": 2, "b": 1, "c": 3})
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_keyv_sqlite_set_json_path() {
+    use serde_json::json;
+
+    let store = SqliteStoreBuilder::new()
+        .in_memory()
+        .table_name("cache")
+        .build()
+        .await
+        .unwrap();
+
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_with_ttl(
+        "user",
+        json!({"name": "alice", "address": {"city": "Paris"}, "tags": ["a", "b"], "0": 0}),
+        3600,
+    )
+    .await
+    .unwrap();
+
+    assert!(keyv
+        .set_json_path("user", "$.address.city", "Lyon")
+        .await
+        .unwrap());
+    assert!(keyv
+        .set_json_path("user", "$.address.geo", json!({"lat": 45.76}))
+        .await
+        .unwrap());
+    assert!(keyv.set_json_path("user", "$.tags[1]", "c").await.unwrap());
+    assert!(keyv.set_json_path("user", "$.0", 1).await.unwrap());
+    assert!(!keyv.set_json_path("user", "$.tags[2]", "d").await.unwrap());
+    assert!(!keyv
+        .set_json_path("user", "$.name.first", "a")
+        .await
+        .unwrap());
+    assert!(!keyv
+        .set_json_path("missing", "$.name", "bob")
+        .await
+        .unwrap());
+    assert_eq!(
+        keyv.get("user").await.unwrap().unwrap(),
+        json!({
+            "name": "alice",
+            "address": {"city": "Lyon", "geo": {"lat": 45.76}},
+            "tags": ["a", "c"],
+            "0": 1
+        })
+    );
+    assert!(keyv.ttl("user").await.unwrap().is_some());
+}
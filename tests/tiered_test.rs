@@ -0,0 +1,73 @@
+use keyv::{
+    adapter::{inmemory::InMemoryStore, tiered::TieredStore},
+    Keyv, Store,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_tiered_read_through() {
+    let far = InMemoryStore::new();
+    far.set("number", json!(42), None).await.unwrap();
+
+    let store = TieredStore::new(Box::new(InMemoryStore::new()), Box::new(far));
+    assert_eq!(store.near().get("number").await.unwrap(), None);
+
+    assert_eq!(store.get("number").await.unwrap(), Some(json!(42)));
+    assert_eq!(store.near().get("number").await.unwrap(), Some(json!(42)));
+}
+
+#[tokio::test]
+async fn test_tiered_write_through_and_remove() {
+    let store = TieredStore::new(
+        Box::new(InMemoryStore::new()),
+        Box::new(InMemoryStore::new()),
+    );
+
+    store.set("key", json!("value"), None).await.unwrap();
+    assert_eq!(store.near().get("key").await.unwrap(), Some(json!("value")));
+    assert_eq!(store.far().get("key").await.unwrap(), Some(json!("value")));
+
+    store.remove("key").await.unwrap();
+    assert_eq!(store.near().get("key").await.unwrap(), None);
+    assert_eq!(store.far().get("key").await.unwrap(), None);
+
+    store.set("a", json!(1), None).await.unwrap();
+    store.clear().await.unwrap();
+    assert!(store.near().is_empty().await.unwrap());
+    assert!(store.far().is_empty().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_tiered_with_keyv() {
+    let store = TieredStore::new(
+        Box::new(InMemoryStore::new()),
+        Box::new(InMemoryStore::new()),
+    );
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("counter", 1).await.unwrap();
+    assert_eq!(keyv.increment("counter", 2).await.unwrap(), 3);
+    assert_eq!(keyv.get_as::<i64>("counter").await.unwrap(), Some(3));
+
+    assert!(keyv.compare_and_swap("counter", Some(3), 4).await.unwrap());
+    assert_eq!(keyv.get_as::<i64>("counter").await.unwrap(), Some(4));
+    assert_eq!(keyv.len().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_tiered_read_through_keeps_far_ttl() {
+    let far = InMemoryStore::new();
+    far.set("session", json!("data"), Some(60)).await.unwrap();
+    far.set("forever", json!("data"), None).await.unwrap();
+
+    let store = TieredStore::new(Box::new(InMemoryStore::new()), Box::new(far))
+        .with_near_ttl(std::time::Duration::from_secs(3600));
+    store.get("session").await.unwrap();
+    store.get("forever").await.unwrap();
+
+    // The copy expires with the far entry, not after the longer near TTL.
+    let ttl = store.near().get_ttl("session").await.unwrap().unwrap();
+    assert!(ttl > 0 && ttl <= 60);
+    let ttl = store.near().get_ttl("forever").await.unwrap().unwrap();
+    assert!(ttl > 60 && ttl <= 3600);
+}
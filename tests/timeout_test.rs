@@ -0,0 +1,118 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use keyv::{
+    adapter::{inmemory::InMemoryStore, timeout::TimeoutStore},
+    Keyv, KeyvError, Store, StoreError,
+};
+use serde_json::{json, Value};
+
+/// Delays every read by a fixed amount before delegating to an in-memory store.
+struct SlowStore {
+    inner: InMemoryStore,
+    delay: Duration,
+}
+
+#[async_trait]
+impl Store for SlowStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        tokio::time::sleep(self.delay).await;
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_timeout_store() {
+    let slow = Arc::new(SlowStore {
+        inner: InMemoryStore::new(),
+        delay: Duration::from_millis(200),
+    });
+    slow.set("number", json!(42), None).await.unwrap();
+
+    let store = TimeoutStore::new(slow.clone(), Duration::from_millis(50));
+    assert!(matches!(
+        store.get("number").await,
+        Err(StoreError::Timeout(timeout)) if timeout == Duration::from_millis(50)
+    ));
+    store.set("other", json!(1), None).await.unwrap();
+
+    // Field updates read the object first, which is slow.
+    assert!(matches!(
+        store.set_field("user", "name", json!("alice"), None).await,
+        Err(StoreError::Timeout(_))
+    ));
+    assert!(matches!(
+        store.get_field("number", "name").await,
+        Err(StoreError::Timeout(_))
+    ));
+
+    let store = TimeoutStore::new(slow, Duration::from_secs(5));
+    assert_eq!(store.get("number").await.unwrap(), Some(json!(42)));
+}
+
+#[tokio::test]
+async fn test_timeout_store_import() {
+    let store = TimeoutStore::new(Arc::new(InMemoryStore::new()), Duration::from_millis(100));
+    let entries = |delay: Duration| {
+        Box::pin(stream::iter(0..4).then(move |i| async move {
+            tokio::time::sleep(delay).await;
+            Ok((format!("key{}", i), json!(i), None))
+        }))
+    };
+
+    // Taking longer than the limit in total is fine as long as entries keep coming.
+    assert_eq!(
+        store
+            .import(entries(Duration::from_millis(40)))
+            .await
+            .unwrap(),
+        4
+    );
+    assert!(matches!(
+        store.import(entries(Duration::from_millis(300))).await,
+        Err(StoreError::Timeout(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_with_timeout() {
+    let slow = SlowStore {
+        inner: InMemoryStore::new(),
+        delay: Duration::from_millis(200),
+    };
+    let keyv = Keyv::try_new(slow)
+        .await
+        .unwrap()
+        .with_timeout(Duration::from_millis(50));
+
+    keyv.set("number", 42).await.unwrap();
+    assert!(matches!(
+        keyv.get("number").await,
+        Err(KeyvError::StoreError(StoreError::Timeout(_)))
+    ));
+}
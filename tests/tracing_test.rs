@@ -0,0 +1,52 @@
+#[cfg(feature = "tracing")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "tracing")]
+use keyv::Keyv;
+#[cfg(feature = "tracing")]
+use tracing::{
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+/// Records the name of every span that is created.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+struct SpanNames(Arc<Mutex<Vec<String>>>);
+
+#[cfg(feature = "tracing")]
+impl Subscriber for SpanNames {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.0.lock().unwrap();
+        names.push(span.metadata().name().to_string());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn test_keyv_tracing_spans() {
+    let names = SpanNames::default();
+    let _guard = tracing::subscriber::set_default(names.clone());
+
+    let keyv = Keyv::default();
+    keyv.set("number", 42).await.unwrap();
+    keyv.get("number").await.unwrap();
+    keyv.remove("number").await.unwrap();
+
+    assert_eq!(*names.0.lock().unwrap(), vec!["set", "get", "remove"]);
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use keyv::{
+    adapter::{inmemory::InMemoryStore, verified::VerifiedStore},
+    BatchOp, Keyv, KeyvError, Store, StoreError,
+};
+use serde_json::{json, Value};
+
+/// Stores numbers as `f64`, losing the precision of large integers like some JSON paths do.
+struct LossyStore {
+    inner: InMemoryStore,
+}
+
+/// Turns every number inside `value` into an `f64`.
+fn lossy(value: Value) -> Value {
+    match value {
+        Value::Number(number) => json!(number.as_f64()),
+        Value::Array(values) => Value::Array(values.into_iter().map(lossy).collect()),
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(name, value)| (name, lossy(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[async_trait]
+impl Store for LossyStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+        self.inner.set(key, lossy(value), ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<u64, StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn keys(&self, prefix: Option<&str>) -> Result<Vec<String>, StoreError> {
+        self.inner.keys(prefix).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_verified_store() {
+    let store = VerifiedStore::new(Arc::new(LossyStore {
+        inner: InMemoryStore::new(),
+    }));
+
+    store.set("name", json!("alice"), None).await.unwrap();
+    store.set("small", json!(42.5), None).await.unwrap();
+    assert!(matches!(
+        store.set("id", json!(u64::MAX), None).await,
+        Err(StoreError::IntegrityError(key)) if key == "id"
+    ));
+    assert!(matches!(
+        store
+            .set_many(&[("a", json!(1.5), None), ("b", json!(i64::MIN + 1), None)])
+            .await,
+        Err(StoreError::IntegrityError(key)) if key == "b"
+    ));
+    assert!(matches!(
+        store.swap("name", json!(u64::MAX - 1), None).await,
+        Err(StoreError::IntegrityError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_verified_store_apply_batch() {
+    let store = VerifiedStore::new(Arc::new(InMemoryStore::new()));
+    store
+        .apply_batch(&[
+            BatchOp::Set {
+                key: "a".to_string(),
+                value: json!(1),
+                ttl: None,
+            },
+            BatchOp::Remove {
+                key: "a".to_string(),
+            },
+            BatchOp::Set {
+                key: "b".to_string(),
+                value: json!(1),
+                ttl: None,
+            },
+            BatchOp::Set {
+                key: "b".to_string(),
+                value: json!(2),
+                ttl: None,
+            },
+        ])
+        .await
+        .unwrap();
+    assert_eq!(store.get("b").await.unwrap(), Some(json!(2)));
+}
+
+#[tokio::test]
+async fn test_verified_store_partial_updates() {
+    let store = VerifiedStore::new(Arc::new(LossyStore {
+        inner: InMemoryStore::new(),
+    }));
+
+    store
+        .set_field("user", "name", json!("alice"), None)
+        .await
+        .unwrap();
+    assert!(store
+        .set_json_path("user", "$.name", json!("bob"))
+        .await
+        .unwrap());
+    assert!(store.remove_field("user", "name").await.unwrap());
+    assert_eq!(store.get("user").await.unwrap(), Some(json!({})));
+    assert!(matches!(
+        store.set_field("user", "id", json!(u64::MAX), None).await,
+        Err(StoreError::IntegrityError(key)) if key == "user"
+    ));
+    assert!(matches!(
+        store.set_json_path("user", "$.id", json!(u64::MAX - 1)).await,
+        Err(StoreError::IntegrityError(key)) if key == "user"
+    ));
+
+    store
+        .set_group(&[("a", json!(1.5)), ("b", json!("two"))], None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        store
+            .set_group(&[("a", json!(1.5)), ("c", json!(i64::MIN + 1))], None)
+            .await,
+        Err(StoreError::IntegrityError(key)) if key == "c"
+    ));
+}
+
+#[tokio::test]
+async fn test_keyv_with_verify_writes() {
+    let keyv = Keyv::try_new(LossyStore {
+        inner: InMemoryStore::new(),
+    })
+    .await
+    .unwrap()
+    .with_verify_writes(true);
+    assert!(matches!(
+        keyv.set("id", u64::MAX).await,
+        Err(KeyvError::StoreError(StoreError::IntegrityError(_)))
+    ));
+
+    let keyv = Keyv::try_new(LossyStore {
+        inner: InMemoryStore::new(),
+    })
+    .await
+    .unwrap()
+    .with_verify_writes(false);
+    keyv.set("id", u64::MAX).await.unwrap();
+}